@@ -9,6 +9,8 @@ use clap_complete::{generate_to, Shell};
 mod cli;
 use cli::Cli;
 
+const FISH_SHELL_COMPLETION: &str = "complete -c gwt -n \"__fish_gwt_using_subcommand completions; and __fish_seen_subcommand_from generate install\" -f -a \"(gwt completions shells 2>/dev/null)\"\n";
+
 fn main() -> std::io::Result<()> {
     let outdir = env::var_os("OUT_DIR").expect("OUT_DIR not set");
     let completions_dir = Path::new(&outdir).join("completions");
@@ -20,6 +22,13 @@ fn main() -> std::io::Result<()> {
     for shell in Shell::value_variants() {
         let path = generate_to(*shell, &mut cmd, "gwt", &completions_dir)?;
 
+        // clap_complete's fish output doesn't complete positional values, so ask gwt for the shells
+        if *shell == Shell::Fish {
+            let mut content = fs::read_to_string(&path)?;
+            content.push_str(FISH_SHELL_COMPLETION);
+            fs::write(&path, content)?;
+        }
+
         println!("Generated {} completions: {:?}", shell, path);
     }
 
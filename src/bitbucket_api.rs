@@ -1,9 +1,22 @@
 use anyhow::{Context, Result};
+use chrono::Utc;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::bitbucket_auth::BitbucketAuth;
+use crate::commands::list_helpers;
+use crate::config::DEFAULT_PR_LIMIT;
+use crate::rate_limit::RateLimited;
+use crate::{ui, utils};
+
+const API_BASE_URL: &str = "https://api.bitbucket.org/2.0";
+
+/// Points the client at a proxy or mirror of the Bitbucket Cloud API instead
+const API_URL_ENV_VAR: &str = "BITBUCKET_CLOUD_API_URL";
+
+/// Largest page size the Bitbucket Cloud pull request endpoint accepts
+const MAX_PAGE_LEN: u32 = 50;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct BitbucketUser {
@@ -52,17 +65,37 @@ pub struct BitbucketPullRequest {
 #[derive(Debug, Deserialize)]
 pub struct BitbucketPullRequestsResponse {
     pub values: Vec<BitbucketPullRequest>,
+    pub next: Option<String>,
 }
 
 pub struct BitbucketClient {
     client: Client,
     auth: BitbucketAuth,
+    base_url: String,
+    pr_limit: u32,
 }
 
 impl BitbucketClient {
     pub fn new(auth: BitbucketAuth) -> Self {
         let client = Client::new();
-        BitbucketClient { client, auth }
+        BitbucketClient {
+            client,
+            auth,
+            base_url: api_base_url(std::env::var(API_URL_ENV_VAR).ok()),
+            pr_limit: DEFAULT_PR_LIMIT,
+        }
+    }
+
+    #[cfg(test)]
+    fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = api_base_url(Some(base_url.to_string()));
+        self
+    }
+
+    /// Cap the number of pull requests fetched per request
+    pub fn with_pr_limit(mut self, pr_limit: u32) -> Self {
+        self.pr_limit = pr_limit;
+        self
     }
 
     fn get_email(&self) -> String {
@@ -70,64 +103,212 @@ impl BitbucketClient {
         self.auth.email().unwrap_or_else(|| "user".to_string())
     }
 
-    pub async fn get_pull_requests(&self, workspace: &str, repo_slug: &str) -> Result<Vec<BitbucketPullRequest>> {
+    /// Pull requests opened from `branch`, filtered by the server so one worktree costs one request
+    pub async fn get_pull_requests(
+        &self,
+        workspace: &str,
+        repo_slug: &str,
+        branch: &str,
+    ) -> Result<Vec<BitbucketPullRequest>> {
+        let url = reqwest::Url::parse_with_params(
+            &format!(
+                "{}/repositories/{}/{}/pullrequests",
+                self.base_url, workspace, repo_slug
+            ),
+            &[
+                ("q", branch_query(branch)),
+                ("pagelen", self.pr_limit.clamp(1, MAX_PAGE_LEN).to_string()),
+            ],
+        )
+        .context("Invalid Bitbucket API URL")?;
+        self.get_paginated(url.as_str(), workspace, repo_slug).await
+    }
+
+    /// All open pull requests in the repository, following pagination up to the PR limit
+    pub async fn get_all_open_pull_requests(
+        &self,
+        workspace: &str,
+        repo_slug: &str,
+    ) -> Result<Vec<BitbucketPullRequest>> {
+        let url = format!(
+            "{}/repositories/{}/{}/pullrequests?state=OPEN&pagelen={}",
+            self.base_url,
+            workspace,
+            repo_slug,
+            self.pr_limit.clamp(1, MAX_PAGE_LEN)
+        );
+        self.get_paginated(&url, workspace, repo_slug).await
+    }
+
+    /// Follow `next` links from `url`, stopping once `pr_limit` pull requests have been collected
+    async fn get_paginated(&self, url: &str, workspace: &str, repo_slug: &str) -> Result<Vec<BitbucketPullRequest>> {
+        let token = self.auth.get_token()?;
+        let limit = self.pr_limit as usize;
+        let mut pull_requests = Vec::new();
+        let mut next_url = Some(url.to_string());
+
+        let mut retried = false;
+
+        while let Some(url) = next_url.take() {
+            let response = self
+                .client
+                .get(&url)
+                .basic_auth(self.get_email(), Some(&token))
+                .header("Accept", "application/json")
+                .send()
+                .await
+                .context("Failed to send request to Bitbucket API")?;
+
+            if let Some(limited) =
+                RateLimited::from_response("Bitbucket", response.status(), response.headers(), Utc::now())
+            {
+                // Sit out a short wait once; anything longer is reported with the reset time
+                match limited.backoff(Utc::now()) {
+                    Some(wait) if !retried => {
+                        limited.warn_retrying(wait);
+                        tokio::time::sleep(wait).await;
+                        retried = true;
+                        next_url = Some(url);
+                        continue;
+                    }
+                    _ => return Err(limited.into()),
+                }
+            }
+
+            if response.status().is_client_error() {
+                let status = response.status();
+                let text = response.text().await.unwrap_or_default();
+
+                if status == 401 {
+                    return Err(anyhow::anyhow!(
+                        "Authentication failed. Please check your Bitbucket credentials and run 'gwt auth bitbucket-cloud' to update them."
+                    ));
+                } else if status == 404 {
+                    return Err(anyhow::anyhow!(
+                        "Repository not found: {}/{}. Please check the workspace and repository name.",
+                        workspace,
+                        repo_slug
+                    ));
+                } else {
+                    return Err(anyhow::anyhow!("API request failed with status {}: {}", status, text));
+                }
+            }
+
+            let page: BitbucketPullRequestsResponse = response
+                .json()
+                .await
+                .context("Failed to parse Bitbucket API response")?;
+
+            pull_requests.extend(page.values);
+            next_url = page.next;
+
+            if pull_requests.len() >= limit {
+                break;
+            }
+        }
+
+        if next_url.is_some() || pull_requests.len() > limit {
+            pull_requests.truncate(limit);
+            utils::warn_pr_limit_reached(pull_requests.len());
+        }
+
+        Ok(pull_requests)
+    }
+
+    /// Open a pull request from `source` into `destination` and return its web URL
+    pub async fn create_pull_request(
+        &self,
+        workspace: &str,
+        repo_slug: &str,
+        source: &str,
+        destination: &str,
+        title: &str,
+        draft: bool,
+    ) -> Result<String> {
         let token = self.auth.get_token()?;
         let url = format!(
-            "https://api.bitbucket.org/2.0/repositories/{}/{}/pullrequests",
-            workspace, repo_slug
+            "{}/repositories/{}/{}/pullrequests",
+            self.base_url, workspace, repo_slug
         );
 
         let response = self
             .client
-            .get(&url)
-            .basic_auth(&self.get_email(), Some(&token))
+            .post(&url)
+            .basic_auth(self.get_email(), Some(&token))
             .header("Accept", "application/json")
+            .json(&create_pull_request_body(source, destination, title, draft))
             .send()
             .await
             .context("Failed to send request to Bitbucket API")?;
 
-        if response.status().is_client_error() {
-            let status = response.status();
+        let status = response.status();
+        if status == 401 {
+            return Err(anyhow::anyhow!(
+                "Authentication failed. Please check your Bitbucket credentials and run 'gwt auth bitbucket-cloud' to update them."
+            ));
+        } else if !status.is_success() {
             let text = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!(
+                "Failed to create pull request (status {}): {}",
+                status,
+                text
+            ));
+        }
 
+        let pr: BitbucketPullRequest = response
+            .json()
+            .await
+            .context("Failed to parse Bitbucket API response")?;
+
+        Ok(list_helpers::extract_bitbucket_cloud_url(&pr))
+    }
+
+    /// Account UUID of the user the stored token belongs to, as it appears on pull request authors
+    pub async fn get_current_user(&self) -> Result<String> {
+        let token = self.auth.get_token()?;
+        let url = format!("{}/user", self.base_url);
+
+        let response = self
+            .client
+            .get(&url)
+            .basic_auth(self.get_email(), Some(&token))
+            .header("Accept", "application/json")
+            .send()
+            .await
+            .context("Failed to look up the Bitbucket user")?;
+
+        let status = response.status();
+        if !status.is_success() {
             if status == 401 {
                 return Err(anyhow::anyhow!(
-                    "Authentication failed. Please check your Bitbucket credentials and run 'gwt auth bitbucket' to update them."
-                ));
-            } else if status == 404 {
-                return Err(anyhow::anyhow!(
-                    "Repository not found: {}/{}. Please check the workspace and repository name.",
-                    workspace,
-                    repo_slug
+                    "Authentication failed. Please check your Bitbucket credentials."
                 ));
-            } else {
-                return Err(anyhow::anyhow!("API request failed with status {}: {}", status, text));
             }
+            return Err(anyhow::anyhow!("Bitbucket user lookup failed with status: {}", status));
         }
 
-        let pr_response: BitbucketPullRequestsResponse = response
+        let user: BitbucketUser = response
             .json()
             .await
             .context("Failed to parse Bitbucket API response")?;
-
-        Ok(pr_response.values)
+        Ok(user.uuid)
     }
 
     pub async fn test_connection(&self) -> Result<()> {
         let token = self.auth.get_token()?;
-        let url = "https://api.bitbucket.org/2.0/user";
+        let url = format!("{}/user", self.base_url);
 
         let response = self
             .client
-            .get(url)
-            .basic_auth(&self.get_email(), Some(&token))
+            .get(&url)
+            .basic_auth(self.get_email(), Some(&token))
             .header("Accept", "application/json")
             .send()
             .await
             .context("Failed to test Bitbucket API connection")?;
 
         if response.status().is_success() {
-            println!("✓ Bitbucket API connection successful");
+            ui::success("Bitbucket API connection successful");
             Ok(())
         } else {
             let status = response.status();
@@ -142,6 +323,20 @@ impl BitbucketClient {
     }
 }
 
+fn create_pull_request_body(source: &str, destination: &str, title: &str, draft: bool) -> serde_json::Value {
+    serde_json::json!({
+        "title": title,
+        "source": { "branch": { "name": source } },
+        "destination": { "branch": { "name": destination } },
+        "draft": draft,
+    })
+}
+
+/// BBQL filter for pull requests whose source is `branch`
+fn branch_query(branch: &str) -> String {
+    format!("source.branch.name=\"{}\"", branch.replace('"', "\\\""))
+}
+
 pub fn extract_bitbucket_info_from_url(url: &str) -> Option<(String, String)> {
     // Parse URLs like:
     // https://bitbucket.org/workspace/repo
@@ -166,9 +361,211 @@ pub fn is_bitbucket_repository(remote_url: &str) -> bool {
     remote_url.contains("bitbucket.org")
 }
 
+/// The API root to use: `override_url` (from `BITBUCKET_CLOUD_API_URL`) if set, else the canonical host
+fn api_base_url(override_url: Option<String>) -> String {
+    match override_url {
+        Some(url) if !url.trim().is_empty() => url.trim().trim_end_matches('/').to_string(),
+        _ => API_BASE_URL.to_string(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serial_test::serial;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn pull_request_json(id: u64, branch: &str) -> serde_json::Value {
+        let repository = serde_json::json!({ "name": "repo", "full_name": "ws/repo", "uuid": "{repo}" });
+        serde_json::json!({
+            "id": id,
+            "title": format!("PR {}", id),
+            "state": "OPEN",
+            "author": { "display_name": "Dev", "uuid": "{dev}" },
+            "source": { "branch": { "name": branch }, "repository": repository },
+            "destination": { "branch": { "name": "main" }, "repository": repository },
+            "created_on": "2024-01-01T00:00:00Z",
+            "updated_on": "2024-01-01T00:00:00Z",
+            "links": { "html": { "href": format!("https://bitbucket.org/ws/repo/pull-requests/{}", id) } }
+        })
+    }
+
+    #[test]
+    fn test_api_base_url() {
+        assert_eq!(api_base_url(None), API_BASE_URL);
+        assert_eq!(api_base_url(Some("  ".to_string())), API_BASE_URL);
+        assert_eq!(
+            api_base_url(Some("https://bitbucket-proxy.example.com/2.0/".to_string())),
+            "https://bitbucket-proxy.example.com/2.0"
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_all_open_pull_requests_follows_pagination() {
+        std::env::set_var("BITBUCKET_CLOUD_API_TOKEN", "test-token");
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/repositories/ws/repo/pullrequests"))
+            .and(query_param("page", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "values": [pull_request_json(3, "feature/c")]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repositories/ws/repo/pullrequests"))
+            .and(query_param("state", "OPEN"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "values": [pull_request_json(1, "feature/a"), pull_request_json(2, "feature/b")],
+                "next": format!("{}/repositories/ws/repo/pullrequests?page=2", server.uri())
+            })))
+            .mount(&server)
+            .await;
+
+        let auth = BitbucketAuth::new("ws".to_string(), "repo".to_string(), None).unwrap();
+        let client = BitbucketClient::new(auth).with_base_url(&server.uri());
+
+        let prs = client.get_all_open_pull_requests("ws", "repo").await.unwrap();
+        let ids: Vec<u64> = prs.iter().map(|pr| pr.id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+
+        // The cap stops pagination early
+        let auth = BitbucketAuth::new("ws".to_string(), "repo".to_string(), None).unwrap();
+        let client = BitbucketClient::new(auth).with_base_url(&server.uri()).with_pr_limit(2);
+        let prs = client.get_all_open_pull_requests("ws", "repo").await.unwrap();
+        assert_eq!(prs.len(), 2);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_pull_requests_parses_response() {
+        std::env::set_var("BITBUCKET_CLOUD_API_TOKEN", "test-token");
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/repositories/ws/repo/pullrequests"))
+            .and(query_param("q", "source.branch.name=\"feature/login\""))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "values": [pull_request_json(7, "feature/login")]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let auth = BitbucketAuth::new("ws".to_string(), "repo".to_string(), None).unwrap();
+        let client = BitbucketClient::new(auth).with_base_url(&server.uri());
+
+        let prs = client.get_pull_requests("ws", "repo", "feature/login").await.unwrap();
+        assert_eq!(prs.len(), 1);
+        assert_eq!(prs[0].title, "PR 7");
+        assert_eq!(prs[0].source.branch.name, "feature/login");
+        assert_eq!(prs[0].destination.branch.name, "main");
+        assert_eq!(
+            prs[0].links["html"]["href"],
+            "https://bitbucket.org/ws/repo/pull-requests/7"
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_pull_requests_maps_client_errors() {
+        std::env::set_var("BITBUCKET_CLOUD_API_TOKEN", "test-token");
+        let server = MockServer::start().await;
+
+        for (repo, status) in [("private", 401), ("missing", 404), ("bad", 400)] {
+            Mock::given(method("GET"))
+                .and(path(format!("/repositories/ws/{}/pullrequests", repo)))
+                .respond_with(ResponseTemplate::new(status).set_body_string("nope"))
+                .mount(&server)
+                .await;
+        }
+
+        let auth = BitbucketAuth::new("ws".to_string(), "repo".to_string(), None).unwrap();
+        let client = BitbucketClient::new(auth).with_base_url(&server.uri());
+
+        assert!(client_error(&client, "private").await.contains("Authentication failed"));
+        assert!(client_error(&client, "missing")
+            .await
+            .contains("Repository not found: ws/missing"));
+        assert!(client_error(&client, "bad")
+            .await
+            .contains("API request failed with status 400 Bad Request: nope"));
+    }
+
+    async fn client_error(client: &BitbucketClient, repo: &str) -> String {
+        client
+            .get_pull_requests("ws", repo, "feature/a")
+            .await
+            .unwrap_err()
+            .to_string()
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_pull_requests_backs_off_on_rate_limit() {
+        std::env::set_var("BITBUCKET_CLOUD_API_TOKEN", "test-token");
+        let server = MockServer::start().await;
+
+        // A short Retry-After is waited out once, then the request succeeds
+        Mock::given(method("GET"))
+            .and(path("/repositories/ws/repo/pullrequests"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repositories/ws/repo/pullrequests"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "values": [pull_request_json(1, "feature/a")]
+            })))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        // A long one is reported instead of waited for
+        Mock::given(method("GET"))
+            .and(path("/repositories/ws/repo/pullrequests"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "3600"))
+            .mount(&server)
+            .await;
+
+        let auth = BitbucketAuth::new("ws".to_string(), "repo".to_string(), None).unwrap();
+        let client = BitbucketClient::new(auth).with_base_url(&server.uri());
+
+        let prs = client.get_pull_requests("ws", "repo", "feature/a").await.unwrap();
+        assert_eq!(prs.len(), 1);
+
+        let error = client.get_pull_requests("ws", "repo", "feature/a").await.unwrap_err();
+        let limited = error.downcast_ref::<RateLimited>().unwrap();
+        assert!(limited.reset_at.is_some());
+        assert!(error.to_string().contains("rate limit reached. It resets at"));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_create_pull_request_url_without_links() {
+        std::env::set_var("BITBUCKET_CLOUD_API_TOKEN", "test-token");
+        let server = MockServer::start().await;
+
+        let mut created = pull_request_json(9, "feature/x");
+        created["links"] = serde_json::json!({});
+        Mock::given(method("POST"))
+            .and(path("/repositories/ws/repo/pullrequests"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(created))
+            .mount(&server)
+            .await;
+
+        let auth = BitbucketAuth::new("ws".to_string(), "repo".to_string(), None).unwrap();
+        let client = BitbucketClient::new(auth).with_base_url(&server.uri());
+
+        let url = client
+            .create_pull_request("ws", "repo", "feature/x", "main", "Add x", false)
+            .await
+            .unwrap();
+        assert_eq!(url, "https://bitbucket.org/ws/repo/pull-requests/9");
+    }
 
     #[test]
     fn test_extract_bitbucket_info_https() {
@@ -198,6 +595,21 @@ mod tests {
         assert_eq!(result, None);
     }
 
+    #[test]
+    fn test_branch_query() {
+        assert_eq!(branch_query("feature/x"), "source.branch.name=\"feature/x\"");
+        assert_eq!(branch_query("say\"hi"), "source.branch.name=\"say\\\"hi\"");
+    }
+
+    #[test]
+    fn test_create_pull_request_body() {
+        let body = create_pull_request_body("feature/x", "main", "Add x", true);
+        assert_eq!(body["source"]["branch"]["name"], "feature/x");
+        assert_eq!(body["destination"]["branch"]["name"], "main");
+        assert_eq!(body["title"], "Add x");
+        assert_eq!(body["draft"], true);
+    }
+
     #[test]
     fn test_is_bitbucket_repository() {
         assert!(is_bitbucket_repository("https://bitbucket.org/workspace/repo"));
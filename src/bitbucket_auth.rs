@@ -59,6 +59,20 @@ impl BitbucketAuth {
         // Then check keyring
         self.token_entry.get_password().is_ok()
     }
+
+    /// Delete the token stored in the keyring; `false` when none was stored
+    pub fn remove_token(&self) -> Result<bool> {
+        match self.token_entry.delete_credential() {
+            Ok(()) => Ok(true),
+            Err(keyring::Error::NoEntry) => Ok(false),
+            Err(e) => Err(e).context("Failed to remove the Bitbucket token from the keyring"),
+        }
+    }
+}
+
+/// Whether a token is set in the environment, which takes precedence over the keyring
+pub fn token_in_environment() -> bool {
+    env::var(TOKEN_ENV_VAR).is_ok_and(|token| !token.is_empty())
 }
 
 pub fn get_auth_from_config() -> Result<(String, String, Option<String>)> {
@@ -68,11 +82,12 @@ pub fn get_auth_from_config() -> Result<(String, String, Option<String>)> {
     let (_, config) =
         GitWorktreeConfig::find_config()?.ok_or_else(|| anyhow::anyhow!("No git-worktree-config.yaml found"))?;
 
-    if !config.repository_url.contains("bitbucket.org") {
+    let repo_url = config.resolved_repository_url();
+    if !repo_url.contains("bitbucket.org") {
         return Err(anyhow::anyhow!("This is not a Bitbucket repository"));
     }
 
-    let (workspace, repo) = extract_bitbucket_info_from_url(&config.repository_url)
+    let (workspace, repo) = extract_bitbucket_info_from_url(&repo_url)
         .ok_or_else(|| anyhow::anyhow!("Failed to parse Bitbucket repository URL"))?;
 
     Ok((workspace, repo, config.bitbucket_email))
@@ -99,7 +114,7 @@ mod tests {
     #[test]
     fn test_bitbucket_auth_creation() {
         // Temporarily remove environment variable for isolated testing
-        let _guard = env::remove_var(EMAIL_ENV_VAR);
+        env::remove_var(EMAIL_ENV_VAR);
 
         let auth = BitbucketAuth::new(
             "myworkspace".to_string(),
@@ -112,6 +127,12 @@ mod tests {
         assert_eq!(auth.email(), Some("test@example.com".to_string()));
     }
 
+    #[test]
+    fn test_remove_token_without_stored_token() {
+        let auth = BitbucketAuth::new("workspace".to_string(), "never-stored".to_string(), None).unwrap();
+        assert!(!auth.remove_token().unwrap());
+    }
+
     #[test]
     fn test_workspace_repo_key() {
         let auth = BitbucketAuth::new("workspace".to_string(), "repo".to_string(), None).unwrap();
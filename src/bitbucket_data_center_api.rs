@@ -1,9 +1,14 @@
 use anyhow::{Context, Result};
+use chrono::Utc;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use crate::bitbucket_data_center_auth::BitbucketDataCenterAuth;
+use crate::commands::list_helpers;
+use crate::config::DEFAULT_PR_LIMIT;
+use crate::rate_limit::RateLimited;
+use crate::{ui, utils};
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct BitbucketDataCenterUser {
@@ -63,6 +68,7 @@ pub struct BitbucketDataCenterRepository {
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
+#[allow(dead_code)]
 pub struct BitbucketDataCenterBranch {
     pub id: String,
     #[serde(rename = "displayId")]
@@ -116,29 +122,223 @@ pub struct BitbucketDataCenterPullRequestsResponse {
     #[allow(dead_code)]
     pub limit: u32,
     #[serde(rename = "isLastPage")]
-    #[allow(dead_code)]
     pub is_last_page: bool,
     #[allow(dead_code)]
     pub start: u32,
+    #[serde(rename = "nextPageStart")]
+    pub next_page_start: Option<u32>,
+}
+
+/// Failures the list layer reports differently, so users know whether to fix the network or the token
+#[derive(Debug)]
+pub enum BitbucketDataCenterError {
+    /// The server could not be reached at all (DNS, connection refused, timeout, TLS)
+    Unreachable {
+        base_url: String,
+        source: reqwest::Error,
+    },
+    AuthFailed,
+    NotFound {
+        project_key: String,
+        repo_slug: String,
+    },
+    Status {
+        status: reqwest::StatusCode,
+        body: String,
+    },
+}
+
+impl std::fmt::Display for BitbucketDataCenterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unreachable { base_url, source } => {
+                write!(f, "Bitbucket Data Center is unreachable at {}: {}", base_url, source)
+            }
+            Self::AuthFailed => write!(
+                f,
+                "Authentication failed. Please check your Bitbucket Data Center access token and run 'gwt auth bitbucket-data-center' to update it."
+            ),
+            Self::NotFound {
+                project_key,
+                repo_slug,
+            } => write!(
+                f,
+                "Repository not found: {}/{}. Please check the project key and repository slug.",
+                project_key, repo_slug
+            ),
+            Self::Status { status, body } => write!(f, "API request failed with status {}: {}", status, body),
+        }
+    }
+}
+
+impl std::error::Error for BitbucketDataCenterError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Unreachable { source, .. } => Some(source),
+            _ => None,
+        }
+    }
 }
 
 pub struct BitbucketDataCenterClient {
     client: Client,
     auth: BitbucketDataCenterAuth,
     base_url: String,
+    pr_limit: u32,
 }
 
 impl BitbucketDataCenterClient {
     pub fn new(auth: BitbucketDataCenterAuth, base_url: String) -> Self {
         let client = Client::new();
-        BitbucketDataCenterClient { client, auth, base_url }
+        BitbucketDataCenterClient {
+            client,
+            auth,
+            base_url,
+            pr_limit: DEFAULT_PR_LIMIT,
+        }
     }
 
+    /// Cap the number of pull requests fetched per request
+    pub fn with_pr_limit(mut self, pr_limit: u32) -> Self {
+        self.pr_limit = pr_limit;
+        self
+    }
+
+    /// Server URL the client talks to, e.g. `https://git.acme.com`
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    #[cfg(test)]
+    fn with_http_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Pull requests in the repository, following `start`/`limit` paging up to the PR limit
     pub async fn get_pull_requests(
         &self,
         project_key: &str,
         repo_slug: &str,
     ) -> Result<Vec<BitbucketDataCenterPullRequest>> {
+        self.fetch_pull_requests(project_key, repo_slug, None).await
+    }
+
+    /// Pull requests opened from `branch`, filtered by the server so one worktree costs one request
+    pub async fn get_pull_requests_for_branch(
+        &self,
+        project_key: &str,
+        repo_slug: &str,
+        branch: &str,
+    ) -> Result<Vec<BitbucketDataCenterPullRequest>> {
+        self.fetch_pull_requests(project_key, repo_slug, Some(branch)).await
+    }
+
+    async fn fetch_pull_requests(
+        &self,
+        project_key: &str,
+        repo_slug: &str,
+        branch: Option<&str>,
+    ) -> Result<Vec<BitbucketDataCenterPullRequest>> {
+        let branch_filter: Vec<(&str, String)> = match branch {
+            Some(branch) => vec![
+                ("at", format!("refs/heads/{}", branch)),
+                ("direction", "OUTGOING".to_string()),
+            ],
+            None => Vec::new(),
+        };
+        let token = self.auth.get_token()?;
+        let limit = self.pr_limit.max(1) as usize;
+        let mut pull_requests = Vec::new();
+        let mut start = 0;
+        let mut retried = false;
+
+        loop {
+            // Ask only for what is still needed; the server may return fewer per page
+            let url = format!(
+                "{}/rest/api/1.0/projects/{}/repos/{}/pull-requests?start={}&limit={}",
+                self.base_url.trim_end_matches('/'),
+                project_key,
+                repo_slug,
+                start,
+                limit - pull_requests.len()
+            );
+
+            let response = self
+                .client
+                .get(&url)
+                .query(&branch_filter)
+                .bearer_auth(&token)
+                .header("Accept", "application/json")
+                .send()
+                .await
+                .map_err(|source| BitbucketDataCenterError::Unreachable {
+                    base_url: self.base_url.clone(),
+                    source,
+                })?;
+
+            let status = response.status();
+            if let Some(limited) =
+                RateLimited::from_response("Bitbucket Data Center", status, response.headers(), Utc::now())
+            {
+                // Sit out a short wait once; anything longer is reported with the reset time
+                match limited.backoff(Utc::now()) {
+                    Some(wait) if !retried => {
+                        limited.warn_retrying(wait);
+                        tokio::time::sleep(wait).await;
+                        retried = true;
+                        continue;
+                    }
+                    _ => return Err(limited.into()),
+                }
+            }
+
+            if status == 401 {
+                return Err(BitbucketDataCenterError::AuthFailed.into());
+            } else if status == 404 {
+                return Err(BitbucketDataCenterError::NotFound {
+                    project_key: project_key.to_string(),
+                    repo_slug: repo_slug.to_string(),
+                }
+                .into());
+            } else if !status.is_success() {
+                let body = response.text().await.unwrap_or_default();
+                return Err(BitbucketDataCenterError::Status { status, body }.into());
+            }
+
+            let page: BitbucketDataCenterPullRequestsResponse = response
+                .json()
+                .await
+                .context("Failed to parse Bitbucket Data Center API response")?;
+
+            pull_requests.extend(page.values);
+
+            match page.next_page_start {
+                Some(next_start) if !page.is_last_page => {
+                    if pull_requests.len() >= limit {
+                        pull_requests.truncate(limit);
+                        utils::warn_pr_limit_reached(pull_requests.len());
+                        break;
+                    }
+                    start = next_start;
+                }
+                _ => break,
+            }
+        }
+
+        Ok(pull_requests)
+    }
+
+    /// Open a pull request from `source` into `destination` and return its web URL
+    pub async fn create_pull_request(
+        &self,
+        project_key: &str,
+        repo_slug: &str,
+        source: &str,
+        destination: &str,
+        title: &str,
+        draft: bool,
+    ) -> Result<String> {
         let token = self.auth.get_token()?;
         let url = format!(
             "{}/rest/api/1.0/projects/{}/repos/{}/pull-requests",
@@ -149,38 +349,71 @@ impl BitbucketDataCenterClient {
 
         let response = self
             .client
-            .get(&url)
+            .post(&url)
             .bearer_auth(&token)
             .header("Accept", "application/json")
+            .json(&create_pull_request_body(source, destination, title, draft))
             .send()
             .await
             .context("Failed to send request to Bitbucket Data Center API")?;
 
-        if response.status().is_client_error() {
-            let status = response.status();
+        let status = response.status();
+        if status == 401 {
+            return Err(anyhow::anyhow!(
+                "Authentication failed. Please check your Bitbucket Data Center access token and run 'gwt auth bitbucket-data-center' to update it."
+            ));
+        } else if !status.is_success() {
             let text = response.text().await.unwrap_or_default();
-
-            if status == 401 {
-                return Err(anyhow::anyhow!(
-                    "Authentication failed. Please check your Bitbucket Data Center access token and run 'gwt auth bitbucket-data-center' to update it."
-                ));
-            } else if status == 404 {
-                return Err(anyhow::anyhow!(
-                    "Repository not found: {}/{}. Please check the project key and repository slug.",
-                    project_key,
-                    repo_slug
-                ));
-            } else {
-                return Err(anyhow::anyhow!("API request failed with status {}: {}", status, text));
-            }
+            return Err(anyhow::anyhow!(
+                "Failed to create pull request (status {}): {}",
+                status,
+                text
+            ));
         }
 
-        let pr_response: BitbucketDataCenterPullRequestsResponse = response
+        let pr: BitbucketDataCenterPullRequest = response
             .json()
             .await
             .context("Failed to parse Bitbucket Data Center API response")?;
 
-        Ok(pr_response.values)
+        Ok(list_helpers::extract_bitbucket_data_center_url(&pr, &self.base_url))
+    }
+
+    /// Username the access token belongs to, as it appears on pull request authors
+    pub async fn get_current_user(&self) -> Result<String> {
+        let token = self.auth.get_token()?;
+        // Answers with the bare username in plain text
+        let url = format!(
+            "{}/plugins/servlet/applinks/whoami",
+            self.base_url.trim_end_matches('/')
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .bearer_auth(&token)
+            .send()
+            .await
+            .map_err(|source| BitbucketDataCenterError::Unreachable {
+                base_url: self.base_url.clone(),
+                source,
+            })?;
+
+        let status = response.status();
+        if status == 401 {
+            return Err(BitbucketDataCenterError::AuthFailed.into());
+        }
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(BitbucketDataCenterError::Status { status, body }.into());
+        }
+
+        let username = response.text().await?.trim().to_string();
+        // An anonymous request gets an empty answer rather than a 401
+        if username.is_empty() {
+            return Err(BitbucketDataCenterError::AuthFailed.into());
+        }
+        Ok(username)
     }
 
     pub async fn test_connection(&self) -> Result<()> {
@@ -197,7 +430,7 @@ impl BitbucketDataCenterClient {
             .context("Failed to test Bitbucket Data Center API connection")?;
 
         if response.status().is_success() {
-            println!("✓ Bitbucket Data Center API connection successful");
+            ui::success("Bitbucket Data Center API connection successful");
             Ok(())
         } else {
             let status = response.status();
@@ -212,6 +445,15 @@ impl BitbucketDataCenterClient {
     }
 }
 
+fn create_pull_request_body(source: &str, destination: &str, title: &str, draft: bool) -> serde_json::Value {
+    serde_json::json!({
+        "title": title,
+        "fromRef": { "id": format!("refs/heads/{}", source) },
+        "toRef": { "id": format!("refs/heads/{}", destination) },
+        "draft": draft,
+    })
+}
+
 pub fn extract_bitbucket_data_center_info_from_url(url: &str) -> Option<(String, String, String)> {
     // Parse URLs like:
     // https://git.acmeorg.com/scm/PROJECT/repository.git
@@ -279,6 +521,147 @@ pub fn extract_bitbucket_data_center_info_from_url(url: &str) -> Option<(String,
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serial_test::serial;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn pull_request_json(id: u64, branch: &str) -> serde_json::Value {
+        let repository = serde_json::json!({
+            "slug": "repo",
+            "name": "repo",
+            "id": 1,
+            "project": { "key": "PROJ", "name": "Project", "id": 1 }
+        });
+        let pr_ref = |branch: &str| {
+            serde_json::json!({
+                "id": format!("refs/heads/{}", branch),
+                "displayId": branch,
+                "latestCommit": "abc123",
+                "type": "BRANCH",
+                "repository": repository
+            })
+        };
+        serde_json::json!({
+            "id": id,
+            "version": 0,
+            "title": format!("PR {}", id),
+            "state": "OPEN",
+            "open": true,
+            "closed": false,
+            "author": {
+                "user": { "name": "dev", "displayName": "Dev", "id": 1, "slug": "dev" },
+                "role": "AUTHOR",
+                "approved": false,
+                "status": "UNAPPROVED"
+            },
+            "fromRef": pr_ref(branch),
+            "toRef": pr_ref("main"),
+            "createdDate": 0,
+            "updatedDate": 0,
+            "links": {}
+        })
+    }
+
+    fn page_json(values: Vec<serde_json::Value>, start: u32, next_page_start: Option<u32>) -> serde_json::Value {
+        serde_json::json!({
+            "size": values.len(),
+            "limit": 2,
+            "isLastPage": next_page_start.is_none(),
+            "start": start,
+            "nextPageStart": next_page_start,
+            "values": values
+        })
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_pull_requests_follows_pagination() {
+        std::env::set_var("BITBUCKET_DATA_CENTER_HTTP_ACCESS_TOKEN", "test-token");
+        let server = MockServer::start().await;
+        let endpoint = "/rest/api/1.0/projects/PROJ/repos/repo/pull-requests";
+
+        Mock::given(method("GET"))
+            .and(path(endpoint))
+            .and(query_param("start", "0"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(page_json(
+                vec![pull_request_json(1, "feature/a"), pull_request_json(2, "feature/b")],
+                0,
+                Some(2),
+            )))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(endpoint))
+            .and(query_param("start", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(page_json(
+                vec![pull_request_json(3, "feature/c")],
+                2,
+                None,
+            )))
+            .mount(&server)
+            .await;
+
+        let client = BitbucketDataCenterClient::new(BitbucketDataCenterAuth, server.uri());
+        let prs = client.get_pull_requests("PROJ", "repo").await.unwrap();
+        let ids: Vec<u64> = prs.iter().map(|pr| pr.id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+
+        // The cap stops paging before the second request
+        let client = BitbucketDataCenterClient::new(BitbucketDataCenterAuth, server.uri()).with_pr_limit(2);
+        let prs = client.get_pull_requests("PROJ", "repo").await.unwrap();
+        assert_eq!(prs.len(), 2);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_pull_requests_for_branch_filters_on_server() {
+        std::env::set_var("BITBUCKET_DATA_CENTER_HTTP_ACCESS_TOKEN", "test-token");
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/rest/api/1.0/projects/PROJ/repos/repo/pull-requests"))
+            .and(query_param("at", "refs/heads/feature/login"))
+            .and(query_param("direction", "OUTGOING"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(page_json(
+                vec![pull_request_json(7, "feature/login")],
+                0,
+                None,
+            )))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = BitbucketDataCenterClient::new(BitbucketDataCenterAuth, server.uri());
+        let prs = client
+            .get_pull_requests_for_branch("PROJ", "repo", "feature/login")
+            .await
+            .unwrap();
+        assert_eq!(prs.len(), 1);
+        assert_eq!(prs[0].from_ref.display_id, "feature/login");
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_create_pull_request_url_without_links() {
+        std::env::set_var("BITBUCKET_DATA_CENTER_HTTP_ACCESS_TOKEN", "test-token");
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/rest/api/1.0/projects/PROJ/repos/repo/pull-requests"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(pull_request_json(9, "feature/x")))
+            .mount(&server)
+            .await;
+
+        let client = BitbucketDataCenterClient::new(BitbucketDataCenterAuth, format!("{}/", server.uri()));
+        let url = client
+            .create_pull_request("PROJ", "repo", "feature/x", "main", "Add x", false)
+            .await
+            .unwrap();
+        assert_eq!(
+            url,
+            format!("{}/projects/PROJ/repos/repo/pull-requests/9", server.uri())
+        );
+    }
 
     #[test]
     fn test_extract_bitbucket_data_center_info_scm() {
@@ -350,6 +733,130 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_get_pull_requests_reports_unreachable_server() {
+        std::env::set_var("BITBUCKET_DATA_CENTER_HTTP_ACCESS_TOKEN", "test-token");
+
+        // Nothing listens on port 1, so the connection is refused
+        let client = BitbucketDataCenterClient::new(BitbucketDataCenterAuth, "http://127.0.0.1:1".to_string());
+        let error = client.get_pull_requests("PROJ", "repo").await.unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref::<BitbucketDataCenterError>(),
+            Some(BitbucketDataCenterError::Unreachable { .. })
+        ));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_pull_requests_parses_response() {
+        std::env::set_var("BITBUCKET_DATA_CENTER_HTTP_ACCESS_TOKEN", "test-token");
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/rest/api/1.0/projects/PROJ/repos/repo/pull-requests"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(page_json(
+                vec![pull_request_json(7, "feature/login")],
+                0,
+                None,
+            )))
+            .mount(&server)
+            .await;
+
+        let client = BitbucketDataCenterClient::new(BitbucketDataCenterAuth, format!("{}/", server.uri()));
+        let prs = client.get_pull_requests("PROJ", "repo").await.unwrap();
+        assert_eq!(prs.len(), 1);
+        assert_eq!(prs[0].title, "PR 7");
+        assert_eq!(prs[0].state, "OPEN");
+        assert_eq!(prs[0].from_ref.display_id, "feature/login");
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_current_user() {
+        std::env::set_var("BITBUCKET_DATA_CENTER_HTTP_ACCESS_TOKEN", "test-token");
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/plugins/servlet/applinks/whoami"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("jdoe\n"))
+            .mount(&server)
+            .await;
+
+        let client = BitbucketDataCenterClient::new(BitbucketDataCenterAuth, server.uri());
+        assert_eq!(client.get_current_user().await.unwrap(), "jdoe");
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_pull_requests_maps_errors() {
+        std::env::set_var("BITBUCKET_DATA_CENTER_HTTP_ACCESS_TOKEN", "test-token");
+        let server = MockServer::start().await;
+
+        for (repo, status) in [("private", 401), ("missing", 404), ("broken", 500)] {
+            Mock::given(method("GET"))
+                .and(path(format!(
+                    "/rest/api/1.0/projects/PROJ/repos/{}/pull-requests",
+                    repo
+                )))
+                .respond_with(ResponseTemplate::new(status).set_body_string("nope"))
+                .mount(&server)
+                .await;
+        }
+
+        let client = BitbucketDataCenterClient::new(BitbucketDataCenterAuth, server.uri());
+
+        let auth_failed = client.get_pull_requests("PROJ", "private").await.unwrap_err();
+        assert!(matches!(
+            auth_failed.downcast_ref::<BitbucketDataCenterError>(),
+            Some(BitbucketDataCenterError::AuthFailed)
+        ));
+        let not_found = client.get_pull_requests("PROJ", "missing").await.unwrap_err();
+        assert!(matches!(
+            not_found.downcast_ref::<BitbucketDataCenterError>(),
+            Some(BitbucketDataCenterError::NotFound { repo_slug, .. }) if repo_slug == "missing"
+        ));
+        let status = client.get_pull_requests("PROJ", "broken").await.unwrap_err();
+        assert!(matches!(
+            status.downcast_ref::<BitbucketDataCenterError>(),
+            Some(BitbucketDataCenterError::Status { status, body }) if status.as_u16() == 500 && body == "nope"
+        ));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_pull_requests_reports_timeouts_as_unreachable() {
+        std::env::set_var("BITBUCKET_DATA_CENTER_HTTP_ACCESS_TOKEN", "test-token");
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_delay(std::time::Duration::from_secs(5)))
+            .mount(&server)
+            .await;
+
+        let http_client = Client::builder()
+            .timeout(std::time::Duration::from_millis(100))
+            .build()
+            .unwrap();
+        let client =
+            BitbucketDataCenterClient::new(BitbucketDataCenterAuth, server.uri()).with_http_client(http_client);
+        let error = client.get_pull_requests("PROJ", "repo").await.unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref::<BitbucketDataCenterError>(),
+            Some(BitbucketDataCenterError::Unreachable { .. })
+        ));
+    }
+
+    #[test]
+    fn test_create_pull_request_body() {
+        let body = create_pull_request_body("feature/x", "main", "Add x", false);
+        assert_eq!(body["fromRef"]["id"], "refs/heads/feature/x");
+        assert_eq!(body["toRef"]["id"], "refs/heads/main");
+        assert_eq!(body["draft"], false);
+    }
+
     #[test]
     fn test_extract_bitbucket_data_center_info_invalid() {
         let url = "https://github.com/user/repo";
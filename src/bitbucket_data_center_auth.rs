@@ -1,6 +1,8 @@
 use anyhow::Result;
 use std::env;
 
+use crate::config::GitWorktreeConfig;
+
 const TOKEN_ENV_VAR: &str = "BITBUCKET_DATA_CENTER_HTTP_ACCESS_TOKEN";
 
 pub struct BitbucketDataCenterAuth;
@@ -58,22 +60,26 @@ fn derive_api_base_url_from_repo_url(repo_url: &str) -> Option<String> {
 }
 
 pub fn get_auth_from_config() -> Result<(String, String, String)> {
-    use crate::bitbucket_data_center_api::extract_bitbucket_data_center_info_from_url;
-    use crate::config::GitWorktreeConfig;
-    use crate::github;
-
     let (_, config) =
         GitWorktreeConfig::find_config()?.ok_or_else(|| anyhow::anyhow!("No git-worktree-config.yaml found"))?;
+    auth_from_config(&config)
+}
+
+/// Like `get_auth_from_config`, for a project configuration that has already been loaded
+pub fn auth_from_config(config: &GitWorktreeConfig) -> Result<(String, String, String)> {
+    use crate::bitbucket_data_center_api::extract_bitbucket_data_center_info_from_url;
+    use crate::cli::Provider;
+    use crate::github;
 
     // Check sourceControl field instead of URL pattern
-    if config.source_control != "bitbucket-data-center" {
+    if config.source_control != Provider::BitbucketDataCenter {
         return Err(anyhow::anyhow!(
             "Repository is not configured for Bitbucket Data Center (sourceControl: {})",
             config.source_control
         ));
     }
 
-    let repo_url = &config.repository_url;
+    let repo_url = &config.resolved_repository_url();
 
     // First try to extract from actual Bitbucket Data Center URL
     if let Some((base_url, project_key, repo_slug)) = extract_bitbucket_data_center_info_from_url(repo_url) {
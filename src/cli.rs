@@ -14,8 +14,18 @@ pub struct Cli {
     #[arg(short = 'v', long = "version", action = clap::ArgAction::Version)]
     pub version: (),
 
+    /// Only print warnings, errors and the output you asked for
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+
+    /// Disable colored output
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    /// Without a subcommand, gwt runs the project's `defaultCommand` (a short status summary unless
+    /// set to `list`)
     #[command(subcommand)]
-    pub command: Commands,
+    pub command: Option<Commands>,
 }
 
 #[derive(Subcommand)]
@@ -31,7 +41,14 @@ pub enum CompletionAction {
         /// Shell to install completions for (auto-detected if not specified)
         #[arg(value_enum)]
         shell: Option<clap_complete::Shell>,
+        /// Install for every supported shell whose program (bash, zsh, fish, pwsh, elvish) is on
+        /// PATH, creating its completion directory if needed
+        #[arg(long, conflicts_with = "shell")]
+        all: bool,
     },
+    /// List supported shells, detected shell first (used by completion scripts)
+    #[command(hide = true)]
+    Shells,
 }
 
 #[derive(Subcommand)]
@@ -56,6 +73,8 @@ pub enum BitbucketCloudAuthAction {
     Setup,
     /// Test the authentication connection
     Test,
+    /// Remove the token stored for this repository
+    Logout,
 }
 
 #[derive(Subcommand)]
@@ -66,7 +85,45 @@ pub enum BitbucketDataCenterAuthAction {
     Test,
 }
 
-#[derive(clap::ValueEnum, Clone, Debug)]
+#[derive(Subcommand)]
+pub enum ConfigAction {
+    /// Append a command to a hook list
+    SetHook {
+        /// Hook type to add the command to
+        #[arg(value_parser = ["postAdd", "postRemove", "postSwitch"])]
+        hook_type: String,
+        /// Command to run (supports ${branchName}, ${worktreePath} and, for postAdd, ${baseBranch})
+        command: String,
+    },
+    /// Remove all commands for a hook type
+    ClearHook {
+        /// Hook type to clear
+        #[arg(value_parser = ["postAdd", "postRemove", "postSwitch"])]
+        hook_type: String,
+    },
+    /// Check git-worktree-config.yaml for problems; exits non-zero on errors
+    Validate,
+}
+
+fn parse_host_alias(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((alias, host)) if !alias.is_empty() && !host.is_empty() => Ok((alias.to_string(), host.to_string())),
+        _ => Err(format!("expected ALIAS=HOST, got '{}'", value)),
+    }
+}
+
+/// Overrides for the `pathStyle` config setting
+#[derive(clap::Args, Clone, Debug, Default)]
+pub struct PathStyleArgs {
+    /// Show worktree paths relative to the project root
+    #[arg(long, conflicts_with = "absolute")]
+    pub relative: bool,
+    /// Show worktree paths in full
+    #[arg(long)]
+    pub absolute: bool,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Provider {
     /// GitHub repository
     Github,
@@ -76,6 +133,47 @@ pub enum Provider {
     BitbucketDataCenter,
 }
 
+/// Hooks `gwt init` writes into the new config
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HookTemplate {
+    /// Commented-out examples to uncomment
+    #[default]
+    Default,
+    /// Run npm install in new worktrees
+    Node,
+    /// Run cargo fetch in new worktrees
+    Rust,
+    /// No hooks at all
+    None,
+}
+
+/// The name used for `--provider` and `sourceControl`, e.g. `bitbucket-cloud`
+impl std::fmt::Display for Provider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Provider::Github => "github",
+            Provider::BitbucketCloud => "bitbucket-cloud",
+            Provider::BitbucketDataCenter => "bitbucket-data-center",
+        })
+    }
+}
+
+impl std::str::FromStr for Provider {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "github" => Ok(Provider::Github),
+            "bitbucket-cloud" => Ok(Provider::BitbucketCloud),
+            "bitbucket-data-center" => Ok(Provider::BitbucketDataCenter),
+            _ => Err(format!(
+                "unknown provider '{}', expected github, bitbucket-cloud or bitbucket-data-center",
+                name
+            )),
+        }
+    }
+}
+
 #[derive(Subcommand)]
 pub enum Commands {
     /// Initialize a new worktree project from a repository URL
@@ -85,21 +183,247 @@ pub enum Commands {
         /// Repository provider (required for unknown URLs)
         #[arg(long, value_enum)]
         provider: Option<Provider>,
+        /// SSH host alias used in the URL and the real host it stands for (repeatable)
+        #[arg(long = "host-alias", value_name = "ALIAS=HOST", value_parser = parse_host_alias)]
+        host_aliases: Vec<(String, String)>,
+        /// Directory name for the initial worktree (defaults to the default branch name)
+        #[arg(long, value_name = "NAME")]
+        main_worktree_name: Option<String>,
+        /// Keep the clone in a directory named after the repository instead of the default branch
+        #[arg(long, conflicts_with = "main_worktree_name")]
+        no_clone_rename: bool,
+        /// Name the cloned remote NAME instead of origin, e.g. upstream
+        #[arg(short = 'o', long = "origin", value_name = "NAME")]
+        remote_name: Option<String>,
+        /// Skip checking that the repository is reachable before cloning
+        #[arg(long)]
+        no_remote_check: bool,
+        /// Make a read-only mirror clone (repo.git) and add worktrees on top of it
+        #[arg(long, conflicts_with = "no_clone_rename")]
+        mirror: bool,
+        /// Initialize submodules after cloning, and in every worktree gwt adds later
+        #[arg(long, alias = "recurse-submodules")]
+        submodules: bool,
+        /// Hooks to start the config with
+        #[arg(long, value_enum, value_name = "NAME", default_value_t = HookTemplate::Default)]
+        template: HookTemplate,
     },
 
     /// Add a new worktree for a branch
     Add {
         /// Branch name (can include slashes like feature/branch-name)
-        branch_name: String,
+        #[arg(required_unless_present_any = ["from_issue", "checkout_pr"])]
+        branch_name: Option<String>,
+        /// Name the branch after GitHub issue NUMBER, e.g. 42-fix-login-bug
+        #[arg(long, value_name = "NUMBER", conflicts_with_all = ["branch_name", "detach"])]
+        from_issue: Option<u32>,
+        /// Check out the branch of GitHub pull request NUMBER
+        #[arg(
+            long,
+            value_name = "NUMBER",
+            conflicts_with_all = ["branch_name", "from_issue", "detach", "from", "base_sha", "track_remote", "create_pr"]
+        )]
+        checkout_pr: Option<u32>,
+        /// With --checkout-pr, name the worktree directory pr-NUMBER instead of after the branch
+        // clap skips `requires` when a present argument conflicts with the required one,
+        // so the branch name and issue conflicts are spelled out here as well
+        #[arg(long, requires = "checkout_pr", conflicts_with_all = ["path", "branch_name", "from_issue"])]
+        name_from_pr: bool,
+        /// Create the worktree at this path instead of <project-root>/<branch-name>
+        #[arg(long)]
+        path: Option<std::path::PathBuf>,
+        /// Create a detached worktree without a branch (the name is used for the directory)
+        #[arg(long)]
+        detach: bool,
+        /// Commit, tag or branch to check out with --detach (defaults to the current HEAD)
+        #[arg(long = "ref", value_name = "REF", requires = "detach")]
+        git_ref: Option<String>,
+        /// Start a new branch from REF instead of <remote>/<main branch>
+        #[arg(long, value_name = "REF", conflicts_with = "detach")]
+        from: Option<String>,
+        /// Start at exactly this commit: a new branch from it, or detached there with --detach
+        #[arg(long, value_name = "SHA", conflicts_with_all = ["from", "git_ref", "track_remote", "track"])]
+        base_sha: Option<String>,
+        /// Create the branch tracking REMOTE/BRANCH, e.g. upstream/feature in a fork workflow
+        #[arg(long, value_name = "REMOTE/BRANCH", conflicts_with_all = ["from", "detach"])]
+        track_remote: Option<String>,
+        /// Make a new branch track the branch it starts from (overrides defaultTrack)
+        #[arg(long, conflicts_with_all = ["no_track", "track_remote", "detach"])]
+        track: bool,
+        /// Don't make a new branch track the branch it starts from (overrides defaultTrack)
+        #[arg(long, conflicts_with_all = ["track_remote", "detach"])]
+        no_track: bool,
+        /// Run postAdd hooks in the background and log their output instead of waiting
+        #[arg(long)]
+        bg_hooks: bool,
+        /// Push the new branch and open a pull request for it
+        #[arg(long, conflicts_with = "detach")]
+        create_pr: bool,
+        /// Open the pull request as a draft
+        #[arg(long, requires = "create_pr")]
+        draft: bool,
+        /// Pull request title (defaults to the issue title with --from-issue, else the branch name)
+        #[arg(long, requires = "create_pr")]
+        title: Option<String>,
+        /// Branch the pull request targets (defaults to the main branch)
+        #[arg(long, requires = "create_pr")]
+        base: Option<String>,
+        /// Open the branch's pull request in the browser if it already has an open one
+        #[arg(long, conflicts_with_all = ["create_pr", "detach"])]
+        open_pr_if_exists: bool,
+        /// With --open-pr-if-exists, print the pull request URL instead of opening it
+        #[arg(long, requires = "open_pr_if_exists")]
+        print: bool,
+        /// Start a new branch with no history, e.g. for docs or gh-pages
+        #[arg(long, conflicts_with_all = [
+            "detach", "from", "base_sha", "track_remote", "track", "no_track", "create_pr",
+            "open_pr_if_exists", "from_issue", "checkout_pr",
+        ])]
+        orphan: bool,
+        #[command(flatten)]
+        path_style: PathStyleArgs,
     },
 
     /// List all worktrees in the current project
-    List,
+    List {
+        /// Maximum number of pull requests to fetch from the provider (default: 100)
+        #[arg(long)]
+        pr_limit: Option<u32>,
+        /// Show how many commits each worktree has that are not in REV (default: <remote>/<main branch>)
+        #[arg(long, value_name = "REV", num_args = 0..=1)]
+        since: Option<Option<String>>,
+        /// Only show worktrees with commits not in the --since rev
+        #[arg(long)]
+        since_only: bool,
+        /// Show pull request titles in full instead of truncating them to the terminal width
+        #[arg(long)]
+        full_titles: bool,
+        /// Only show the worktree that contains PATH (exits non-zero if none does)
+        #[arg(long, value_name = "PATH", conflicts_with_all = ["since", "since_only"])]
+        contains: Option<std::path::PathBuf>,
+        /// Only show worktrees whose branch has no pull request yet (main and protected branches excluded)
+        #[arg(long, conflicts_with = "contains")]
+        no_pr_only: bool,
+        /// Print one aligned line per worktree: branch, status, number and title
+        #[arg(long, conflicts_with = "contains")]
+        compact: bool,
+        /// Print a JSON object with the worktrees and open pull requests
+        #[arg(long, conflicts_with_all = ["contains", "compact"])]
+        json: bool,
+        /// Print one JSON object per line (NDJSON), each worktree as soon as it's ready
+        #[arg(long, conflicts_with_all = ["contains", "compact", "json", "all"])]
+        json_lines: bool,
+        /// Print one line per worktree from a template, e.g. '{branch} {pr_url}'. Placeholders: branch,
+        /// path, head, upstream, unpushed, untracked, locked, commit_author, commit_date, pr_url, pr_status,
+        /// pr_title, pr_number, pr_author
+        #[arg(long, value_name = "TEMPLATE", conflicts_with_all = ["contains", "compact", "json", "json_lines", "all"])]
+        format: Option<String>,
+        /// Give up on pull request information after SECS seconds and list without it
+        #[arg(long, value_name = "SECS")]
+        fetch_prs_timeout: Option<u64>,
+        /// Also show the bare repository (always included with --json)
+        #[arg(long)]
+        include_bare: bool,
+        /// List every project found in or below the current directory
+        #[arg(long, conflicts_with_all = ["contains", "json"])]
+        all: bool,
+        /// Leave out the summary line with worktree and pull request counts
+        #[arg(long)]
+        no_summary: bool,
+        /// Show the author and age of each worktree's latest commit
+        #[arg(long)]
+        last_commit: bool,
+        /// Flag worktrees with untracked files, which a forced remove would lose
+        #[arg(long)]
+        untracked: bool,
+        /// Skip pull requests for branches matching this glob, e.g. 'dependabot/*' (repeatable;
+        /// replaces listIgnoreBranches)
+        #[arg(long, value_name = "PATTERN")]
+        no_pr_for: Vec<String>,
+        /// Only show worktrees and pull requests you opened
+        #[arg(long, conflicts_with_all = ["contains", "no_pr_only"])]
+        mine: bool,
+        /// Flag pull requests that have merge conflicts with their base (GitHub)
+        #[arg(long, conflicts_with = "contains")]
+        conflicts: bool,
+        #[command(flatten)]
+        path_style: PathStyleArgs,
+    },
+
+    /// Lock a worktree so git and gwt won't remove or prune it
+    Lock {
+        /// Branch name to lock (current worktree if not specified)
+        branch_name: Option<String>,
+        /// Why it is locked, shown by gwt list
+        #[arg(long)]
+        reason: Option<String>,
+        #[command(flatten)]
+        path_style: PathStyleArgs,
+    },
+
+    /// Unlock a worktree locked with gwt lock or git worktree lock
+    Unlock {
+        /// Branch name to unlock (current worktree if not specified)
+        branch_name: Option<String>,
+        #[command(flatten)]
+        path_style: PathStyleArgs,
+    },
+
+    /// Move the whole project to a new directory and repair its worktree links
+    MoveAll {
+        /// Where the project should live; must not exist yet or be empty
+        new_root: std::path::PathBuf,
+        #[command(flatten)]
+        path_style: PathStyleArgs,
+    },
 
     /// Remove a worktree
     Remove {
         /// Branch name to remove (current worktree if not specified)
         branch_name: Option<String>,
+        /// Allow removing a worktree whose branch matches protectedBranches
+        #[arg(long)]
+        force_protected: bool,
+        /// Force-delete the branch even if it has unmerged changes, without asking
+        #[arg(long)]
+        force_branch: bool,
+        /// Remove every worktree whose branch is merged into the main branch
+        #[arg(long, conflicts_with_all = ["branch_name", "force_protected", "force_branch"])]
+        all_merged: bool,
+        /// Don't ask for confirmation (see `confirmations` in git-worktree-config.yaml)
+        #[arg(short, long)]
+        yes: bool,
+        /// Show what would be removed and which hooks would run, without changing anything
+        #[arg(long)]
+        dry_run: bool,
+        #[command(flatten)]
+        path_style: PathStyleArgs,
+    },
+
+    /// Switch to the worktree for a branch
+    Switch {
+        /// Branch name (or worktree directory name)
+        branch_name: String,
+        /// Create the worktree first if it does not exist yet
+        #[arg(short, long)]
+        create: bool,
+        /// Only print the worktree path (for use by a shell wrapper)
+        #[arg(long)]
+        print_path: bool,
+        /// Refuse to switch away from a worktree with uncommitted changes
+        #[arg(long)]
+        safe: bool,
+        #[command(flatten)]
+        path_style: PathStyleArgs,
+    },
+
+    /// Edit the project configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+        /// Write the change without asking (see `confirmations` in git-worktree-config.yaml)
+        #[arg(short, long, global = true)]
+        yes: bool,
     },
 
     /// Manage authentication for external services
@@ -114,4 +438,14 @@ pub enum Commands {
         #[command(subcommand)]
         action: Option<CompletionAction>,
     },
+
+    /// Check whether a newer gwt release is available
+    Upgrade {
+        /// Ignore the cached result and query the registry again
+        #[arg(long)]
+        refresh: bool,
+        /// Silently refresh the cached result (used by the update notice)
+        #[arg(long, hide = true)]
+        background: bool,
+    },
 }
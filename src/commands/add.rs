@@ -1,162 +1,990 @@
-use anyhow::{bail, Result};
-use colored::Colorize;
+use anyhow::{bail, Context, Result};
 use std::fs;
+use std::io::{self, IsTerminal, Write};
 use std::path::{Path, PathBuf};
 
-use crate::config::GitWorktreeConfig;
-use crate::git;
-use crate::hooks;
+use super::add_setup::{SetupState, SetupStep};
+use super::list_helpers::{self, PullRequestInfo};
+use crate::cli::Provider;
+use crate::config::{GitWorktreeConfig, PathStyle, DEFAULT_REMOTE};
+use crate::{bitbucket_api, bitbucket_auth, bitbucket_data_center_api, bitbucket_data_center_auth, git, github};
+use crate::{hooks, platform, project, ui, utils};
 
-pub fn run(branch_name: &str) -> Result<()> {
+#[derive(Debug, Default, Clone)]
+pub struct AddOptions<'a> {
+    /// Create the worktree here instead of <project-root>/<branch-name>
+    pub path: Option<&'a Path>,
+    /// Check out `git_ref` without creating a branch
+    pub detach: bool,
+    /// Commit-ish for a detached worktree (defaults to the current HEAD)
+    pub git_ref: Option<&'a str>,
+    /// Start a new branch from this ref instead of `<remote>/<main_branch>`
+    pub from: Option<&'a str>,
+    /// Exact commit to start the new branch (or the detached worktree) at
+    pub base_sha: Option<&'a str>,
+    /// Create the new branch tracking this remote branch, e.g. `upstream/feature`
+    pub track_remote: Option<&'a str>,
+    /// Whether a new branch tracks its base; overrides `defaultTrack`
+    pub track: Option<bool>,
+    /// Run `postAdd` hooks in the background even without `backgroundHooks`
+    pub background_hooks: bool,
+    /// Push the new branch and open a pull request for it
+    pub create_pr: bool,
+    pub draft: bool,
+    /// Pull request title (defaults to the branch name)
+    pub title: Option<&'a str>,
+    /// Pull request target branch (defaults to the main branch)
+    pub base: Option<&'a str>,
+    /// Overrides `pathStyle` for the paths we print
+    pub path_style: Option<PathStyle>,
+    /// GitHub issue the branch was named after; a `--create-pr` pull request closes it
+    pub issue: Option<&'a Issue>,
+    /// Directory name under the project root, instead of the branch name
+    pub dir_name: Option<&'a str>,
+    /// Afterwards, open the branch's open pull request in the browser if it has one
+    pub open_existing_pr: bool,
+    /// With `open_existing_pr`, print the pull request URL instead of opening it
+    pub print_pr_url: bool,
+    /// Start a branch with no history instead of branching from a base
+    pub orphan: bool,
+}
+
+/// A GitHub issue and the branch name generated for it by `--from-issue`
+#[derive(Debug)]
+pub struct Issue {
+    pub number: u32,
+    pub title: String,
+    pub branch_name: String,
+}
+
+/// Longest slug taken from an issue title for its branch name
+const ISSUE_SLUG_MAX_CHARS: usize = 40;
+
+/// Where to open the pull request for `--create-pr`, resolved before anything is created
+enum PullRequestTarget {
+    GitHub {
+        client: github::GitHubClient,
+        owner: String,
+        repo: String,
+    },
+    BitbucketCloud {
+        client: bitbucket_api::BitbucketClient,
+        workspace: String,
+        repo: String,
+    },
+    BitbucketDataCenter {
+        client: bitbucket_data_center_api::BitbucketDataCenterClient,
+        project_key: String,
+        repo_slug: String,
+    },
+}
+
+pub fn run(branch_name: &str, options: &AddOptions) -> Result<()> {
     if branch_name.is_empty() {
         bail!("Error: Branch name is required\nUsage: gwt add <branch-name>");
     }
 
     // Determine git root and target path
-    let (git_working_dir, target_path, project_root) = determine_paths(branch_name)?;
+    let (git_working_dir, target_path, project_root) = determine_paths(branch_name, options.path, options.dir_name)?;
+    let paths = project::PathDisplay::new(options.path_style)?;
 
-    println!(
-        "{}",
-        format!("Preparing worktree (new branch '{}')", branch_name).cyan()
-    );
+    // A previous `gwt add` created this worktree but failed before finishing its setup
+    if let Some(state) = SetupState::load(&target_path).filter(|state| state.branch == branch_name) {
+        return resume_setup(&target_path, &project_root, state, options, &paths);
+    }
+    check_path_collision(&git_working_dir, &target_path, &project_root, branch_name)?;
 
-    // Get main branch from config
-    let main_branch = get_main_branch(&project_root)?;
+    // Checked up front so a typo'd or unfetched commit doesn't leave half a worktree behind
+    if let Some(sha) = options.base_sha {
+        if !git::commit_exists(&git_working_dir, sha) {
+            bail!(
+                "Commit '{}' does not exist in this repository. Run 'git fetch' if it was pushed recently.",
+                sha
+            );
+        }
+    }
+
+    if options.detach {
+        return add_detached(
+            branch_name,
+            options,
+            &git_working_dir,
+            &target_path,
+            &project_root,
+            &paths,
+        );
+    }
+    if options.orphan {
+        return add_orphan(
+            branch_name,
+            options,
+            &git_working_dir,
+            &target_path,
+            &project_root,
+            &paths,
+        );
+    }
+
+    // Fail on missing auth before creating anything
+    let pr_target = if options.create_pr {
+        Some(resolve_pull_request_target(&project_root)?)
+    } else {
+        None
+    };
+
+    ui::step(format!("Preparing worktree (new branch '{}')", branch_name));
+
+    let ProjectSettings {
+        main_branch,
+        remote,
+        default_track,
+        mirror,
+        ..
+    } = load_project_settings(&project_root)?;
+    // A mirror keeps the remote's branches as its own, so the base is the branch itself
+    let main_base = |main_branch: &str| {
+        if mirror {
+            main_branch.to_string()
+        } else {
+            git::remote_branch(&remote, main_branch)
+        }
+    };
 
     // Check if branch exists locally or remotely
-    let (local_exists, remote_exists) = git::branch_exists(&git_working_dir, branch_name)?;
+    let (local_exists, remote_exists) = git::branch_exists(&git_working_dir, &remote, branch_name)?;
 
-    // Create worktree based on branch existence
-    if local_exists {
-        println!(
-            "{}",
-            format!(
-                "Branch '{}' exists locally, checking out existing branch...",
-                branch_name
-            )
-            .yellow()
+    if options.from.is_some() && (local_exists || remote_exists) {
+        ui::warn(format!("Branch '{}' already exists, ignoring --from", branch_name));
+    }
+
+    // Ignoring the commit would quietly give a different tree than the one asked for
+    if options.base_sha.is_some() && (local_exists || remote_exists) {
+        bail!(
+            "Branch '{}' already exists; --base-sha only creates new branches",
+            branch_name
         );
-        git::execute_streaming(
+    }
+
+    if let Some(remote_ref) = options.track_remote {
+        if local_exists {
+            bail!(
+                "Branch '{}' already exists locally; --track-remote only creates new branches",
+                branch_name
+            );
+        }
+        if !git::remote_ref_exists(&git_working_dir, remote_ref)? {
+            let remote_name = remote_ref.split_once('/').map_or(remote_ref, |(name, _)| name);
+            bail!(
+                "Remote branch '{}' does not exist. Run 'git fetch {}' if it was pushed recently.",
+                remote_ref,
+                remote_name
+            );
+        }
+    }
+
+    // git refuses to check a branch out twice, e.g. one created in the main checkout. An orphan
+    // branch has no ref yet, so look whether or not the branch exists.
+    let worktrees = git::list_worktrees(Some(&git_working_dir))?;
+    if let Some(holder) = git::find_branch_worktree(&worktrees, branch_name) {
+        return add_from_checked_out_branch(branch_name, &holder.path, options, &paths);
+    }
+    // Another gwt (or git) may get there between the check above and ours
+    let explain = |error| checked_out_meanwhile(error, &git_working_dir, branch_name, &paths);
+
+    // Create worktree based on branch existence; `base` is what it was created from
+    let base = if local_exists {
+        ui::step(format!(
+            "Branch '{}' exists locally, checking out existing branch...",
+            branch_name
+        ));
+        worktree_add(
             &["worktree", "add", target_path.to_str().unwrap(), branch_name],
-            Some(&git_working_dir),
-        )?;
+            &git_working_dir,
+            &target_path,
+        )
+        .map_err(explain)?;
+        git::upstream_of(&git_working_dir, branch_name).unwrap_or_else(|| main_base(&main_branch))
+    } else if let Some(remote_ref) = options.track_remote {
+        ui::step(format!(
+            "Creating branch '{}' tracking '{}'...",
+            branch_name, remote_ref
+        ));
+        worktree_add(
+            &[
+                "worktree",
+                "add",
+                "--track",
+                "-b",
+                branch_name,
+                target_path.to_str().unwrap(),
+                remote_ref,
+            ],
+            &git_working_dir,
+            &target_path,
+        )
+        .map_err(explain)?;
+        remote_ref.to_string()
     } else if remote_exists {
-        println!(
-            "{}",
-            format!(
-                "Branch '{}' exists remotely, checking out remote branch...",
-                branch_name
-            )
-            .yellow()
-        );
-        git::execute_streaming(
+        ui::step(format!(
+            "Branch '{}' exists remotely, checking out remote branch...",
+            branch_name
+        ));
+        let base = git::remote_branch(&remote, branch_name);
+        ensure_fetched(&git_working_dir, &remote, branch_name, &base)?;
+        worktree_add(
             &[
                 "worktree",
                 "add",
                 target_path.to_str().unwrap(),
                 "-b",
                 branch_name,
-                &format!("origin/{}", branch_name),
+                &base,
             ],
-            Some(&git_working_dir),
-        )?;
+            &git_working_dir,
+            &target_path,
+        )
+        .map_err(explain)?;
+        base
     } else {
-        println!(
-            "{}",
-            format!("Creating new branch '{}' from 'origin/{}'...", branch_name, main_branch).cyan()
-        );
-        git::execute_streaming(
+        let base = match options.base_sha.or(options.from) {
+            Some(from) => from.to_string(),
+            None if mirror => main_base(&main_branch),
+            None => main_base(&resolve_main_branch(&git_working_dir, &remote, &main_branch)?),
+        };
+
+        // A commit has nothing to track
+        let track = options.base_sha.is_none() && options.track.or(default_track).unwrap_or(false);
+
+        ui::step(format!("Creating new branch '{}' from '{}'...", branch_name, base));
+        worktree_add(
             &[
                 "worktree",
                 "add",
-                "--no-track",
+                if track { "--track" } else { "--no-track" },
                 target_path.to_str().unwrap(),
                 "-b",
                 branch_name,
-                &format!("origin/{}", main_branch),
+                &base,
+            ],
+            &git_working_dir,
+            &target_path,
+        )
+        .map_err(explain)?;
+        base
+    };
+
+    // Success messages
+    ui::success(format!("Worktree created at: {}", paths.show(&target_path)));
+    ui::success(format!("Branch: {}", branch_name));
+
+    let state = SetupState::new(branch_name, &base);
+    state.save(&target_path)?;
+    finish_setup(&target_path, &project_root, state, pr_target, options)
+}
+
+/// Everything after creating the worktree. Each finished step is recorded (see `add_setup`), so
+/// when one fails, running the same `gwt add` again resumes from there.
+fn finish_setup(
+    target_path: &Path,
+    project_root: &Path,
+    mut state: SetupState,
+    pr_target: Option<PullRequestTarget>,
+    options: &AddOptions,
+) -> Result<()> {
+    let settings = load_project_settings(project_root)?;
+    let branch_name = state.branch.clone();
+
+    // Before the hooks, which may well need them (e.g. to build). An orphan branch has no files yet.
+    if !options.orphan && !state.is_done(SetupStep::Submodules) {
+        match settings.submodules {
+            Some(true) => init_submodules(target_path),
+            None if target_path.join(".gitmodules").exists() => ui::warn(
+                "This branch has submodules, which were left empty. Set 'submodules: true' in git-worktree-config.yaml to have gwt check them out (or 'submodules: false' to silence this).",
+            ),
+            _ => {}
+        }
+        state.complete(target_path, SetupStep::Submodules)?;
+    }
+
+    if !state.is_done(SetupStep::Hooks) {
+        hooks::execute_post_add_hooks(
+            target_path,
+            &[
+                ("branchName", &branch_name),
+                ("worktreePath", target_path.to_str().unwrap()),
+                ("baseBranch", &state.base_branch),
             ],
-            Some(&git_working_dir),
+            options.background_hooks,
         )?;
+        state.complete(target_path, SetupStep::Hooks)?;
     }
 
-    // Success messages
-    println!(
-        "{}",
-        format!("✓ Worktree created at: {}", target_path.display()).green()
-    );
-    println!("{}", format!("✓ Branch: {}", branch_name).green());
-
-    // Execute post-add hooks
-    hooks::execute_hooks(
-        "postAdd",
-        &target_path,
-        &[
-            ("branchName", branch_name),
-            ("worktreePath", target_path.to_str().unwrap()),
-        ],
-    )?;
+    if let Some(pr_target) = pr_target {
+        let base = options.base.unwrap_or(&settings.main_branch);
+        let title = options
+            .title
+            .or(options.issue.map(|issue| issue.title.as_str()))
+            .unwrap_or(&branch_name);
+        // GitHub links the issue and closes it when the pull request merges
+        let body = options
+            .issue
+            .map(|issue| format!("Closes #{}", issue.number))
+            .unwrap_or_default();
+
+        // Providers refuse a pull request without changes, so a fresh branch has to wait for its
+        // first commit. The step stays pending: the same `gwt add` then pushes and opens it.
+        let base_ref = match settings.mirror {
+            true => base.to_string(),
+            false => git::remote_branch(&settings.remote, base),
+        };
+        if git::count_commits_since(&base_ref, target_path)? == Some(0) {
+            ui::warn(format!(
+                "No pull request opened: '{}' has no commits on top of '{}' yet. Commit your work, then run 'gwt add {} --create-pr' again to push it and open one.",
+                branch_name, base_ref, branch_name
+            ));
+            return Ok(());
+        }
+
+        ui::step(format!("Pushing branch '{}' to {}...", branch_name, settings.remote));
+        git::execute_streaming(&["push", "-u", &settings.remote, &branch_name], Some(target_path))
+            .map_err(|e| anyhow::anyhow!("Failed to push '{}', pull request was not created: {}", branch_name, e))?;
+
+        let url = create_pull_request(&pr_target, &branch_name, base, title, &body, options.draft)?;
+        ui::success(format!("Pull request created: {}", url));
+        state.complete(target_path, SetupStep::PullRequest)?;
+    }
+
+    if options.open_existing_pr {
+        open_existing_pull_request(project_root, &branch_name, options.print_pr_url);
+    }
 
+    SetupState::clear(target_path);
     Ok(())
 }
 
-fn determine_paths(branch_name: &str) -> Result<(PathBuf, PathBuf, PathBuf)> {
-    let project_root = find_project_root()?;
-    let target_path = project_root.join(branch_name);
-    let git_working_dir = find_existing_worktree(&project_root)?;
+/// Offer to finish the setup a previous `gwt add` left incomplete; without a terminal, just do it
+fn resume_setup(
+    target_path: &Path,
+    project_root: &Path,
+    state: SetupState,
+    options: &AddOptions,
+    paths: &project::PathDisplay,
+) -> Result<()> {
+    ui::warn(format!(
+        "The worktree at {} was created, but setting it up did not finish",
+        paths.show(target_path)
+    ));
+    if io::stdin().is_terminal() && !ui::ask_yes_no("Finish setting it up now?")? {
+        bail!("Cancelled. Run 'gwt remove {}' to start over instead.", state.branch);
+    }
 
-    Ok((git_working_dir, target_path, project_root))
+    let pr_target = if options.create_pr && !state.is_done(SetupStep::PullRequest) {
+        Some(resolve_pull_request_target(project_root)?)
+    } else {
+        None
+    };
+
+    ui::step(format!("Resuming setup of '{}'...", state.branch));
+    let branch = state.branch.clone();
+    finish_setup(target_path, project_root, state, pr_target, options)?;
+    ui::success(format!(
+        "Worktree for '{}' is set up at {}",
+        branch,
+        paths.show(target_path)
+    ));
+    Ok(())
+}
+
+/// `branch` is checked out at `holder`: offer a new branch from its tip, or point at the holder
+fn add_from_checked_out_branch(
+    branch: &str,
+    holder: &Path,
+    options: &AddOptions,
+    paths: &project::PathDisplay,
+) -> Result<()> {
+    let hint = format!(
+        "Use 'gwt switch {}' to go there, or 'gwt add <new-branch> --from {}' to start a new branch from its tip.",
+        branch, branch
+    );
+    if !io::stdin().is_terminal() {
+        bail!(
+            "Branch '{}' is already checked out at {}.\n{}",
+            branch,
+            paths.show(holder),
+            hint
+        );
+    }
+
+    ui::warn(format!(
+        "Branch '{}' is already checked out at {}",
+        branch,
+        paths.show(holder)
+    ));
+    print!("New branch to create from its tip (leave empty to cancel): ");
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let new_branch = input.trim();
+    if new_branch.is_empty() {
+        bail!("Cancelled. {}", hint);
+    }
+
+    run(
+        new_branch,
+        &AddOptions {
+            from: Some(branch),
+            // Tracking a local branch would make `git pull` pull from it
+            track: Some(false),
+            ..options.clone()
+        },
+    )
+}
+
+/// `git worktree add` failed; when that's because `branch` got checked out elsewhere in the
+/// meantime, say where instead of passing on git's message
+fn checked_out_meanwhile(
+    error: anyhow::Error,
+    git_dir: &Path,
+    branch: &str,
+    paths: &project::PathDisplay,
+) -> anyhow::Error {
+    let holder = git::list_worktrees(Some(git_dir))
+        .ok()
+        .and_then(|worktrees| git::find_branch_worktree(&worktrees, branch).map(|wt| wt.path.clone()));
+    match holder {
+        Some(holder) => anyhow::anyhow!(
+            "Branch '{}' was checked out at {} while this worktree was being created.\nUse 'gwt switch {}' to go there.",
+            branch,
+            paths.show(&holder),
+            branch
+        ),
+        None => error,
+    }
 }
 
-fn find_project_root() -> Result<PathBuf> {
-    let current_dir = std::env::current_dir()?;
+/// Open (or print) the open pull request for `branch`, if there is one. The worktree exists by
+/// now, so a failed lookup is only a warning.
+fn open_existing_pull_request(project_root: &Path, branch: &str, print_url: bool) {
+    let pull_request = resolve_pull_request_target(project_root).and_then(|target| find_pull_request(target, branch));
 
-    // Search upward for git-worktree-config.yaml
-    let mut search_path = current_dir.clone();
-    loop {
-        if search_path.join("git-worktree-config.yaml").exists() {
-            return Ok(search_path);
+    let url = match pull_request {
+        Ok(Some(pr)) if matches!(pr.status.as_str(), "OPEN" | "DRAFT") => pr.url,
+        Ok(_) => {
+            ui::step(format!("No open pull request for '{}'", branch));
+            return;
+        }
+        Err(e) => {
+            ui::warn(format!("Could not look up a pull request for '{}': {}", branch, e));
+            return;
         }
+    };
 
-        if !search_path.pop() {
-            break;
+    if print_url {
+        println!("{}", url);
+    } else if let Err(e) = platform::open_url(&url) {
+        ui::warn(format!("Could not open {}: {}", url, e));
+    } else {
+        ui::success(format!("Opened pull request: {}", url));
+    }
+}
+
+#[tokio::main]
+async fn find_pull_request(target: PullRequestTarget, branch: &str) -> Result<Option<PullRequestInfo>> {
+    let (provider, owner_or_workspace, repo, github_client, bitbucket_client, bitbucket_data_center_client) =
+        match target {
+            PullRequestTarget::GitHub { client, owner, repo } => {
+                (Provider::Github, owner, repo, Some(client), None, None)
+            }
+            PullRequestTarget::BitbucketCloud {
+                client,
+                workspace,
+                repo,
+            } => (Provider::BitbucketCloud, workspace, repo, None, Some(client), None),
+            PullRequestTarget::BitbucketDataCenter {
+                client,
+                project_key,
+                repo_slug,
+            } => (
+                Provider::BitbucketDataCenter,
+                project_key,
+                repo_slug,
+                None,
+                None,
+                Some(client),
+            ),
+        };
+
+    list_helpers::fetch_pr_for_branch(
+        provider,
+        &owner_or_workspace,
+        &repo,
+        branch,
+        &github_client,
+        &bitbucket_client,
+        &bitbucket_data_center_client,
+    )
+    .await
+}
+
+/// Look up GitHub issue `number` and name a branch after it, e.g. `42-fix-login-bug`
+pub fn issue_branch(number: u32) -> Result<Issue> {
+    let (owner, repo) = github_repository("--from-issue")?;
+    let title = github::GitHubClient::new().get_issue_title(&owner, &repo, number)?;
+    ui::step(format!("Issue #{}: {}", number, title));
+
+    Ok(Issue {
+        number,
+        branch_name: issue_branch_name(number, &title),
+        title,
+    })
+}
+
+/// The branch of GitHub pull request `number`, for `--checkout-pr`. It is fetched first, so a
+/// branch pushed after the last fetch isn't mistaken for a new one.
+pub fn pull_request_branch(number: u32) -> Result<String> {
+    let (owner, repo) = github_repository("--checkout-pr")?;
+    let (branch, from_fork) = github::GitHubClient::new().get_pull_request_head(&owner, &repo, number)?;
+    if from_fork {
+        bail!(
+            "Pull request #{} comes from a fork, so '{}' isn't on this repository's remote. Check it out with 'gh pr checkout {}' instead.",
+            number,
+            branch,
+            number
+        );
+    }
+    ui::step(format!("Pull request #{}: {}", number, branch));
+
+    let remote = load_project_settings(&project::find_project_root()?)?.remote;
+    let git_dir = project::find_git_directory()?;
+    git::execute_capture(&["fetch", &remote, &branch], Some(&git_dir)).with_context(|| {
+        format!(
+            "Failed to fetch '{}' for pull request #{} from {}",
+            branch, number, remote
+        )
+    })?;
+    Ok(branch)
+}
+
+/// Owner and name of this project's GitHub repository; `flag` only works with GitHub
+fn github_repository(flag: &str) -> Result<(String, String)> {
+    let project_root = project::find_project_root()?;
+    let config = GitWorktreeConfig::load(&project_root.join("git-worktree-config.yaml"))?;
+    if config.source_control != Provider::Github {
+        bail!(
+            "{} only supports GitHub projects (this project uses {})",
+            flag,
+            config.source_control
+        );
+    }
+
+    let repo_url = config.resolved_repository_url();
+    github::GitHubClient::parse_github_url(&repo_url)
+        .ok_or_else(|| anyhow::anyhow!("Failed to parse GitHub repository URL: {}", repo_url))
+}
+
+fn issue_branch_name(number: u32, title: &str) -> String {
+    match utils::slugify(title, ISSUE_SLUG_MAX_CHARS) {
+        slug if slug.is_empty() => number.to_string(),
+        slug => format!("{}-{}", number, slug),
+    }
+}
+
+/// The main branch to base new branches on. A stale `mainBranch` (e.g. after a master→main
+/// rename) falls back to the remote's default branch with a warning; with no default to fall
+/// back to, fail with the available branches rather than git's opaque error.
+fn resolve_main_branch(git_working_dir: &Path, remote: &str, base: &str) -> Result<String> {
+    let (_, remote_exists) = git::branch_exists(git_working_dir, remote, base)?;
+    if remote_exists {
+        return Ok(base.to_string());
+    }
+
+    // remote HEAD can outlive the branch it points at, so check that one too
+    if let Some(default_branch) = git::remote_default_branch(git_working_dir, remote) {
+        if default_branch != base && git::branch_exists(git_working_dir, remote, &default_branch)?.1 {
+            ui::warn(format!(
+                "Base branch '{}' does not exist; using {}'s default branch '{}' instead. Update mainBranch in git-worktree-config.yaml.",
+                git::remote_branch(remote, base),
+                remote,
+                default_branch
+            ));
+            return Ok(default_branch);
         }
     }
 
-    // No config found, provide helpful error
-    if git::get_git_root()?.is_some() {
-        bail!("Found git repository but no git-worktree-config.yaml. This doesn't appear to be a worktree project.");
+    let mut message = format!("Base branch '{}' does not exist.", git::remote_branch(remote, base));
+    let available = git::list_remote_branches(git_working_dir, remote).unwrap_or_default();
+    if available.is_empty() {
+        message.push_str(&format!(
+            "\nNo branches found on {}; try 'git fetch {}' first.",
+            remote, remote
+        ));
     } else {
-        bail!("Not in a git repository or project root with git-worktree-config.yaml");
+        message.push_str(&format!("\nAvailable branches on {}:", remote));
+        for branch in &available {
+            message.push_str(&format!("\n  {}", branch));
+        }
     }
+    message.push_str("\nIf the main branch is wrong, update mainBranch in git-worktree-config.yaml.");
+
+    bail!(message)
 }
 
-fn find_existing_worktree(project_root: &Path) -> Result<PathBuf> {
-    let entries = fs::read_dir(project_root)?;
+fn resolve_pull_request_target(project_root: &Path) -> Result<PullRequestTarget> {
+    let config = GitWorktreeConfig::load(&project_root.join("git-worktree-config.yaml"))?;
+    let repo_url = &config.resolved_repository_url();
+
+    match config.source_control {
+        Provider::BitbucketCloud => {
+            let (workspace, repo) = bitbucket_api::extract_bitbucket_info_from_url(repo_url)
+                .ok_or_else(|| anyhow::anyhow!("Failed to parse Bitbucket repository URL: {}", repo_url))?;
+            let auth = bitbucket_auth::BitbucketAuth::new(workspace.clone(), repo.clone(), config.bitbucket_email)?;
+            auth.get_token()?;
+
+            Ok(PullRequestTarget::BitbucketCloud {
+                client: bitbucket_api::BitbucketClient::new(auth),
+                workspace,
+                repo,
+            })
+        }
+        Provider::BitbucketDataCenter => {
+            let (base_url, project_key, repo_slug) = bitbucket_data_center_auth::get_auth_from_config()?;
+            let auth = bitbucket_data_center_auth::BitbucketDataCenterAuth::new(
+                project_key.clone(),
+                repo_slug.clone(),
+                base_url.clone(),
+            )?;
+            auth.get_token()?;
 
-    for entry in entries {
-        let entry = entry?;
-        if entry.file_type()?.is_dir() {
-            let dir_path = entry.path();
-            if dir_path.join(".git").exists() {
-                return Ok(dir_path);
+            Ok(PullRequestTarget::BitbucketDataCenter {
+                client: bitbucket_data_center_api::BitbucketDataCenterClient::new(auth, base_url),
+                project_key,
+                repo_slug,
+            })
+        }
+        Provider::Github => {
+            let (owner, repo) = github::GitHubClient::parse_github_url(repo_url)
+                .ok_or_else(|| anyhow::anyhow!("Failed to parse GitHub repository URL: {}", repo_url))?;
+            let client = github::GitHubClient::new();
+            if !client.has_auth() {
+                bail!("GitHub authentication required to create a pull request. Run 'gh auth login' to authenticate.");
             }
+
+            Ok(PullRequestTarget::GitHub { client, owner, repo })
+        }
+    }
+}
+
+#[tokio::main]
+async fn create_pull_request(
+    target: &PullRequestTarget,
+    head: &str,
+    base: &str,
+    title: &str,
+    body: &str,
+    draft: bool,
+) -> Result<String> {
+    match target {
+        PullRequestTarget::GitHub { client, owner, repo } => {
+            client.create_pull_request(owner, repo, head, base, title, body, draft)
         }
+        PullRequestTarget::BitbucketCloud {
+            client,
+            workspace,
+            repo,
+        } => {
+            client
+                .create_pull_request(workspace, repo, head, base, title, draft)
+                .await
+        }
+        PullRequestTarget::BitbucketDataCenter {
+            client,
+            project_key,
+            repo_slug,
+        } => {
+            client
+                .create_pull_request(project_key, repo_slug, head, base, title, draft)
+                .await
+        }
+    }
+}
+
+/// Make sure the commit `remote_ref` points at is here, fetching just that branch when it isn't
+/// (a shallow or partial clone, or an interrupted fetch). A second try covers a flaky network.
+fn ensure_fetched(git_dir: &Path, remote: &str, branch: &str, remote_ref: &str) -> Result<()> {
+    if git::commit_exists(git_dir, remote_ref) {
+        return Ok(());
+    }
+
+    ui::step(format!(
+        "Commits of '{}' are missing locally, fetching them...",
+        remote_ref
+    ));
+    let refspec = format!("refs/heads/{}:refs/remotes/{}", branch, remote_ref);
+    let fetch = || git::execute_capture(&["fetch", remote, &refspec], Some(git_dir));
+    match fetch().or_else(|_| fetch()) {
+        Ok(_) if git::commit_exists(git_dir, remote_ref) => Ok(()),
+        Err(e) if e.to_string().contains("couldn't find remote ref") => bail!(
+            "Branch '{}' no longer exists on {}. Run 'git fetch --prune {}' to drop the stale '{}'.",
+            branch,
+            remote,
+            remote,
+            remote_ref
+        ),
+        Err(e) => bail!(
+            "Branch '{}' exists on {}, but its commits couldn't be fetched: {}",
+            branch,
+            remote,
+            e
+        ),
+        Ok(_) => bail!(
+            "Branch '{}' exists on {}, but its commits are still missing after fetching it",
+            branch,
+            remote
+        ),
+    }
+}
+
+/// Check out the submodules of a new worktree. It exists by now, so a failure is only a warning.
+fn init_submodules(worktree: &Path) {
+    ui::step("Initializing submodules...");
+    if let Err(error) = git::update_submodules(worktree) {
+        ui::warn(format!(
+            "Could not initialize submodules: {:#}\nRun 'git submodule update --init --recursive' in the worktree.",
+            error
+        ));
+    }
+}
+
+/// Run `git worktree add` with `args` after creating the missing parents of `target_path`
+/// (e.g. `feature/deep/` for `feature/deep/nested`), removing them again if git fails
+fn worktree_add(args: &[&str], git_dir: &Path, target_path: &Path) -> Result<()> {
+    let created = create_missing_parents(target_path)?;
+    let result = git::execute_streaming(args, Some(git_dir));
+    if let (Err(_), Some(topmost)) = (&result, created) {
+        remove_empty_parents(target_path, &topmost);
     }
+    result
+}
+
+/// Create the parent directories of `path`, returning the topmost one that had to be created
+fn create_missing_parents(path: &Path) -> Result<Option<PathBuf>> {
+    let Some(parent) = path.parent() else {
+        return Ok(None);
+    };
+    let topmost = parent
+        .ancestors()
+        .take_while(|dir| !dir.as_os_str().is_empty() && !dir.exists())
+        .last()
+        .map(Path::to_path_buf);
 
-    bail!("No existing worktrees found in project root. Create one first using gwt init.")
+    if topmost.is_some() {
+        fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    Ok(topmost)
 }
 
-fn get_main_branch(project_root: &Path) -> Result<String> {
+/// Remove the parents of `path` up to and including `topmost`, stopping at the first one
+/// that isn't empty
+fn remove_empty_parents(path: &Path, topmost: &Path) {
+    for dir in path.ancestors().skip(1) {
+        if fs::remove_dir(dir).is_err() || dir == topmost {
+            break;
+        }
+    }
+}
+
+fn add_detached(
+    name: &str,
+    options: &AddOptions,
+    git_working_dir: &Path,
+    target_path: &Path,
+    project_root: &Path,
+    paths: &project::PathDisplay,
+) -> Result<()> {
+    // "Current HEAD" means the worktree we're standing in, if any
+    let ref_dir = git::get_git_root()?.unwrap_or_else(|| git_working_dir.to_path_buf());
+    let git_ref = options.base_sha.or(options.git_ref).unwrap_or("HEAD");
+
+    ui::step(format!("Preparing detached worktree '{}' at '{}'", name, git_ref));
+    worktree_add(
+        &["worktree", "add", "--detach", target_path.to_str().unwrap(), git_ref],
+        &ref_dir,
+        target_path,
+    )?;
+
+    ui::success(format!("Worktree created at: {}", paths.show(target_path)));
+    ui::success(format!("Detached at: {}", git_ref));
+
+    let state = SetupState::new(name, git_ref);
+    state.save(target_path)?;
+    finish_setup(target_path, project_root, state, None, options)
+}
+
+fn add_orphan(
+    branch_name: &str,
+    options: &AddOptions,
+    git_working_dir: &Path,
+    target_path: &Path,
+    project_root: &Path,
+    paths: &project::PathDisplay,
+) -> Result<()> {
+    let ProjectSettings { remote, .. } = load_project_settings(project_root)?;
+    let (local_exists, remote_exists) = git::branch_exists(git_working_dir, &remote, branch_name)?;
+    if local_exists || remote_exists {
+        bail!(
+            "Branch '{}' already exists; --orphan only starts new branches",
+            branch_name
+        );
+    }
+
+    // `git worktree add --orphan` needs git 2.42, so switch a fresh detached worktree instead.
+    // Switching to an orphan branch empties the worktree; the branch appears with its first commit.
+    ui::step(format!("Preparing worktree (new orphan branch '{}')", branch_name));
+    worktree_add(
+        &["worktree", "add", "--detach", target_path.to_str().unwrap()],
+        git_working_dir,
+        target_path,
+    )?;
+    if let Err(error) = git::execute_capture(&["switch", "--orphan", branch_name], Some(target_path)) {
+        let _ = git::execute_capture(
+            &["worktree", "remove", "--force", target_path.to_str().unwrap()],
+            Some(git_working_dir),
+        );
+        return Err(error.context(format!("Failed to start orphan branch '{}'", branch_name)));
+    }
+
+    ui::success(format!("Worktree created at: {}", paths.show(target_path)));
+    ui::success(format!("Branch: {} (orphan, no commits yet)", branch_name));
+
+    // There is no base to report
+    let state = SetupState::new(branch_name, "");
+    state.save(target_path)?;
+    finish_setup(target_path, project_root, state, None, options)
+}
+
+fn determine_paths(
+    branch_name: &str,
+    path: Option<&Path>,
+    dir_name: Option<&str>,
+) -> Result<(PathBuf, PathBuf, PathBuf)> {
+    let project_root = project::find_project_root()?;
+    let git_working_dir = project::find_existing_worktree(&project_root)?;
+
+    // An explicit --path may point anywhere; git tracks the worktree wherever it lives
+    let target_path = match path {
+        Some(path) => std::env::current_dir()?.join(path),
+        None => project_root.join(dir_name.unwrap_or(branch_name)),
+    };
+
+    Ok((git_working_dir, target_path, project_root))
+}
+
+/// Refuse targets that clash with an existing worktree, e.g. adding `feature` when
+/// `feature/login` exists (or the reverse), which would nest one worktree inside another.
+fn check_path_collision(
+    git_working_dir: &Path,
+    target_path: &Path,
+    project_root: &Path,
+    branch_name: &str,
+) -> Result<()> {
+    let target = normalize_path(target_path);
+    let worktrees = git::list_worktrees(Some(git_working_dir))?;
+
+    for worktree in worktrees.iter().filter(|wt| !wt.bare) {
+        let existing = normalize_path(&worktree.path);
+        let owner = worktree
+            .branch
+            .as_deref()
+            .map(|b| b.strip_prefix("refs/heads/").unwrap_or(b))
+            .unwrap_or("a detached HEAD");
+
+        let problem = if existing == target {
+            format!("A worktree for '{}' already exists at {}", owner, existing.display())
+        } else if target.starts_with(&existing) {
+            format!(
+                "{} would be nested inside the worktree for '{}' at {}",
+                target.display(),
+                owner,
+                existing.display()
+            )
+        } else if existing.starts_with(&target) {
+            format!(
+                "{} would contain the existing worktree for '{}' at {}",
+                target.display(),
+                owner,
+                existing.display()
+            )
+        } else {
+            continue;
+        };
+
+        let suggestion = project_root.join(branch_name.replace('/', "-"));
+        bail!(
+            "{}.\nChoose another location with --path, e.g.: gwt add {} --path {}",
+            problem,
+            branch_name,
+            suggestion.display()
+        );
+    }
+
+    Ok(())
+}
+
+/// Canonicalize the longest existing prefix of `path`, so paths that don't exist yet
+/// compare correctly against the canonical paths git reports
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut existing = path.to_path_buf();
+    let mut rest = Vec::new();
+
+    while !existing.exists() {
+        match (existing.file_name().map(|n| n.to_os_string()), existing.parent()) {
+            (Some(name), Some(parent)) => {
+                rest.push(name);
+                existing = parent.to_path_buf();
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+
+    let mut normalized = existing.canonicalize().unwrap_or(existing);
+    normalized.extend(rest.iter().rev());
+    normalized
+}
+
+/// What `add` takes from the project config
+struct ProjectSettings {
+    main_branch: String,
+    remote: String,
+    default_track: Option<bool>,
+    mirror: bool,
+    /// `submodules` in the config; unset means "not decided", which warns about submodules
+    submodules: Option<bool>,
+}
+
+fn load_project_settings(project_root: &Path) -> Result<ProjectSettings> {
     let config_path = project_root.join("git-worktree-config.yaml");
     if config_path.exists() {
         let config = GitWorktreeConfig::load(&config_path)?;
-        Ok(config.main_branch)
+        Ok(ProjectSettings {
+            remote: config.remote().to_string(),
+            mirror: config.is_mirror(),
+            main_branch: config.main_branch,
+            default_track: config.default_track,
+            submodules: config.submodules,
+        })
     } else {
         // Fallback to detecting from git if no config
-        if let Some(git_root) = git::get_git_root()? {
-            git::get_default_branch(&git_root)
+        let main_branch = if let Some(git_root) = git::get_git_root()? {
+            git::get_default_branch(&git_root)?
         } else {
-            Ok("main".to_string())
-        }
+            "main".to_string()
+        };
+        Ok(ProjectSettings {
+            main_branch,
+            remote: DEFAULT_REMOTE.to_string(),
+            default_track: None,
+            mirror: false,
+            submodules: None,
+        })
     }
 }
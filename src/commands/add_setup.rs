@@ -0,0 +1,92 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::git;
+
+/// Kept in the worktree's git directory, so it never shows up in `git status`
+const MARKER_FILENAME: &str = "gwt-setup-incomplete";
+
+/// What `gwt add` does once the worktree exists
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SetupStep {
+    Submodules,
+    Hooks,
+    PullRequest,
+}
+
+/// Progress of setting up a new worktree, saved until every step has finished so that running
+/// the same `gwt add` again picks up where a failed step left off
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetupState {
+    /// Branch (or name, for detached worktrees) the worktree was added for
+    pub branch: String,
+    /// What it was created from, passed to `postAdd` hooks as `baseBranch`
+    pub base_branch: String,
+    done: Vec<SetupStep>,
+}
+
+impl SetupState {
+    pub fn new(branch: &str, base_branch: &str) -> Self {
+        SetupState {
+            branch: branch.to_string(),
+            base_branch: base_branch.to_string(),
+            done: Vec::new(),
+        }
+    }
+
+    /// The unfinished setup of the worktree at `worktree`, if it has one
+    pub fn load(worktree: &Path) -> Option<Self> {
+        let content = fs::read_to_string(marker_path(worktree)?).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    pub fn save(&self, worktree: &Path) -> Result<()> {
+        let path = marker_path(worktree).context("Could not find the new worktree's git directory")?;
+        fs::write(&path, serde_json::to_string(self)?).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn is_done(&self, step: SetupStep) -> bool {
+        self.done.contains(&step)
+    }
+
+    /// Record that `step` finished, so a later run skips it
+    pub fn complete(&mut self, worktree: &Path, step: SetupStep) -> Result<()> {
+        self.done.push(step);
+        self.save(worktree)
+    }
+
+    /// Setup is done; forget about it
+    pub fn clear(worktree: &Path) {
+        if let Some(path) = marker_path(worktree) {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+fn marker_path(worktree: &Path) -> Option<PathBuf> {
+    git::worktree_git_dir(worktree).map(|git_dir| git_dir.join(MARKER_FILENAME))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_setup_state_round_trip() {
+        let mut state = SetupState::new("feature/login", "origin/main");
+        state.done.push(SetupStep::Submodules);
+
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(
+            json,
+            r#"{"branch":"feature/login","baseBranch":"origin/main","done":["submodules"]}"#
+        );
+        let loaded: SetupState = serde_json::from_str(&json).unwrap();
+        assert!(loaded.is_done(SetupStep::Submodules));
+        assert!(!loaded.is_done(SetupStep::Hooks));
+    }
+}
@@ -1,14 +1,16 @@
+use super::list_identity;
 use crate::bitbucket_api::BitbucketClient;
 use crate::bitbucket_auth::{self, BitbucketAuth};
 use crate::bitbucket_data_center_api::BitbucketDataCenterClient;
 use crate::bitbucket_data_center_auth::{self, BitbucketDataCenterAuth};
 use crate::github::GitHubClient;
+use crate::ui;
 use anyhow::Result;
 
 pub fn run() -> Result<()> {
     let client = GitHubClient::new();
     if client.has_auth() {
-        println!("✓ You are already authenticated with GitHub via gh CLI");
+        ui::success("You are already authenticated with GitHub via gh CLI");
         println!("Run 'gh auth logout' to remove credentials if needed");
     } else {
         println!("Please authenticate with GitHub using: gh auth login");
@@ -30,6 +32,23 @@ pub async fn run_bitbucket_cloud(action: Option<BitbucketCloudAuthAction>) -> Re
             let client = BitbucketClient::new(auth);
             client.test_connection().await?;
         }
+        Some(BitbucketCloudAuthAction::Logout) => {
+            let (workspace, repo, email) = bitbucket_auth::get_auth_from_config()?;
+            let auth = BitbucketAuth::new(workspace.clone(), repo.clone(), email)?;
+            // The next token may belong to someone else, so `--mine` must look the user up again
+            list_identity::forget_bitbucket_cloud_user(&workspace, &repo);
+            if auth.remove_token()? {
+                ui::success(format!(
+                    "Removed the stored Bitbucket Cloud token for {}/{}",
+                    workspace, repo
+                ));
+            } else {
+                println!("No Bitbucket Cloud token is stored for {}/{}", workspace, repo);
+            }
+            if bitbucket_auth::token_in_environment() {
+                ui::warn("BITBUCKET_CLOUD_API_TOKEN is still set in your environment and will be used; unset it to log out completely");
+            }
+        }
     }
     Ok(())
 }
@@ -0,0 +1,404 @@
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+
+use super::init::validate_worktree_name;
+use crate::cli::{ConfigAction, Provider};
+use crate::config::{GitWorktreeConfig, Hooks, HOOK_TYPES};
+use crate::{bitbucket_api, bitbucket_data_center_api, github, ui};
+
+pub fn run(action: ConfigAction, assume_yes: bool) -> Result<()> {
+    let (config_path, mut config) = GitWorktreeConfig::find_config()?
+        .ok_or_else(|| anyhow::anyhow!("No git-worktree-config.yaml found. Run 'gwt init' first."))?;
+
+    let (hook_type, command) = match action {
+        ConfigAction::Validate => return validate_config(&config_path, &config),
+        ConfigAction::SetHook { hook_type, command } => (hook_type, Some(command)),
+        ConfigAction::ClearHook { hook_type } => (hook_type, None),
+    };
+
+    let hooks = config.hooks.get_or_insert_with(Hooks::default);
+    let Some(commands) = hooks.get_mut(&hook_type) else {
+        bail!(
+            "Unknown hook type '{}'. Expected one of: {}",
+            hook_type,
+            HOOK_TYPES.join(", ")
+        );
+    };
+
+    match command {
+        Some(command) => commands.get_or_insert_with(Vec::new).push(command),
+        None => *commands = None,
+    }
+
+    let commands = commands.clone().unwrap_or_default();
+
+    let before = fs::read_to_string(&config_path).context("Failed to read config file")?;
+    let after = config.to_yaml()?;
+    if before == after {
+        println!("{}", format!("No changes to {}", config_path.display()).dimmed());
+    } else {
+        println!("{}", format!("Changes to {}:", config_path.display()).bold());
+        print_diff(&line_diff(&before, &after));
+
+        // Prompts need someone to answer them; scripts keep working without --yes
+        let confirmations = config.confirmations.unwrap_or_default();
+        if !assume_yes
+            && confirmations.prompts_for(false)
+            && io::stdin().is_terminal()
+            && !ui::ask_yes_no("Write these changes?")?
+        {
+            println!("{}", "Config left unchanged.".yellow());
+            return Ok(());
+        }
+
+        fs::write(&config_path, after).context("Failed to write config file")?;
+        ui::success(format!("Updated {}", config_path.display()));
+    }
+
+    if commands.is_empty() {
+        println!("{}", format!("{} has no hooks", hook_type).dimmed());
+    } else {
+        println!("{}", format!("{} hooks:", hook_type).cyan());
+        for command in &commands {
+            println!("  - {}", command);
+        }
+    }
+
+    Ok(())
+}
+
+/// Something `gwt config validate` found wrong; only errors make it fail
+#[derive(Debug, PartialEq)]
+struct Problem {
+    error: bool,
+    /// Line in the config file, when the problem can be pinned to one
+    line: Option<usize>,
+    message: String,
+}
+
+/// Report every problem in the config, failing if any of them is an error. Syntax errors and
+/// unknown keys never get this far: loading the config already reports them with their line.
+fn validate_config(config_path: &Path, config: &GitWorktreeConfig) -> Result<()> {
+    let content = fs::read_to_string(config_path).context("Failed to read config file")?;
+    let project_root = config_path.parent().unwrap_or(Path::new("."));
+    let problems = find_problems(config, &content, project_root);
+
+    for problem in &problems {
+        let message = match problem.line {
+            Some(line) => format!("line {}: {}", line, problem.message),
+            None => problem.message.clone(),
+        };
+        if problem.error {
+            ui::error(message);
+        } else {
+            ui::warn(message);
+        }
+    }
+
+    let errors = problems.iter().filter(|problem| problem.error).count();
+    if errors > 0 {
+        bail!("{} has {} error(s)", config_path.display(), errors);
+    }
+    ui::success(format!("{} is valid", config_path.display()));
+    Ok(())
+}
+
+fn find_problems(config: &GitWorktreeConfig, content: &str, project_root: &Path) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let mut report =
+        |error: bool, line: Option<usize>, message: String| problems.push(Problem { error, line, message });
+
+    let repo_url = config.resolved_repository_url();
+    if repo_url.trim().is_empty() {
+        report(
+            true,
+            key_line(content, "repositoryUrl"),
+            "repositoryUrl is empty".to_string(),
+        );
+    } else {
+        // git can still clone and fetch such a URL; only pull request features need it parsed
+        let understood = match config.source_control {
+            Provider::Github => github::GitHubClient::parse_github_url(&repo_url).is_some(),
+            Provider::BitbucketCloud => bitbucket_api::extract_bitbucket_info_from_url(&repo_url).is_some(),
+            Provider::BitbucketDataCenter => {
+                bitbucket_data_center_api::extract_bitbucket_data_center_info_from_url(&repo_url).is_some()
+                    || github::GitHubClient::parse_github_url(&repo_url).is_some()
+            }
+        };
+        if !understood {
+            report(
+                false,
+                key_line(content, "repositoryUrl"),
+                format!(
+                    "repositoryUrl '{}' can't be read as a {} repository; pull request features won't work",
+                    repo_url, config.source_control
+                ),
+            );
+        }
+    }
+
+    if config.main_branch.trim().is_empty() {
+        report(true, key_line(content, "mainBranch"), "mainBranch is empty".to_string());
+    }
+
+    if let Some(name) = &config.main_worktree_name {
+        if let Err(e) = validate_worktree_name(name) {
+            report(true, key_line(content, "mainWorktreeName"), e.to_string());
+        } else if !project_root.join(name).is_dir() {
+            report(
+                false,
+                key_line(content, "mainWorktreeName"),
+                format!(
+                    "mainWorktreeName '{}' does not exist in {}",
+                    name,
+                    project_root.display()
+                ),
+            );
+        }
+    }
+
+    if config
+        .remote_name
+        .as_deref()
+        .is_some_and(|remote| remote.trim().is_empty() || remote.contains(char::is_whitespace))
+    {
+        report(
+            true,
+            key_line(content, "remoteName"),
+            "remoteName must be a remote name such as 'upstream'".to_string(),
+        );
+    }
+
+    if config.pr_limit == Some(0) {
+        report(
+            false,
+            key_line(content, "prLimit"),
+            "prLimit is 0, so no pull requests are fetched".to_string(),
+        );
+    }
+
+    if config
+        .protected_branches
+        .iter()
+        .flatten()
+        .any(|pattern| pattern.trim().is_empty())
+    {
+        report(
+            false,
+            key_line(content, "protectedBranches"),
+            "protectedBranches has an empty pattern".to_string(),
+        );
+    }
+
+    // Hooks run inside a worktree, so relative scripts are looked up in the main one
+    let main_worktree = project_root.join(config.main_worktree_name.as_deref().unwrap_or(&config.main_branch));
+    let hooks = config.effective_hooks().unwrap_or_default();
+    for hook_type in HOOK_TYPES {
+        for hook in hooks.get(hook_type).cloned().flatten().unwrap_or_default() {
+            let Some((script, relative)) = hook_script(&hook) else {
+                continue;
+            };
+            let line = content
+                .lines()
+                .position(|line| line.contains(hook.as_str()))
+                .map(|index| index + 1);
+            if relative {
+                if main_worktree.is_dir() && !main_worktree.join(&script).exists() {
+                    report(
+                        false,
+                        line,
+                        format!(
+                            "{} hook script '{}' is not in {}",
+                            hook_type,
+                            script.display(),
+                            main_worktree.display()
+                        ),
+                    );
+                }
+            } else if !script.exists() {
+                report(
+                    true,
+                    line,
+                    format!("{} hook script '{}' does not exist", hook_type, script.display()),
+                );
+            }
+        }
+    }
+
+    problems
+}
+
+/// The script a hook runs, when it runs one by path, and whether that path is relative.
+/// Commented hooks and paths built from `${...}` variables can't be checked ahead of time.
+fn hook_script(hook: &str) -> Option<(PathBuf, bool)> {
+    let program = hook.split_whitespace().next()?;
+    if program.starts_with('#') || program.contains("${") {
+        return None;
+    }
+
+    if let Some(rest) = program.strip_prefix("~/") {
+        let home = std::env::var_os("HOME")?;
+        Some((PathBuf::from(home).join(rest), false))
+    } else if program.starts_with("./") || program.starts_with("../") {
+        Some((PathBuf::from(program), true))
+    } else if Path::new(program).is_absolute() {
+        Some((PathBuf::from(program), false))
+    } else {
+        // A command looked up on PATH
+        None
+    }
+}
+
+/// 1-based line of a top-level `key:` in the config file
+fn key_line(content: &str, key: &str) -> Option<usize> {
+    content
+        .lines()
+        .position(|line| {
+            line.strip_prefix(key)
+                .is_some_and(|rest| rest.trim_start().starts_with(':'))
+        })
+        .map(|index| index + 1)
+}
+
+#[derive(Debug, PartialEq)]
+enum DiffLine<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Line-by-line diff of `old` and `new`, keeping their longest common subsequence as context
+fn line_diff<'a>(old: &'a str, new: &'a str) -> Vec<DiffLine<'a>> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // common[i][j] is the length of the longest common subsequence of old[i..] and new[j..]
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut diff = Vec::new();
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            diff.push(DiffLine::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if common[i + 1][j] >= common[i][j + 1] {
+            diff.push(DiffLine::Removed(old[i]));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new[j]));
+            j += 1;
+        }
+    }
+    diff.extend(old[i..].iter().map(|line| DiffLine::Removed(line)));
+    diff.extend(new[j..].iter().map(|line| DiffLine::Added(line)));
+    diff
+}
+
+fn print_diff(diff: &[DiffLine]) {
+    for line in diff {
+        match line {
+            DiffLine::Same(text) => println!("{}", format!("  {}", text).dimmed()),
+            DiffLine::Removed(text) => println!("{}", format!("- {}", text).red()),
+            DiffLine::Added(text) => println!("{}", format!("+ {}", text).green()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str) -> GitWorktreeConfig {
+        serde_yaml::from_str(content).unwrap()
+    }
+
+    #[test]
+    fn test_find_problems() {
+        let content = "repositoryUrl: git@github.com:owner/repo.git\nmainBranch: main\ncreatedAt: 2024-01-01T00:00:00Z\nsourceControl: github\nhooks:\n  postAdd:\n  - /no/such/setup.sh --fast\n  - '# /no/such/other.sh'\n  - npm install\nmainWorktreeName: a/b\nprLimit: 0\n";
+        let problems = find_problems(&parse(content), content, Path::new("/nonexistent"));
+        assert_eq!(
+            problems,
+            vec![
+                Problem {
+                    error: true,
+                    line: Some(10),
+                    message: "Invalid main worktree name 'a/b': use a plain directory name such as 'root'".to_string(),
+                },
+                Problem {
+                    error: false,
+                    line: Some(11),
+                    message: "prLimit is 0, so no pull requests are fetched".to_string(),
+                },
+                Problem {
+                    error: true,
+                    line: Some(7),
+                    message: "postAdd hook script '/no/such/setup.sh' does not exist".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_problems_url_must_match_provider() {
+        let content = "repositoryUrl: /srv/git/repo.git\nmainBranch: main\ncreatedAt: 2024-01-01T00:00:00Z\nsourceControl: bitbucket-cloud\n";
+        let problems = find_problems(&parse(content), content, Path::new("/nonexistent"));
+        assert_eq!(problems.len(), 1);
+        assert!(!problems[0].error);
+        assert_eq!(problems[0].line, Some(1));
+    }
+
+    #[test]
+    fn test_hook_script() {
+        assert_eq!(
+            hook_script("./scripts/setup.sh --fast"),
+            Some((PathBuf::from("./scripts/setup.sh"), true))
+        );
+        assert_eq!(
+            hook_script("/opt/setup.sh"),
+            Some((PathBuf::from("/opt/setup.sh"), false))
+        );
+        assert_eq!(hook_script("npm install"), None);
+        assert_eq!(hook_script("${worktreePath}/setup.sh"), None);
+        assert_eq!(hook_script("# ./setup.sh"), None);
+    }
+
+    #[test]
+    fn test_line_diff() {
+        let old = "mainBranch: main\nhooks:\n  postAdd:\n  - npm install\n";
+        let new = "mainBranch: main\nhooks:\n  postAdd:\n  - npm install\n  - npm test\n  postSwitch: null\n";
+        assert_eq!(
+            line_diff(old, new),
+            vec![
+                DiffLine::Same("mainBranch: main"),
+                DiffLine::Same("hooks:"),
+                DiffLine::Same("  postAdd:"),
+                DiffLine::Same("  - npm install"),
+                DiffLine::Added("  - npm test"),
+                DiffLine::Added("  postSwitch: null"),
+            ]
+        );
+
+        assert_eq!(
+            line_diff("a\nb\nc\n", "a\nx\nc\n"),
+            vec![
+                DiffLine::Same("a"),
+                DiffLine::Removed("b"),
+                DiffLine::Added("x"),
+                DiffLine::Same("c"),
+            ]
+        );
+    }
+}
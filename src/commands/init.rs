@@ -1,77 +1,299 @@
 use anyhow::{Context, Result};
-use colored::Colorize;
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use crate::cli::Provider;
-use crate::config::{GitWorktreeConfig, CONFIG_FILENAME};
+use crate::cli::{HookTemplate, Provider};
+use crate::config::{GitWorktreeConfig, Hooks, Layout, CONFIG_FILENAME, DEFAULT_REMOTE};
 use crate::git;
-use crate::{bitbucket_api, github};
+use crate::{bitbucket_api, github, ui, utils};
 
-pub fn run(repo_url: &str, provider: Option<Provider>) -> Result<()> {
-    // Detect or validate the repository provider
-    let detected_provider = detect_repository_provider(repo_url, provider)?;
+#[derive(Debug, Default)]
+pub struct InitOptions {
+    /// Repository provider, overriding detection from the URL
+    pub provider: Option<Provider>,
+    /// SSH host aliases in the URL mapped to their real hosts
+    pub host_aliases: BTreeMap<String, String>,
+    /// Directory name for the initial worktree (defaults to the default branch)
+    pub main_worktree_name: Option<String>,
+    /// Name for the cloned remote (defaults to `origin`)
+    pub remote_name: Option<String>,
+    /// Keep the clone in its repository-named directory instead of renaming it
+    pub no_clone_rename: bool,
+    /// Skip the `git ls-remote` access check before cloning
+    pub no_remote_check: bool,
+    /// Make a `git clone --mirror` and add the first worktree on top of it
+    pub mirror: bool,
+    /// Initialize submodules after cloning and record `submodules: true` for `gwt add`
+    pub submodules: bool,
+    /// Hooks the new config starts with
+    pub template: HookTemplate,
+}
 
-    println!("{}", format!("✓ Detected provider: {:?}", detected_provider).green());
+pub fn run(repo_url: &str, options: InitOptions) -> Result<()> {
+    let InitOptions {
+        provider,
+        host_aliases,
+        main_worktree_name,
+        remote_name,
+        no_clone_rename,
+        no_remote_check,
+        mirror,
+        submodules,
+        template,
+    } = options;
 
     // Extract repository name from URL
     let repo_name = extract_repo_name(repo_url)?;
-    let project_root = std::env::current_dir()?;
 
-    // Remove existing clone directory if it exists
-    if Path::new(&repo_name).exists() {
-        fs::remove_dir_all(&repo_name).context("Failed to remove existing directory")?;
+    if let Some(name) = &main_worktree_name {
+        validate_worktree_name(name)?;
     }
+    // --no-clone-rename records the clone's own directory as the main worktree
+    let main_worktree_name = main_worktree_name.or_else(|| no_clone_rename.then(|| repo_name.clone()));
 
-    // Clone the repository with streaming output (this is the key improvement!)
-    git::clone(repo_url, &repo_name)?;
+    // Detect or validate the repository provider
+    let detected_provider = detect_repository_provider(repo_url, provider, &host_aliases)?;
 
-    // Get the default branch name
-    let repo_path = PathBuf::from(&repo_name);
-    let default_branch = git::get_default_branch(&repo_path).context("Failed to get default branch")?;
+    ui::success(format!("Detected provider: {:?}", detected_provider));
 
-    // Rename directory to match branch name
-    let final_dir_name = &default_branch;
-    if Path::new(final_dir_name).exists() {
-        fs::remove_dir_all(final_dir_name).context("Failed to remove existing directory")?;
+    let project_root = std::env::current_dir()?;
+
+    // A quick ls-remote explains a bad URL or missing access before anything is deleted or cloned
+    if !no_remote_check {
+        ui::step(format!("Checking access to {}...", repo_url));
+        match git::ls_remote(repo_url).map_err(|stderr| (classify_remote_failure(&stderr), stderr)) {
+            Ok(()) => {}
+            // Without a credential helper only the clone can ask for a username and password
+            Err((RemoteFailure::CredentialsNeeded, _)) => {
+                ui::step("The remote asks for credentials, so the clone will prompt for them")
+            }
+            Err((_, stderr)) => anyhow::bail!("{}", describe_remote_failure(repo_url, &stderr)),
+        }
     }
 
-    fs::rename(&repo_name, final_dir_name).context("Failed to rename directory")?;
+    let remote = remote_name.as_deref().unwrap_or(DEFAULT_REMOTE);
+    let (default_branch, final_dir_name) = if mirror {
+        clone_mirror(repo_url, &repo_name, remote, main_worktree_name.as_deref())?
+    } else {
+        clone_standard(repo_url, &repo_name, remote, main_worktree_name.as_deref())?
+    };
+
+    if submodules {
+        ui::step("Initializing submodules...");
+        // The clone is done, so keep it and still write the config
+        if let Err(error) = git::update_submodules(&project_root.join(&final_dir_name)) {
+            ui::warn(format!(
+                "Could not initialize submodules: {:#}\nRun 'git submodule update --init --recursive' in {}.",
+                error, final_dir_name
+            ));
+        }
+    } else if project_root.join(&final_dir_name).join(".gitmodules").exists() {
+        ui::warn(format!(
+            "This repository has submodules, which were left empty. Run 'git submodule update --init --recursive' in {}, or init again with --submodules.",
+            final_dir_name
+        ));
+    }
 
     // Create configuration file
-    let config = GitWorktreeConfig::new(repo_url.to_string(), default_branch.clone(), detected_provider);
+    let mut config = GitWorktreeConfig::new(repo_url.to_string(), default_branch.clone(), detected_provider);
+    if !host_aliases.is_empty() {
+        config.host_aliases = Some(host_aliases);
+    }
+    config.main_worktree_name = main_worktree_name.clone();
+    config.remote_name = remote_name.filter(|name| name != DEFAULT_REMOTE);
+    config.layout = mirror.then_some(Layout::Mirror);
+    config.submodules = submodules.then_some(true);
+    config.hooks = Hooks::template(template);
     let config_path = project_root.join(CONFIG_FILENAME);
     config.save(&config_path).context("Failed to save configuration")?;
 
     // Print success messages
-    println!("{}", format!("✓ Repository cloned to: {}", final_dir_name).green());
-    println!("{}", format!("✓ Default branch: {}", default_branch).green());
-    println!("{}", format!("✓ Config saved to: {}", config_path.display()).green());
+    ui::success(format!("Repository cloned to: {}", final_dir_name));
+    ui::success(format!("Default branch: {}", default_branch));
+    ui::success(format!("Config saved to: {}", config_path.display()));
 
     // Post-init hooks removed - no longer needed
 
     Ok(())
 }
 
+/// Why `git ls-remote` could not reach a repository
+#[derive(Debug, PartialEq)]
+enum RemoteFailure {
+    /// Git wanted to prompt for a username or password; says nothing about the repository
+    CredentialsNeeded,
+    NotFound,
+    AuthRequired,
+    Network,
+    Other,
+}
+
+fn classify_remote_failure(stderr: &str) -> RemoteFailure {
+    let stderr = stderr.to_lowercase();
+    let mentions = |patterns: &[&str]| patterns.iter().any(|pattern| stderr.contains(pattern));
+    // HTTPS: "fatal: repository 'https://host/repo.git/' not found"
+    let repository_not_found = stderr
+        .lines()
+        .any(|line| line.starts_with("fatal: repository '") && line.ends_with("' not found"));
+
+    if mentions(&["terminal prompts disabled"]) {
+        RemoteFailure::CredentialsNeeded
+    } else if mentions(&[
+        "could not resolve host",
+        "could not resolve hostname",
+        "connection refused",
+        "connection timed out",
+        "operation timed out",
+        "network is unreachable",
+        "no route to host",
+    ]) {
+        RemoteFailure::Network
+    } else if mentions(&[
+        "permission denied (publickey",
+        "authentication failed for",
+        "host key verification failed",
+        "the requested url returned error: 401",
+        "the requested url returned error: 403",
+    ]) {
+        RemoteFailure::AuthRequired
+    } else if repository_not_found
+        || mentions(&[
+            "repository not found",
+            "does not appear to be a git repository",
+            "the project you were looking for could not be found",
+            "the requested url returned error: 404",
+        ])
+    {
+        RemoteFailure::NotFound
+    } else {
+        RemoteFailure::Other
+    }
+}
+
+fn describe_remote_failure(repo_url: &str, stderr: &str) -> String {
+    let reason = match classify_remote_failure(stderr) {
+        RemoteFailure::NotFound => format!(
+            "Repository not found: {}\nCheck the URL for typos. Private repositories also show up as not found when you lack access.",
+            repo_url
+        ),
+        RemoteFailure::CredentialsNeeded | RemoteFailure::AuthRequired => format!(
+            "Access denied to {}\nCheck your SSH key or credentials for this host, e.g. with 'ssh -T git@github.com' or 'gh auth login'.",
+            repo_url
+        ),
+        RemoteFailure::Network => format!(
+            "Could not reach {}\nCheck your network connection and the host name in the URL.",
+            repo_url
+        ),
+        RemoteFailure::Other => format!("Could not access {}", repo_url),
+    };
+
+    format!(
+        "{}\n\ngit said: {}\n(use --no-remote-check to skip this check)",
+        reason, stderr
+    )
+}
+
+/// Clone into `repo_name` and rename it to the main worktree's directory.
+/// Returns the default branch and that directory.
+fn clone_standard(
+    repo_url: &str,
+    repo_name: &str,
+    remote: &str,
+    main_worktree_name: Option<&str>,
+) -> Result<(String, String)> {
+    // Remove existing clone directory if it exists
+    if Path::new(repo_name).exists() {
+        fs::remove_dir_all(repo_name).context("Failed to remove existing directory")?;
+    }
+
+    // Clone the repository with streaming output (this is the key improvement!)
+    git::clone(repo_url, repo_name, remote)?;
+
+    // Get the default branch name
+    let repo_path = PathBuf::from(repo_name);
+    let default_branch = git::get_default_branch(&repo_path).context("Failed to get default branch")?;
+
+    // Rename directory to the configured name, or the branch name by default
+    let final_dir_name = main_worktree_name.unwrap_or(&default_branch).to_string();
+    if final_dir_name != repo_name {
+        if Path::new(&final_dir_name).exists() {
+            fs::remove_dir_all(&final_dir_name).context("Failed to remove existing directory")?;
+        }
+
+        fs::rename(repo_name, &final_dir_name).context("Failed to rename directory")?;
+    }
+
+    Ok((default_branch, final_dir_name))
+}
+
+/// Mirror-clone into `<repo_name>.git` and add a worktree for the default branch, which a
+/// mirror has no checkout of. Returns the default branch and the worktree's directory.
+fn clone_mirror(
+    repo_url: &str,
+    repo_name: &str,
+    remote: &str,
+    main_worktree_name: Option<&str>,
+) -> Result<(String, String)> {
+    let mirror_dir = format!("{}.git", repo_name);
+    if Path::new(&mirror_dir).exists() {
+        fs::remove_dir_all(&mirror_dir).context("Failed to remove existing directory")?;
+    }
+
+    git::clone_mirror(repo_url, &mirror_dir, remote)?;
+
+    let mirror_path = PathBuf::from(&mirror_dir);
+    let default_branch = git::get_default_branch(&mirror_path).context("Failed to get default branch")?;
+
+    let final_dir_name = main_worktree_name.unwrap_or(&default_branch).to_string();
+    let worktree_path = std::env::current_dir()?.join(&final_dir_name);
+    if worktree_path.exists() {
+        fs::remove_dir_all(&worktree_path).context("Failed to remove existing directory")?;
+    }
+
+    ui::step(format!("Adding worktree for '{}'...", default_branch));
+    git::execute_streaming(
+        &["worktree", "add", worktree_path.to_str().unwrap(), &default_branch],
+        Some(&mirror_path),
+    )
+    .context("Failed to add the initial worktree to the mirror")?;
+
+    Ok((default_branch, final_dir_name))
+}
+
+pub fn validate_worktree_name(name: &str) -> Result<()> {
+    if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\']) {
+        anyhow::bail!(
+            "Invalid main worktree name '{}': use a plain directory name such as 'root'",
+            name
+        );
+    }
+    Ok(())
+}
+
 fn extract_repo_name(repo_url: &str) -> Result<String> {
     let name = repo_url
         .split('/')
-        .last()
+        .next_back()
         .context("Invalid repository URL")?
         .strip_suffix(".git")
-        .unwrap_or_else(|| repo_url.split('/').last().unwrap());
+        .unwrap_or_else(|| repo_url.split('/').next_back().unwrap());
 
     Ok(name.to_string())
 }
 
-fn detect_repository_provider(repo_url: &str, provider: Option<Provider>) -> Result<Provider> {
-    let auto_detected = detect_provider_from_url(repo_url);
+fn detect_repository_provider(
+    repo_url: &str,
+    provider: Option<Provider>,
+    host_aliases: &BTreeMap<String, String>,
+) -> Result<Provider> {
+    let auto_detected = detect_provider_from_url(repo_url, host_aliases);
 
     match provider {
         // Use explicit provider if provided
         Some(explicit) => {
             if let Some(detected) = auto_detected {
-                if !providers_match(&detected, &explicit) {
+                if detected != explicit {
                     warn_provider_mismatch(&detected, &explicit);
                 }
             }
@@ -86,7 +308,9 @@ fn detect_repository_provider(repo_url: &str, provider: Option<Provider>) -> Res
     }
 }
 
-fn detect_provider_from_url(repo_url: &str) -> Option<Provider> {
+fn detect_provider_from_url(repo_url: &str, host_aliases: &BTreeMap<String, String>) -> Option<Provider> {
+    let repo_url = &utils::resolve_host_alias(repo_url, host_aliases);
+
     if github::GitHubClient::parse_github_url(repo_url).is_some() {
         Some(Provider::Github)
     } else if bitbucket_api::is_bitbucket_repository(repo_url) {
@@ -96,19 +320,11 @@ fn detect_provider_from_url(repo_url: &str) -> Option<Provider> {
     }
 }
 
-fn providers_match(a: &Provider, b: &Provider) -> bool {
-    std::mem::discriminant(a) == std::mem::discriminant(b)
-}
-
 fn warn_provider_mismatch(detected: &Provider, explicit: &Provider) {
-    println!(
-        "{}",
-        format!(
-            "⚠ URL suggests {:?} but --provider {:?} specified. Using {:?}.",
-            detected, explicit, explicit
-        )
-        .yellow()
-    );
+    ui::warn(format!(
+        "URL suggests {:?} but --provider {:?} specified. Using {:?}.",
+        detected, explicit, explicit
+    ));
 }
 
 fn create_provider_error(repo_url: &str) -> anyhow::Error {
@@ -121,3 +337,73 @@ fn create_provider_error(repo_url: &str) -> anyhow::Error {
         repo_url
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_provider_from_aliased_url() {
+        let aliases = BTreeMap::from([
+            ("github-work".to_string(), "github.com".to_string()),
+            ("bitbucket-personal".to_string(), "bitbucket.org".to_string()),
+        ]);
+
+        assert!(matches!(
+            detect_provider_from_url("git@github-work:org/repo.git", &aliases),
+            Some(Provider::Github)
+        ));
+        assert!(matches!(
+            detect_provider_from_url("git@bitbucket-personal:workspace/repo.git", &aliases),
+            Some(Provider::BitbucketCloud)
+        ));
+        assert!(detect_provider_from_url("git@github-work:org/repo.git", &BTreeMap::new()).is_none());
+    }
+
+    #[test]
+    fn test_classify_remote_failure() {
+        assert_eq!(
+            classify_remote_failure("ERROR: Repository not found.\nfatal: Could not read from remote repository."),
+            RemoteFailure::NotFound
+        );
+        assert_eq!(
+            classify_remote_failure("fatal: '/tmp/nope' does not appear to be a git repository"),
+            RemoteFailure::NotFound
+        );
+        assert_eq!(
+            classify_remote_failure("git@github.com: Permission denied (publickey)."),
+            RemoteFailure::AuthRequired
+        );
+        assert_eq!(
+            classify_remote_failure(
+                "fatal: could not read Username for 'https://github.com': terminal prompts disabled"
+            ),
+            RemoteFailure::CredentialsNeeded
+        );
+        assert_eq!(
+            classify_remote_failure(
+                "fatal: unable to access 'https://example.com/repo.git/': The requested URL returned error: 403"
+            ),
+            RemoteFailure::AuthRequired
+        );
+        assert_eq!(
+            classify_remote_failure("remote: Not Found\nfatal: repository 'https://example.com/repo.git/' not found"),
+            RemoteFailure::NotFound
+        );
+        assert_eq!(
+            classify_remote_failure(
+                "fatal: unable to access 'https://example.com/team-403/repo.git/': SSL certificate problem: unable to get local issuer certificate"
+            ),
+            RemoteFailure::Other
+        );
+        assert_eq!(
+            classify_remote_failure("ssh: Could not resolve hostname gihub.com: Name or service not known"),
+            RemoteFailure::Network
+        );
+        assert_eq!(
+            classify_remote_failure("fatal: unable to access 'https://example.com/': Connection refused"),
+            RemoteFailure::Network
+        );
+        assert_eq!(classify_remote_failure("fatal: something else"), RemoteFailure::Other);
+    }
+}
@@ -1,16 +1,105 @@
-use anyhow::{bail, Result};
-use colored::Colorize;
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use colored::{ColoredString, Colorize};
 use std::fs;
-use std::path::PathBuf;
+use std::future::Future;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::time::Instant;
 
-use super::list_helpers::{clean_branch_name, fetch_pr_for_branch, PullRequestInfo, extract_bitbucket_cloud_url, extract_bitbucket_data_center_url};
+use super::list_format::{Field, Template};
+use super::list_helpers::{
+    clean_branch_name, extract_bitbucket_cloud_url, extract_bitbucket_data_center_url, fetch_pr_for_branch,
+    PullRequestInfo,
+};
+use super::list_identity;
+use super::list_json::{ListJson, ListJsonLine, PullRequestJson, RemotePullRequestJson, WorktreeJson, JSON_VERSION};
+use super::remove;
+use crate::bitbucket_data_center_api::BitbucketDataCenterError;
+use crate::cli::Provider;
+use crate::rate_limit::RateLimited;
 use crate::{
-    bitbucket_api, bitbucket_auth, bitbucket_data_center_api, bitbucket_data_center_auth, config, git, github,
+    bitbucket_api, bitbucket_auth, bitbucket_data_center_api, bitbucket_data_center_auth, config, git, github, project,
+    ui, utils,
 };
 
+#[derive(Debug, Default)]
+pub struct ListOptions {
+    /// Maximum number of pull requests to fetch (falls back to prLimit, then the default)
+    pub pr_limit: Option<u32>,
+    /// Show commits not in this rev; `Some(None)` means `<remote>/<main_branch>`
+    pub since: Option<Option<String>>,
+    /// Only show worktrees with commits not in the `since` rev
+    pub since_only: bool,
+    /// Never truncate pull request titles to the terminal width
+    pub full_titles: bool,
+    /// Only report the worktree that contains this path
+    pub contains: Option<PathBuf>,
+    /// Only show branch worktrees without a pull request, skipping the main and protected branches
+    pub no_pr_only: bool,
+    /// Overrides `pathStyle` for the paths we print
+    pub path_style: Option<config::PathStyle>,
+    /// One aligned line per worktree instead of the multi-line layout
+    pub compact: bool,
+    /// Print a JSON object (see `list_json`) instead of the human-readable layout
+    pub json: bool,
+    /// Print one JSON object per line as each worktree is ready, then the remote pull requests
+    pub json_lines: bool,
+    /// Print one line per worktree from this template instead of the layout; no remote pull requests
+    pub format: Option<Template>,
+    /// Stop fetching pull request information after this long in total
+    pub fetch_prs_timeout: Option<Duration>,
+    /// Show the bare repository entry, which is hidden except in JSON output
+    pub include_bare: bool,
+    /// List every project found under the current directory, one after another
+    pub all: bool,
+    /// Leave out the one-line count of worktrees and pull requests at the end
+    pub no_summary: bool,
+    /// Show who made the checked-out commit and when (always included in JSON)
+    pub last_commit: bool,
+    /// Flag worktrees with untracked files (always included in JSON)
+    pub untracked: bool,
+    /// Branch globs to skip pull requests for; replaces `listIgnoreBranches` when not empty
+    pub no_pr_for: Vec<String>,
+    /// Only show worktrees and remote pull requests whose pull request the current user opened
+    pub mine: bool,
+    /// Flag pull requests with merge conflicts (GitHub only)
+    pub conflicts: bool,
+}
+
+impl ListOptions {
+    /// Whether the output is JSON in either form
+    fn json_output(&self) -> bool {
+        self.json || self.json_lines
+    }
+}
+
 struct WorktreeDisplay {
     branch: String,
+    path: PathBuf,
+    head: String,
+    bare: bool,
+    detached: bool,
+    /// Commits ahead of the `--since` rev, when requested and resolvable
+    commits_since: Option<u32>,
+    /// Commits on the branch that no remote has yet
+    unpushed: u32,
+    /// Branch this one tracks, e.g. `origin/alice/feature`
+    upstream: Option<String>,
     pr_info: Option<PullRequestInfo>,
+    /// Set when the worktree lives outside the project root (e.g. created with raw git)
+    outside_path: Option<PathBuf>,
+    /// The branch checked out here no longer exists (deleted with raw git)
+    orphaned: bool,
+    /// Only looked up with `--last-commit` or `--json`
+    last_commit: Option<git::LastCommit>,
+    /// Why the pull request needs attention, e.g. "checks failing"; empty when it doesn't
+    attention: Vec<&'static str>,
+    /// Lock reason (empty without one) when the worktree is locked
+    locked: Option<String>,
+    /// Only counted with `--untracked` or `--json`
+    untracked: Option<u32>,
 }
 
 struct RemotePullRequest {
@@ -19,119 +108,133 @@ struct RemotePullRequest {
 }
 
 #[tokio::main]
-pub async fn run() -> Result<()> {
-    // Find a git directory to work with
-    let git_dir = find_git_directory()?;
+pub async fn run(options: &ListOptions) -> Result<()> {
+    if options.all {
+        list_all_projects(options).await
+    } else {
+        let found_config = config::GitWorktreeConfig::find_config()?;
+        let git_dir = project::git_directory_for(found_config.as_ref())?;
+        list_project(options, &git_dir, found_config).await
+    }
+}
+
+/// `--all`: run the single-project listing from each project root under the current directory
+async fn list_all_projects(options: &ListOptions) -> Result<()> {
+    let current_dir = std::env::current_dir()?;
+    let project_roots = project::find_project_roots(&current_dir)?;
+    if project_roots.is_empty() {
+        bail!(
+            "No git-worktree-config.yaml found in or below {}",
+            current_dir.display()
+        );
+    }
+
+    for (index, project_root) in project_roots.iter().enumerate() {
+        if index > 0 {
+            println!();
+        }
+        let name = project_root.strip_prefix(&current_dir).unwrap_or(project_root);
+        let name = if name.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            name
+        };
+        println!("{}", format!("== {} ==", name.display()).bold());
+        println!();
+
+        // Each project is resolved from its own root; the current directory only decides what to list
+        let result = async {
+            let config_path = project_root.join(config::CONFIG_FILENAME);
+            let found_config = Some((config_path.clone(), config::GitWorktreeConfig::load(&config_path)?));
+            let git_dir = project::git_directory_for(found_config.as_ref())?;
+            list_project(options, &git_dir, found_config).await
+        }
+        .await;
 
+        // One broken project shouldn't hide the others
+        if let Err(error) = result {
+            ui::error(format!("{}: {:#}", project_root.display(), error));
+        }
+    }
+
+    Ok(())
+}
+
+/// List the project whose configuration (if any) is `found_config`, running git from `git_dir`
+async fn list_project(
+    options: &ListOptions,
+    git_dir: &Path,
+    found_config: Option<(PathBuf, config::GitWorktreeConfig)>,
+) -> Result<()> {
     // Get the list of worktrees
-    let worktrees = git::list_worktrees(Some(&git_dir))?;
+    let worktrees = git::list_worktrees(Some(git_dir))?;
 
     if worktrees.is_empty() {
         println!("{}", "No worktrees found.".yellow());
         return Ok(());
     }
 
-    // Try to get GitHub/Bitbucket info automatically
-    let (github_client, bitbucket_client, bitbucket_data_center_client, repo_info) = {
-        let github_client = github::GitHubClient::new();
-        let mut bitbucket_client: Option<bitbucket_api::BitbucketClient> = None;
-        let mut bitbucket_data_center_client: Option<bitbucket_data_center_api::BitbucketDataCenterClient> = None;
-
-        if let Some((_, config)) = config::GitWorktreeConfig::find_config()? {
-            let repo_url = &config.repository_url;
-
-            // Use the configured sourceControl instead of URL pattern matching
-            match config.source_control.as_str() {
-                "bitbucket-cloud" => {
-                    if let Some((workspace, repo)) = bitbucket_api::extract_bitbucket_info_from_url(repo_url) {
-                        // Try to get Bitbucket Cloud auth
-                        if let Ok(auth) = bitbucket_auth::BitbucketAuth::new(
-                            workspace.clone(),
-                            repo.clone(),
-                            config.bitbucket_email.clone(),
-                        ) {
-                            if auth.has_stored_token() {
-                                bitbucket_client = Some(bitbucket_api::BitbucketClient::new(auth));
-                            }
-                        }
-                        (
-                            Some(github_client),
-                            bitbucket_client,
-                            None,
-                            Some(("bitbucket-cloud".to_string(), workspace, repo)),
-                        )
-                    } else {
-                        (Some(github_client), None, None, None)
-                    }
-                }
-                "bitbucket-data-center" => {
-                    // Always use get_auth_from_config for bitbucket-data-center since it can derive the API URL
-                    if let Ok((base_url, project_key, repo_slug)) = bitbucket_data_center_auth::get_auth_from_config() {
-                        if let Ok(auth) = bitbucket_data_center_auth::BitbucketDataCenterAuth::new(
-                            project_key.clone(),
-                            repo_slug.clone(),
-                            base_url.clone(),
-                        ) {
-                            if auth.get_token().is_ok() {
-                                bitbucket_data_center_client = Some(
-                                    bitbucket_data_center_api::BitbucketDataCenterClient::new(auth, base_url),
-                                );
-                            }
-                        }
-                        (
-                            Some(github_client),
-                            None,
-                            bitbucket_data_center_client,
-                            Some(("bitbucket-data-center".to_string(), project_key, repo_slug)),
-                        )
-                    } else {
-                        // Could not get auth config - extract repo info for display but no client
-                        let (owner, repo) = github::GitHubClient::parse_github_url(repo_url)
-                            .unwrap_or_else(|| ("".to_string(), "".to_string()));
-                        if !owner.is_empty() && !repo.is_empty() {
-                            (
-                                Some(github_client),
-                                None,
-                                None,
-                                Some(("bitbucket-data-center".to_string(), owner, repo)),
-                            )
-                        } else {
-                            (Some(github_client), None, None, None)
-                        }
-                    }
-                }
-                "github" | _ => {
-                    // Try GitHub
-                    let (owner, repo) = github::GitHubClient::parse_github_url(repo_url)
-                        .unwrap_or_else(|| ("".to_string(), "".to_string()));
-
-                    if !owner.is_empty() && !repo.is_empty() {
-                        (
-                            Some(github_client),
-                            None,
-                            None,
-                            Some(("github".to_string(), owner, repo)),
-                        )
-                    } else {
-                        (Some(github_client), None, None, None)
-                    }
-                }
-            }
-        } else {
-            (Some(github_client), None, None, None)
-        }
+    let paths = project::PathDisplay::for_project(options.path_style, found_config.as_ref());
+    if let Some(path) = &options.contains {
+        return show_containing_worktree(&worktrees, path, &paths);
+    }
+
+    let project_root = found_config
+        .as_ref()
+        .and_then(|(config_path, _)| config_path.parent().map(Path::to_path_buf));
+
+    // The command line takes precedence over the project configuration
+    let pr_limit = options
+        .pr_limit
+        .or_else(|| found_config.as_ref().and_then(|(_, config)| config.pr_limit))
+        .unwrap_or(config::DEFAULT_PR_LIMIT);
+
+    // Pull requests come from the configured provider only; when it can't be used, say why once
+    let pr_source = match &found_config {
+        Some((_, config)) => connect_pr_source(config, pr_limit).map(Some),
+        None => Ok(None),
+    };
+    let (github_client, bitbucket_client, bitbucket_data_center_client, repo_info, pr_unavailable) = match pr_source {
+        Ok(Some(source)) => (
+            source.github_client,
+            source.bitbucket_client,
+            source.bitbucket_data_center_client,
+            Some((source.provider, source.owner_or_workspace, source.repo)),
+            None,
+        ),
+        Ok(None) => (None, None, None, None, None),
+        Err(reason) => (None, None, None, None, Some(reason)),
     };
+    let has_pr_info = repo_info.is_some();
 
-    let has_pr_info = repo_info.is_some()
-        && match &repo_info {
-            Some((platform, _, _)) => match platform.as_str() {
-                "github" => github_client.as_ref().map(|c| c.has_auth()).unwrap_or(false),
-                "bitbucket-cloud" => bitbucket_client.is_some(),
-                "bitbucket-data-center" => bitbucket_data_center_client.is_some(),
-                _ => false,
-            },
-            None => false,
-        };
+    if options.no_pr_only && !has_pr_info {
+        bail!(
+            "--no-pr-only needs pull request information: {}",
+            pr_unavailable.as_deref().unwrap_or("no git-worktree-config.yaml found")
+        );
+    }
+    if options.mine && !has_pr_info {
+        bail!(
+            "--mine needs pull request information: {}",
+            pr_unavailable.as_deref().unwrap_or("no git-worktree-config.yaml found")
+        );
+    }
+
+    let current_user = match &repo_info {
+        Some((platform, owner_or_workspace, repo)) if options.mine => Some(
+            list_identity::current_user(
+                *platform,
+                owner_or_workspace,
+                repo,
+                &github_client,
+                &bitbucket_client,
+                &bitbucket_data_center_client,
+            )
+            .await
+            .context("Could not tell which pull requests are yours")?,
+        ),
+        _ => None,
+    };
 
     // Get local branch names for filtering
     let local_branches: Vec<String> = worktrees
@@ -139,36 +242,116 @@ pub async fn run() -> Result<()> {
         .filter_map(|wt| wt.branch.as_ref().map(|b| clean_branch_name(b)))
         .collect();
 
+    // Bot branches and the like: no lookups for their worktrees, and their PRs aren't listed
+    let ignored_patterns = pr_ignored_patterns(options, found_config.as_ref().map(|(_, config)| config));
+    let pr_ignored = |branch: &str| {
+        ignored_patterns
+            .iter()
+            .any(|pattern| utils::glob_match(pattern, branch))
+    };
+
+    let is_mirror = found_config.as_ref().is_some_and(|(_, config)| config.is_mirror());
+    let attention_triggers = found_config
+        .as_ref()
+        .map_or(config::AttentionTrigger::ALL, |(_, config)| config.attention_triggers());
+
+    // --since-only implies --since with the default rev
+    let since_rev = if options.since.is_some() || options.since_only {
+        Some(
+            options
+                .since
+                .clone()
+                .flatten()
+                .unwrap_or_else(|| match found_config.as_ref() {
+                    Some((_, config)) if config.is_mirror() => config.main_branch.clone(),
+                    Some((_, config)) => git::remote_branch(config.remote(), &config.main_branch),
+                    None => git::remote_branch(config::DEFAULT_REMOTE, "main"),
+                }),
+        )
+    } else {
+        None
+    };
+
+    // A failed lookup leaves the remaining worktrees without PR info, which would look like "no PR"
+    let needs_every_pr = if options.no_pr_only {
+        Some("Could not determine which worktrees have no pull request")
+    } else if current_user.is_some() {
+        Some("Could not determine which worktrees have your pull requests")
+    } else {
+        None
+    };
+    // Filtering on PRs must not emit lines it may have to take back, so those wait for every lookup
+    let stream_json_lines = options.json_lines && needs_every_pr.is_none();
+
     // Convert to display format
     let mut display_worktrees: Vec<WorktreeDisplay> = Vec::new();
+    let mut pr_fetch_error: Option<anyhow::Error> = None;
+    let mut pr_fetch_timed_out = false;
+    // Unknown when listing branches fails; then nothing is reported as orphaned
+    let existing_branches = git::local_branch_refs(git_dir).ok();
+    let pr_deadline = options.fetch_prs_timeout.map(|timeout| Instant::now() + timeout);
 
     for wt in &worktrees {
+        if wt.bare && !options.include_bare && !options.json_output() {
+            continue;
+        }
+
+        let commits_since = match &since_rev {
+            Some(rev) if !wt.bare => git::count_commits_since(rev, &wt.path).unwrap_or(None),
+            _ => None,
+        };
+
+        if options.since_only && commits_since.unwrap_or(0) == 0 {
+            continue;
+        }
+
         let branch = wt.branch.as_ref().map(|b| clean_branch_name(b)).unwrap_or_else(|| {
             if wt.bare {
                 "(bare)".to_string()
             } else {
-                wt.head.chars().take(8).collect()
+                format!("(detached at {})", wt.head.chars().take(8).collect::<String>())
             }
         });
 
-        // Fetch PR info if available
-        let pr_info = if has_pr_info && !wt.bare && branch != "(bare)" {
+        // A branch without commits has no ref yet, but it hasn't been deleted either
+        let orphaned = match (&wt.branch, &existing_branches) {
+            (Some(branch_ref), Some(existing)) => !existing.contains(branch_ref) && !git::is_unborn_branch(wt),
+            _ => false,
+        };
+        let has_branch = wt.branch.is_some() && !orphaned;
+
+        // Fetch PR info if available; bare, detached and orphaned worktrees have no branch to look up,
+        // and ignored branches aren't worth a request.
+        // After a Data Center server turned out unreachable or refused the token, stop asking: it would
+        // otherwise fail (or time out) once per worktree. Other failures only cost that worktree its PR.
+        let pr_lookups_stopped = pr_fetch_error.as_ref().is_some_and(stops_pr_lookups);
+        let pr_info = if has_pr_info && has_branch && !pr_ignored(&branch) && !pr_lookups_stopped && !pr_fetch_timed_out
+        {
             match &repo_info {
                 Some((platform, owner_or_workspace, repo)) => {
-                    let pr_result = fetch_pr_for_branch(
-                        platform,
-                        owner_or_workspace,
-                        repo,
-                        &branch,
-                        &github_client,
-                        &bitbucket_client,
-                        &bitbucket_data_center_client,
+                    let pr_result = within_deadline(
+                        pr_deadline,
+                        fetch_pr_for_branch(
+                            *platform,
+                            owner_or_workspace,
+                            repo,
+                            &branch,
+                            &github_client,
+                            &bitbucket_client,
+                            &bitbucket_data_center_client,
+                        ),
                     )
                     .await;
 
                     match pr_result {
-                        Ok(info) => info,
-                        Err(_) => None,
+                        Some(result) => result.unwrap_or_else(|e| {
+                            pr_fetch_error.get_or_insert(e);
+                            None
+                        }),
+                        None => {
+                            pr_fetch_timed_out = true;
+                            None
+                        }
                     }
                 }
                 None => None,
@@ -177,32 +360,145 @@ pub async fn run() -> Result<()> {
             None
         };
 
+        // Worktrees that will never get a pull request of their own aren't "forgotten"
+        if options.no_pr_only
+            && (pr_info.is_some() || !has_branch || pr_ignored(&branch) || is_long_lived(&branch, &found_config))
+        {
+            continue;
+        }
+        if let Some(user) = &current_user {
+            if !pr_info.as_ref().is_some_and(|info| info.is_authored_by(user)) {
+                continue;
+            }
+        }
+
+        let (unpushed, upstream) = if has_branch {
+            (
+                // A mirror has no remote-tracking branches to compare against
+                if is_mirror {
+                    0
+                } else {
+                    git::count_unpushed_commits(&wt.path).unwrap_or(0)
+                },
+                git::upstream_of(&wt.path, &branch),
+            )
+        } else {
+            (0, None)
+        };
+
+        let mut attention = pr_info
+            .as_ref()
+            .map(|info| info.attention_reasons(attention_triggers))
+            .unwrap_or_default();
+        if options.conflicts && pr_info.as_ref().is_some_and(PullRequestInfo::has_conflicts) {
+            attention.push("conflicts");
+        }
+
+        let uses = |fields: &[Field]| {
+            options
+                .format
+                .as_ref()
+                .is_some_and(|template| fields.iter().any(|field| template.uses(*field)))
+        };
+
+        let last_commit =
+            if (options.last_commit || options.json_output() || uses(&[Field::CommitAuthor, Field::CommitDate]))
+                && !wt.bare
+            {
+                git::last_commit(&wt.path)
+            } else {
+                None
+            };
+
+        // Untracked files are what `git worktree remove --force` silently throws away
+        let untracked = if (options.untracked || options.json_output() || uses(&[Field::Untracked])) && !wt.bare {
+            git::count_untracked_files(&wt.path).ok()
+        } else {
+            None
+        };
+
+        let outside_path = match &project_root {
+            Some(root) if is_outside_project_root(&wt.path, root) => Some(wt.path.clone()),
+            _ => None,
+        };
+
         display_worktrees.push(WorktreeDisplay {
             branch,
+            path: wt.path.clone(),
+            head: wt.head.clone(),
+            bare: wt.bare,
+            detached: !wt.bare && wt.branch.is_none(),
+            commits_since,
+            unpushed,
+            upstream,
             pr_info,
+            outside_path,
+            orphaned,
+            last_commit,
+            attention,
+            locked: wt.locked.clone(),
+            untracked,
         });
+
+        // Out as soon as it's known, so consumers of large projects needn't wait for the rest
+        if let (true, Some(wt)) = (stream_json_lines, display_worktrees.last()) {
+            print_json_line(&ListJsonLine::Worktree(worktree_json(wt)))?;
+        }
     }
 
-    // Display local worktrees
-    if !display_worktrees.is_empty() {
-        println!("{}", "Local Worktrees:".bold());
-        println!();
-        
-        for worktree in &display_worktrees {
-            display_worktree(&worktree);
+    // The main branch is home base: it comes first, then the other protected branches, then the
+    // rest in git's order
+    display_worktrees.sort_by_key(|wt| listing_rank(&wt.branch, &found_config));
+
+    if let Some(failure) = needs_every_pr {
+        if let Some(error) = pr_fetch_error {
+            return Err(error.context(failure));
         }
+        if pr_fetch_timed_out {
+            bail!("{}: fetching pull requests timed out", failure);
+        }
+    }
+    if options.json_lines && !stream_json_lines {
+        for wt in &display_worktrees {
+            print_json_line(&ListJsonLine::Worktree(worktree_json(wt)))?;
+        }
+    }
+
+    let title_width = if options.full_titles { None } else { title_width() };
+
+    if let Some(template) = &options.format {
+        for wt in &display_worktrees {
+            println!("{}", template.render(|field| format_field(wt, field, &paths)));
+        }
+        // Only warnings, on stderr, so the output stays one line per worktree
+        if let Some(error) = &pr_fetch_error {
+            warn_pr_fetch_failed(error);
+        }
+        if pr_fetch_timed_out {
+            eprintln!("PR fetch timed out; some pull request information is missing.");
+        }
+        return Ok(());
+    }
+
+    // JSON is printed in one piece once the remote PRs are known; JSON lines already went out
+    if !options.json_output() {
+        display_local_worktrees(&display_worktrees, options, since_rev.as_deref(), title_width, &paths);
     }
 
     // Fetch all open pull requests and add ones that don't have local worktrees
     let mut remote_prs: Vec<RemotePullRequest> = Vec::new();
 
-    if has_pr_info {
-        match &repo_info {
-            Some((platform, owner_or_workspace, repo)) => {
-                match platform.as_str() {
-                    "github" => {
-                        if let Some(ref client) = github_client {
-                            if let Ok(all_prs) = client.get_all_pull_requests(owner_or_workspace, repo) {
+    let pr_lookups_stopped = pr_fetch_error.as_ref().is_some_and(stops_pr_lookups);
+    if has_pr_info && !pr_lookups_stopped && !pr_fetch_timed_out && !options.no_pr_only {
+        if let Some((platform, owner_or_workspace, repo)) = &repo_info {
+            match platform {
+                Provider::Github => {
+                    if let Some(ref client) = github_client {
+                        match within_deadline(pr_deadline, client.get_all_pull_requests(owner_or_workspace, repo)).await
+                        {
+                            None => pr_fetch_timed_out = true,
+                            Some(Err(e)) => pr_fetch_error = pr_fetch_error.or(rate_limit_error(e)),
+                            Some(Ok(all_prs)) => {
                                 for (pr, branch_name) in all_prs {
                                     // Skip if we already have a local worktree for this branch
                                     if !local_branches.contains(&branch_name) {
@@ -213,6 +509,9 @@ pub async fn run() -> Result<()> {
                                                 url: pr.html_url,
                                                 status: status.to_string(),
                                                 title: pr.title.clone(),
+                                                author: pr.author,
+                                                updated_at: pr.updated_at,
+                                                ..Default::default()
                                             },
                                         });
                                     }
@@ -220,9 +519,15 @@ pub async fn run() -> Result<()> {
                             }
                         }
                     }
-                    "bitbucket-cloud" => {
-                        if let Some(ref client) = bitbucket_client {
-                            if let Ok(all_prs) = client.get_pull_requests(owner_or_workspace, repo).await {
+                }
+                Provider::BitbucketCloud => {
+                    if let Some(ref client) = bitbucket_client {
+                        match within_deadline(pr_deadline, client.get_all_open_pull_requests(owner_or_workspace, repo))
+                            .await
+                        {
+                            None => pr_fetch_timed_out = true,
+                            Some(Err(e)) => pr_fetch_error = pr_fetch_error.or(rate_limit_error(e)),
+                            Some(Ok(all_prs)) => {
                                 for pr in all_prs {
                                     // Only include open PRs
                                     if pr.state == "OPEN" {
@@ -236,6 +541,9 @@ pub async fn run() -> Result<()> {
                                                     url,
                                                     status: "OPEN".to_string(),
                                                     title: pr.title.clone(),
+                                                    author: Some(pr.author.uuid.clone()),
+                                                    updated_at: utils::parse_rfc3339(&pr.updated_on),
+                                                    ..Default::default()
                                                 },
                                             });
                                         }
@@ -244,9 +552,13 @@ pub async fn run() -> Result<()> {
                             }
                         }
                     }
-                    "bitbucket-data-center" => {
-                        if let Some(ref client) = bitbucket_data_center_client {
-                            if let Ok(all_prs) = client.get_pull_requests(owner_or_workspace, repo).await {
+                }
+                Provider::BitbucketDataCenter => {
+                    if let Some(ref client) = bitbucket_data_center_client {
+                        match within_deadline(pr_deadline, client.get_pull_requests(owner_or_workspace, repo)).await {
+                            None => pr_fetch_timed_out = true,
+                            Some(Err(e)) => pr_fetch_error = pr_fetch_error.or(Some(e)),
+                            Some(Ok(all_prs)) => {
                                 for pr in all_prs {
                                     // Only include open PRs
                                     if pr.state == "OPEN" {
@@ -254,13 +566,16 @@ pub async fn run() -> Result<()> {
                                         // Skip if we already have a local worktree for this branch
                                         if !local_branches.contains(&branch_name) {
                                             let status = if pr.draft.unwrap_or(false) { "DRAFT" } else { "OPEN" };
-                                            let url = extract_bitbucket_data_center_url(&pr);
+                                            let url = extract_bitbucket_data_center_url(&pr, client.base_url());
                                             remote_prs.push(RemotePullRequest {
                                                 branch: branch_name,
                                                 pr_info: PullRequestInfo {
                                                     url,
                                                     status: status.to_string(),
                                                     title: pr.title.clone(),
+                                                    author: Some(pr.author.user.name.clone()),
+                                                    updated_at: utils::from_epoch_millis(pr.updated_date),
+                                                    ..Default::default()
                                                 },
                                             });
                                         }
@@ -269,138 +584,763 @@ pub async fn run() -> Result<()> {
                             }
                         }
                     }
-                    _ => {}
                 }
             }
-            None => {}
         }
     }
 
+    remote_prs.retain(|pr| !pr_ignored(&pr.branch));
+    if let Some(user) = &current_user {
+        remote_prs.retain(|pr| pr.pr_info.is_authored_by(user));
+    }
+
     // Display remote PRs if any exist
-    if !remote_prs.is_empty() {
+    if options.json_lines {
+        for pr in &remote_prs {
+            print_json_line(&ListJsonLine::RemotePullRequest(remote_pull_request_json(pr)))?;
+        }
+    } else if options.json {
+        print_json(&display_worktrees, &remote_prs)?;
+    } else if !remote_prs.is_empty() {
         if !display_worktrees.is_empty() {
             println!(); // Add spacing between sections
         }
         println!("{}", "Open Pull Requests (no local worktree):".bold());
         println!();
-        
-        for pr in &remote_prs {
-            display_remote_pr(&pr);
+
+        if options.compact {
+            let branch_width = column_width(remote_prs.iter().map(|pr| pr.branch.as_str()));
+            for pr in &remote_prs {
+                print_compact_row(&pr.branch, branch_width, Some(&pr.pr_info), &[], title_width);
+            }
+        } else {
+            for pr in &remote_prs {
+                display_remote_pr(pr, title_width);
+            }
         }
     }
 
-    if !has_pr_info {
-        if let Some((_, config)) = config::GitWorktreeConfig::find_config()? {
-            match config.source_control.as_str() {
-                "bitbucket-cloud" => {
-                    println!(
-                        "\n{}",
-                        "Tip: Run 'gwt auth bitbucket-cloud setup' to enable Bitbucket Cloud pull request information"
-                            .dimmed()
-                    );
-                }
-                "bitbucket-data-center" => {
-                    println!("\n{}", "Tip: Run 'gwt auth bitbucket-data-center setup' to enable Bitbucket Data Center pull request information".dimmed());
-                }
-                "github" | _ => {
-                    println!(
-                        "\n{}",
-                        "Tip: Run 'gh auth login' to enable GitHub pull request information".dimmed()
-                    );
-                }
+    if !options.json_output() && !options.no_summary && !ui::is_quiet() {
+        if options.compact {
+            println!();
+        }
+        println!(
+            "{}",
+            summary_line(&display_worktrees, &remote_prs, has_pr_info).dimmed()
+        );
+    }
+
+    if let Some(error) = &pr_fetch_error {
+        warn_pr_fetch_failed(error);
+    }
+    if pr_fetch_timed_out {
+        // On stderr so `--json` output stays parseable
+        eprintln!(
+            "\n{}",
+            "PR fetch timed out; some pull request information is missing.".dimmed()
+        );
+    }
+
+    if let Some(reason) = pr_unavailable.filter(|_| !options.json_output()) {
+        println!("\n{}", format!("No pull request information: {}", reason).dimmed());
+    }
+
+    Ok(())
+}
+
+/// The provider `list` fetches pull requests from, with a ready client for it
+struct PrSource {
+    provider: Provider,
+    owner_or_workspace: String,
+    repo: String,
+    github_client: Option<github::GitHubClient>,
+    bitbucket_client: Option<bitbucket_api::BitbucketClient>,
+    bitbucket_data_center_client: Option<bitbucket_data_center_api::BitbucketDataCenterClient>,
+}
+
+/// Connect to the project's `sourceControl` provider, or explain why pull requests can't be
+/// fetched (unparseable URL, missing token). Never falls back to another provider.
+fn connect_pr_source(config: &config::GitWorktreeConfig, pr_limit: u32) -> Result<PrSource, String> {
+    let repo_url = &config.resolved_repository_url();
+
+    match config.source_control {
+        Provider::Github => {
+            let (owner, repo) = github::GitHubClient::parse_github_url(repo_url)
+                .ok_or_else(|| format!("could not read a GitHub owner and repository from {}", repo_url))?;
+            let client = github::GitHubClient::new().with_pr_limit(pr_limit);
+            if !client.has_auth() {
+                return Err("GitHub CLI is not authenticated. Run 'gh auth login' to enable it.".to_string());
+            }
+            Ok(PrSource {
+                provider: Provider::Github,
+                owner_or_workspace: owner,
+                repo,
+                github_client: Some(client),
+                bitbucket_client: None,
+                bitbucket_data_center_client: None,
+            })
+        }
+        Provider::BitbucketCloud => {
+            let (workspace, repo) = bitbucket_api::extract_bitbucket_info_from_url(repo_url).ok_or_else(|| {
+                format!(
+                    "could not read a Bitbucket Cloud workspace and repository from {}",
+                    repo_url
+                )
+            })?;
+            let auth =
+                bitbucket_auth::BitbucketAuth::new(workspace.clone(), repo.clone(), config.bitbucket_email.clone())
+                    .map_err(|e| e.to_string())?;
+            if !auth.has_stored_token() {
+                return Err(
+                    "no Bitbucket Cloud token is stored. Run 'gwt auth bitbucket-cloud setup' to enable it."
+                        .to_string(),
+                );
+            }
+            Ok(PrSource {
+                provider: Provider::BitbucketCloud,
+                owner_or_workspace: workspace,
+                repo,
+                github_client: None,
+                bitbucket_client: Some(bitbucket_api::BitbucketClient::new(auth).with_pr_limit(pr_limit)),
+                bitbucket_data_center_client: None,
+            })
+        }
+        Provider::BitbucketDataCenter => {
+            let (base_url, project_key, repo_slug) =
+                bitbucket_data_center_auth::auth_from_config(config).map_err(|e| e.to_string())?;
+            let auth = bitbucket_data_center_auth::BitbucketDataCenterAuth::new(
+                project_key.clone(),
+                repo_slug.clone(),
+                base_url.clone(),
+            )
+            .map_err(|e| e.to_string())?;
+            if auth.get_token().is_err() {
+                return Err(
+                    "no Bitbucket Data Center token is set. Run 'gwt auth bitbucket-data-center setup' to enable it."
+                        .to_string(),
+                );
             }
+            Ok(PrSource {
+                provider: Provider::BitbucketDataCenter,
+                owner_or_workspace: project_key,
+                repo: repo_slug,
+                github_client: None,
+                bitbucket_client: None,
+                bitbucket_data_center_client: Some(
+                    bitbucket_data_center_api::BitbucketDataCenterClient::new(auth, base_url).with_pr_limit(pr_limit),
+                ),
+            })
         }
     }
+}
+
+fn display_local_worktrees(
+    display_worktrees: &[WorktreeDisplay],
+    options: &ListOptions,
+    since_rev: Option<&str>,
+    title_width: Option<usize>,
+    paths: &project::PathDisplay,
+) {
+    if !display_worktrees.is_empty() {
+        println!("{}", "Local Worktrees:".bold());
+        println!();
 
+        if options.compact {
+            let branch_width = column_width(display_worktrees.iter().map(|wt| wt.branch.as_str()));
+            for worktree in display_worktrees {
+                display_worktree_compact(worktree, branch_width, title_width);
+            }
+        } else {
+            for worktree in display_worktrees {
+                display_worktree(worktree, since_rev, title_width, paths);
+            }
+        }
+    } else if options.since_only {
+        println!(
+            "{}",
+            format!(
+                "No worktrees have commits that are not in {}.",
+                since_rev.unwrap_or_default()
+            )
+            .yellow()
+        );
+    } else if options.no_pr_only {
+        println!("{}", "Every worktree branch has a pull request.".green());
+    }
+}
+
+fn print_json(worktrees: &[WorktreeDisplay], remote_prs: &[RemotePullRequest]) -> Result<()> {
+    let output = ListJson {
+        version: JSON_VERSION,
+        worktrees: worktrees.iter().map(worktree_json).collect(),
+        remote_pull_requests: remote_prs.iter().map(remote_pull_request_json).collect(),
+    };
+
+    println!("{}", serde_json::to_string_pretty(&output)?);
     Ok(())
 }
 
-fn display_worktree(worktree: &WorktreeDisplay) {
-    // Display branch name in cyan
-    println!("{}", worktree.branch.cyan());
-    
+fn print_json_line(line: &ListJsonLine) -> Result<()> {
+    println!("{}", serde_json::to_string(line)?);
+    Ok(())
+}
+
+fn worktree_json(wt: &WorktreeDisplay) -> WorktreeJson<'_> {
+    WorktreeJson {
+        branch: (!wt.bare && !wt.detached).then_some(wt.branch.as_str()),
+        path: &wt.path,
+        head: &wt.head,
+        bare: wt.bare,
+        detached: wt.detached,
+        commits_since: wt.commits_since,
+        unpushed: wt.unpushed,
+        upstream: wt.upstream.as_deref(),
+        outside_project_root: wt.outside_path.is_some(),
+        orphaned: wt.orphaned,
+        needs_attention: !wt.attention.is_empty(),
+        locked: wt.locked.is_some(),
+        lock_reason: wt.locked.as_deref().filter(|reason| !reason.is_empty()),
+        last_commit_author: wt.last_commit.as_ref().map(|commit| commit.author.as_str()),
+        last_commit_date: wt.last_commit.as_ref().map(|commit| commit.date.as_str()),
+        untracked_files: wt.untracked,
+        pull_request: wt.pr_info.as_ref().map(PullRequestJson::from),
+    }
+}
+
+fn remote_pull_request_json(pr: &RemotePullRequest) -> RemotePullRequestJson<'_> {
+    RemotePullRequestJson {
+        branch: &pr.branch,
+        pull_request: (&pr.pr_info).into(),
+    }
+}
+
+/// One `--format` value for a worktree; empty when it doesn't apply, like the JSON output's `null`
+fn format_field(wt: &WorktreeDisplay, field: Field, paths: &project::PathDisplay) -> String {
+    let pr = wt.pr_info.as_ref();
+    match field {
+        Field::Branch if wt.bare || wt.detached => String::new(),
+        Field::Branch => wt.branch.clone(),
+        Field::Path => paths.show(&wt.path),
+        Field::Head => wt.head.clone(),
+        Field::Upstream => wt.upstream.clone().unwrap_or_default(),
+        Field::Unpushed => wt.unpushed.to_string(),
+        Field::Untracked => wt.untracked.map(|count| count.to_string()).unwrap_or_default(),
+        // The reason when there is one, so a bare "locked" still tells locked worktrees apart
+        Field::Locked => match wt.locked.as_deref() {
+            Some("") => "locked".to_string(),
+            Some(reason) => reason.to_string(),
+            None => String::new(),
+        },
+        Field::CommitAuthor => wt
+            .last_commit
+            .as_ref()
+            .map(|commit| commit.author.clone())
+            .unwrap_or_default(),
+        Field::CommitDate => wt
+            .last_commit
+            .as_ref()
+            .map(|commit| commit.date.clone())
+            .unwrap_or_default(),
+        Field::PrUrl => pr.map(|info| info.url.clone()).unwrap_or_default(),
+        Field::PrStatus => pr.map(|info| info.status.clone()).unwrap_or_default(),
+        Field::PrTitle => pr.map(|info| info.title.clone()).unwrap_or_default(),
+        Field::PrNumber => pr
+            .and_then(|info| info.number())
+            .map(str::to_string)
+            .unwrap_or_default(),
+        Field::PrAuthor => pr.and_then(|info| info.author.clone()).unwrap_or_default(),
+    }
+}
+
+/// `--no-pr-for` patterns, or `listIgnoreBranches` without them
+fn pr_ignored_patterns<'a>(options: &'a ListOptions, config: Option<&'a config::GitWorktreeConfig>) -> &'a [String] {
+    if options.no_pr_for.is_empty() {
+        config
+            .and_then(|config| config.list_ignore_branches.as_deref())
+            .unwrap_or_default()
+    } else {
+        &options.no_pr_for
+    }
+}
+
+fn is_long_lived(branch: &str, found_config: &Option<(PathBuf, config::GitWorktreeConfig)>) -> bool {
+    match found_config {
+        Some((_, config)) => branch == config.main_branch || config.protected_pattern(branch).is_some(),
+        None => branch == "main",
+    }
+}
+
+/// Sort key putting the main branch before protected branches before everything else
+fn listing_rank(branch: &str, found_config: &Option<(PathBuf, config::GitWorktreeConfig)>) -> u8 {
+    let main_branch = found_config
+        .as_ref()
+        .map_or("main", |(_, config)| config.main_branch.as_str());
+    if branch == main_branch {
+        0
+    } else if is_long_lived(branch, found_config) {
+        1
+    } else {
+        2
+    }
+}
+
+fn show_containing_worktree(worktrees: &[git::Worktree], path: &Path, paths: &project::PathDisplay) -> Result<()> {
+    // The path may not exist yet (e.g. a file about to be created), so fall back to joining it
+    let absolute = fs::canonicalize(path).or_else(|_| std::env::current_dir().map(|dir| dir.join(path)))?;
+
+    let Some(worktree) = git::find_worktree_containing(worktrees, &absolute) else {
+        bail!("'{}' is not inside any worktree of this project", path.display());
+    };
+
+    let branch = worktree
+        .branch
+        .as_ref()
+        .map(|b| clean_branch_name(b))
+        .unwrap_or_else(|| format!("(detached at {})", worktree.head.chars().take(8).collect::<String>()));
+
+    println!("{}", branch.cyan());
+    println!("  {}", paths.show(&worktree.path));
+
+    Ok(())
+}
+
+fn display_worktree(
+    worktree: &WorktreeDisplay,
+    since_rev: Option<&str>,
+    title_width: Option<usize>,
+    paths: &project::PathDisplay,
+) {
+    // Display branch name in cyan, flagged when its pull request needs work
+    let flag = if worktree.attention.is_empty() {
+        String::new()
+    } else {
+        format!("{} ", "⚠".red())
+    };
+    if worktree.orphaned {
+        println!("{}{} {}", flag, worktree.branch.cyan(), "(orphaned)".red());
+        println!(
+            "  {}",
+            format!("branch no longer exists; clean up with: gwt remove {}", worktree.branch).dimmed()
+        );
+    } else {
+        println!("{}{}", flag, worktree.branch.cyan());
+    }
+
+    if let Some(commit) = &worktree.last_commit {
+        println!(
+            "  {}",
+            format!("{}, {}", commit.author, relative_date(&commit.date)).dimmed()
+        );
+    }
+
+    if let Some(upstream) = &worktree.upstream {
+        if upstream_differs(&worktree.branch, upstream) {
+            println!("  {}", format!("↳ tracks {}", upstream).dimmed());
+        }
+    }
+
+    if let Some(rev) = since_rev {
+        match worktree.commits_since {
+            Some(0) => println!("  {}", format!("up to date with {}", rev).dimmed()),
+            Some(1) => println!("  {}", format!("1 commit not in {}", rev).yellow()),
+            Some(count) => println!("  {}", format!("{} commits not in {}", count, rev).yellow()),
+            None => println!("  {}", format!("{} not found", rev).dimmed()),
+        }
+    }
+
+    match worktree.unpushed {
+        0 => {}
+        1 => println!("  {}", "⬆ 1 unpushed commit".magenta()),
+        count => println!("  {}", format!("⬆ {} unpushed commits", count).magenta()),
+    }
+
+    match worktree.untracked {
+        None | Some(0) => {}
+        Some(1) => println!("  {}", "✚ 1 untracked file".yellow()),
+        Some(count) => println!("  {}", format!("✚ {} untracked files", count).yellow()),
+    }
+
+    if let Some(reason) = &worktree.locked {
+        println!(
+            "  {}",
+            format!("🔒 locked{}", remove::lock_reason_suffix(reason)).yellow()
+        );
+    }
+
+    if let Some(ref path) = worktree.outside_path {
+        println!("  {} {}", paths.show(path).dimmed(), "(outside project root)".yellow());
+    }
+
     // Display PR info if available
     if let Some(ref pr_info) = worktree.pr_info {
-        // Display URL with status
-        let status_colored = match pr_info.status.as_str() {
-            "OPEN" => "open".green(),
-            "CLOSED" => "closed".red(),
-            "MERGED" => "merged".green(),
-            "DRAFT" => "draft".yellow(),
-            _ => pr_info.status.normal(),
-        };
-        println!("  {} ({})", pr_info.url.blue().underline(), status_colored);
-        
+        print_pr_url_line(pr_info);
+
         // Display title if not empty
         if !pr_info.title.is_empty() {
-            println!("  {}", pr_info.title.dimmed());
+            println!("  {}", fit_title(&pr_info.title, title_width).dimmed());
+        }
+
+        if !worktree.attention.is_empty() {
+            println!("  {}", format!("⚠ {}", worktree.attention.join(", ")).red());
         }
     }
     println!(); // Empty line between worktrees
 }
 
-fn display_remote_pr(pr: &RemotePullRequest) {
+/// Whether `upstream` (`<remote>/<branch>`, or a local branch) names a branch other than `branch`
+fn upstream_differs(branch: &str, upstream: &str) -> bool {
+    upstream.split_once('/').map(|(_, name)| name) != Some(branch)
+}
+
+/// The pull request's URL with its status and, when known, when it was last updated
+fn print_pr_url_line(pr_info: &PullRequestInfo) {
+    let updated = pr_info
+        .updated_at
+        .map(|time| format!(" updated {}", utils::format_relative_time(time, Utc::now())).dimmed())
+        .unwrap_or_default();
+    println!(
+        "  {} ({}){}",
+        pr_info.url.blue().underline(),
+        status_label(&pr_info.status, 0),
+        updated
+    );
+}
+
+/// A git ISO 8601 date relative to now, or as given when it can't be parsed
+fn relative_date(date: &str) -> String {
+    utils::parse_rfc3339(date).map_or_else(
+        || date.to_string(),
+        |time| utils::format_relative_time(time, Utc::now()),
+    )
+}
+
+fn display_remote_pr(pr: &RemotePullRequest, title_width: Option<usize>) {
     // Display branch name in cyan
     println!("{}", pr.branch.cyan());
-    
-    // Display URL with status
-    let status_colored = match pr.pr_info.status.as_str() {
-        "OPEN" => "open".green(),
-        "CLOSED" => "closed".red(), 
-        "MERGED" => "merged".green(),
-        "DRAFT" => "draft".yellow(),
-        _ => pr.pr_info.status.normal(),
-    };
-    println!("  {} ({})", pr.pr_info.url.blue().underline(), status_colored);
-    
+
+    print_pr_url_line(&pr.pr_info);
+
     // Display title
     if !pr.pr_info.title.is_empty() {
-        println!("  {}", pr.pr_info.title.dimmed());
+        println!("  {}", fit_title(&pr.pr_info.title, title_width).dimmed());
     }
     println!(); // Empty line between PRs
 }
 
-fn find_git_directory() -> Result<PathBuf> {
-    let current_dir = std::env::current_dir()?;
+/// Width of the status column in compact mode ("merged", "closed")
+const STATUS_WIDTH: usize = 6;
+/// Width of the number column in compact mode ("#12345")
+const NUMBER_WIDTH: usize = 6;
 
-    // First, try to find git-worktree-config.yaml to determine if we're in a worktree project
-    let mut search_path = current_dir.clone();
-    let mut project_root: Option<PathBuf> = None;
+fn display_worktree_compact(worktree: &WorktreeDisplay, branch_width: usize, title_width: Option<usize>) {
+    let mut markers = Vec::new();
+    if let Some(count) = worktree.commits_since.filter(|count| *count > 0) {
+        markers.push(format!("+{}", count));
+    }
+    if worktree.unpushed > 0 {
+        markers.push(format!("⬆{}", worktree.unpushed));
+    }
+    if let Some(count) = worktree.untracked.filter(|count| *count > 0) {
+        markers.push(format!("✚{}", count));
+    }
+    if worktree.outside_path.is_some() {
+        markers.push("(outside project root)".to_string());
+    }
+    if worktree.orphaned {
+        markers.push("(orphaned)".to_string());
+    }
+    if !worktree.attention.is_empty() {
+        markers.push(format!("⚠ {}", worktree.attention.join(", ")));
+    }
+    if worktree.locked.is_some() {
+        markers.push("🔒".to_string());
+    }
 
-    loop {
-        let config_path = search_path.join("git-worktree-config.yaml");
-        if config_path.exists() {
-            project_root = Some(search_path);
-            break;
-        }
+    print_compact_row(
+        &worktree.branch,
+        branch_width,
+        worktree.pr_info.as_ref(),
+        &markers,
+        title_width,
+    );
+}
 
-        if !search_path.pop() {
-            break;
-        }
+/// `branch  status  #num  title  markers`, padded so the columns line up
+fn print_compact_row(
+    branch: &str,
+    branch_width: usize,
+    pr_info: Option<&PullRequestInfo>,
+    markers: &[String],
+    title_width: Option<usize>,
+) {
+    let (status, number, title) = match pr_info {
+        Some(pr_info) => (
+            status_label(&pr_info.status, STATUS_WIDTH),
+            pr_info
+                .number()
+                .map(|number| format!("#{}", number))
+                .unwrap_or_default(),
+            pr_info.title.as_str(),
+        ),
+        None => (
+            format!("{:<width$}", "-", width = STATUS_WIDTH).dimmed(),
+            String::new(),
+            "",
+        ),
+    };
+
+    // title_width already leaves room for a two-space indent; here the columns come first instead
+    let prefix_width = branch_width + STATUS_WIDTH + NUMBER_WIDTH + 6;
+    let title = fit_title(title, title_width.map(|width| (width + 2).saturating_sub(prefix_width)));
+
+    let mut line = format!(
+        "{}  {}  {:<number_width$}  {}",
+        format!("{:<width$}", branch, width = branch_width).cyan(),
+        status,
+        number,
+        title.dimmed(),
+        number_width = NUMBER_WIDTH
+    );
+    if !markers.is_empty() {
+        line.push_str(&format!("  {}", markers.join(" ").yellow()));
     }
+    println!("{}", line.trim_end());
+}
 
-    if let Some(project_root) = project_root {
-        // Found config file, look for any existing worktree to use for git commands
-        let entries = fs::read_dir(&project_root)?;
+fn column_width<'a>(values: impl Iterator<Item = &'a str>) -> usize {
+    values.map(|value| value.chars().count()).max().unwrap_or(0)
+}
 
-        for entry in entries {
-            let entry = entry?;
-            if entry.file_type()?.is_dir() {
-                let dir_path = entry.path();
-                let git_path = dir_path.join(".git");
-                if git_path.exists() {
-                    return Ok(dir_path);
-                }
-            }
-        }
+/// Colored lowercase status, padded to `width` (0 for no padding)
+fn status_label(status: &str, width: usize) -> ColoredString {
+    let label = format!("{:<width$}", status.to_lowercase(), width = width);
+    match status {
+        "OPEN" | "MERGED" => label.green(),
+        "CLOSED" => label.red(),
+        "DRAFT" => label.yellow(),
+        _ => label.normal(),
+    }
+}
 
-        bail!("No existing worktrees found in project root. Create one first using gwt init.");
-    } else {
-        // No config found, check if we're directly in a git repository
-        if let Some(git_root) = git::get_git_root()? {
-            Ok(git_root)
-        } else {
-            bail!("Not in a git repository or project root with git-worktree-config.yaml");
+/// Await a pull request fetch unless `deadline` passes first (`None`); a fetch still running
+/// then is dropped, which kills any `gh` it started
+async fn within_deadline<T>(deadline: Option<Instant>, fetch: impl Future<Output = T>) -> Option<T> {
+    match deadline {
+        Some(deadline) if Instant::now() >= deadline => None,
+        Some(deadline) => tokio::time::timeout_at(deadline, fetch).await.ok(),
+        None => Some(fetch.await),
+    }
+}
+
+/// Keep `error` for the end-of-list warning only if it is a rate limit; other failures
+/// fetching the open PR section have always been silent
+fn rate_limit_error(error: anyhow::Error) -> Option<anyhow::Error> {
+    error.is::<RateLimited>().then_some(error)
+}
+
+/// Whether every further lookup would fail the same way, so `error` ends them for this listing
+fn stops_pr_lookups(error: &anyhow::Error) -> bool {
+    matches!(
+        error.downcast_ref::<BitbucketDataCenterError>(),
+        Some(BitbucketDataCenterError::Unreachable { .. } | BitbucketDataCenterError::AuthFailed)
+    )
+}
+
+fn warn_pr_fetch_failed(error: &anyhow::Error) {
+    if let Some(limited) = error.downcast_ref::<RateLimited>() {
+        eprintln!();
+        ui::warn(limited);
+        return;
+    }
+
+    let message = match error.downcast_ref::<BitbucketDataCenterError>() {
+        Some(BitbucketDataCenterError::Unreachable { .. }) => {
+            format!("{}\nCheck your network or VPN connection.", error)
         }
+        Some(BitbucketDataCenterError::AuthFailed) => error.to_string(),
+        _ => format!("Could not fetch pull request information: {}", error),
+    };
+
+    eprintln!();
+    ui::warn(message);
+}
+
+/// e.g. "5 worktrees, 3 with open PRs, 2 draft, 1 merged or closed"; PR counts that are zero are left out
+fn summary_line(worktrees: &[WorktreeDisplay], remote_prs: &[RemotePullRequest], has_pr_info: bool) -> String {
+    let count = worktrees.iter().filter(|wt| !wt.bare).count();
+    let mut parts = vec![format!("{} worktree{}", count, if count == 1 { "" } else { "s" })];
+
+    if has_pr_info {
+        let with_status = |statuses: &[&str]| {
+            worktrees
+                .iter()
+                .filter(|wt| {
+                    wt.pr_info
+                        .as_ref()
+                        .is_some_and(|pr| statuses.contains(&pr.status.as_str()))
+                })
+                .count()
+        };
+        let counts = [
+            (with_status(&["OPEN"]), "with open PRs"),
+            (with_status(&["DRAFT"]), "draft"),
+            (with_status(&["MERGED", "CLOSED", "DECLINED"]), "merged or closed"),
+            (remote_prs.len(), "open PRs without a worktree"),
+        ];
+        parts.extend(
+            counts
+                .iter()
+                .filter(|(count, _)| *count > 0)
+                .map(|(count, label)| format!("{} {}", count, label)),
+        );
+    }
+
+    parts.join(", ")
+}
+
+/// Room left for a title on the current line, or `None` when output isn't a terminal
+fn title_width() -> Option<usize> {
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+
+    // Titles are indented by two spaces
+    terminal_size::terminal_size().map(|(terminal_size::Width(width), _)| (width as usize).saturating_sub(2))
+}
+
+fn fit_title(title: &str, width: Option<usize>) -> String {
+    match width {
+        Some(width) => utils::truncate_with_ellipsis(title, width),
+        None => title.to_string(),
+    }
+}
+
+fn is_outside_project_root(worktree_path: &Path, project_root: &Path) -> bool {
+    // git reports canonical paths, so compare canonical forms where possible
+    let canonical = |path: &Path| fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    !canonical(worktree_path).starts_with(canonical(project_root))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pr_ignored_patterns_prefers_the_command_line() {
+        let mut config = config::GitWorktreeConfig::new(
+            "git@github.com:org/repo.git".to_string(),
+            "main".to_string(),
+            Provider::Github,
+        );
+        config.list_ignore_branches = Some(vec!["dependabot/*".to_string()]);
+
+        let options = ListOptions::default();
+        assert_eq!(pr_ignored_patterns(&options, Some(&config)), ["dependabot/*"]);
+        assert!(pr_ignored_patterns(&options, None).is_empty());
+
+        let options = ListOptions {
+            no_pr_for: vec!["renovate/*".to_string()],
+            ..ListOptions::default()
+        };
+        assert_eq!(pr_ignored_patterns(&options, Some(&config)), ["renovate/*"]);
+    }
+
+    #[test]
+    fn test_connect_pr_source_explains_unusable_urls() {
+        let config =
+            |url: &str, provider| config::GitWorktreeConfig::new(url.to_string(), "main".to_string(), provider);
+
+        let reason = connect_pr_source(&config("/srv/git/repo.git", Provider::Github), 10)
+            .err()
+            .unwrap();
+        assert!(reason.contains("GitHub owner and repository"), "{}", reason);
+
+        // A Bitbucket project never falls back to reading the URL as GitHub's
+        let reason = connect_pr_source(&config("git@github.com:owner/repo.git", Provider::BitbucketCloud), 10)
+            .err()
+            .unwrap();
+        assert!(reason.contains("Bitbucket Cloud workspace"), "{}", reason);
+    }
+
+    #[test]
+    fn test_listing_rank_puts_home_base_first() {
+        let mut config = config::GitWorktreeConfig::new(
+            "git@github.com:org/repo.git".to_string(),
+            "develop".to_string(),
+            Provider::Github,
+        );
+        config.protected_branches = Some(vec!["release/*".to_string()]);
+        let found_config = Some((PathBuf::from("git-worktree-config.yaml"), config));
+
+        let mut branches = vec!["feature/b", "release/1.0", "feature/a", "develop", "(bare)"];
+        branches.sort_by_key(|branch| listing_rank(branch, &found_config));
+        assert_eq!(branches, ["develop", "release/1.0", "feature/b", "feature/a", "(bare)"]);
+
+        assert_eq!(listing_rank("main", &None), 0);
+        assert_eq!(listing_rank("feature/a", &None), 2);
+    }
+
+    #[test]
+    fn test_upstream_differs() {
+        assert!(!upstream_differs("feature", "origin/feature"));
+        assert!(!upstream_differs("alice/feature", "upstream/alice/feature"));
+        assert!(upstream_differs("feature", "origin/alice/feature"));
+        assert!(upstream_differs("feature", "main"));
+    }
+
+    #[test]
+    fn test_summary_line() {
+        let worktree = |branch: &str, status: Option<&str>| WorktreeDisplay {
+            branch: branch.to_string(),
+            path: PathBuf::from(branch),
+            head: String::new(),
+            bare: false,
+            detached: false,
+            commits_since: None,
+            unpushed: 0,
+            upstream: None,
+            pr_info: status.map(|status| PullRequestInfo {
+                url: String::new(),
+                status: status.to_string(),
+                title: String::new(),
+                ..Default::default()
+            }),
+            outside_path: None,
+            orphaned: false,
+            last_commit: None,
+            attention: Vec::new(),
+            locked: None,
+            untracked: None,
+        };
+        let worktrees = vec![
+            worktree("main", None),
+            worktree("a", Some("OPEN")),
+            worktree("b", Some("OPEN")),
+            worktree("c", Some("DRAFT")),
+            worktree("d", Some("MERGED")),
+        ];
+
+        assert_eq!(
+            summary_line(&worktrees, &[], true),
+            "5 worktrees, 2 with open PRs, 1 draft, 1 merged or closed"
+        );
+        assert_eq!(summary_line(&worktrees, &[], false), "5 worktrees");
+        assert_eq!(summary_line(&worktrees[..1], &[], true), "1 worktree");
+    }
+
+    #[tokio::test]
+    async fn test_within_deadline() {
+        assert_eq!(within_deadline(None, async { 1 }).await, Some(1));
+
+        let deadline = Instant::now() + Duration::from_millis(20);
+        assert_eq!(within_deadline(Some(deadline), async { 2 }).await, Some(2));
+        let slow = tokio::time::sleep(Duration::from_secs(5));
+        assert_eq!(within_deadline(Some(deadline), slow).await, None);
+
+        // Once the deadline has passed, nothing more is started
+        let mut started = false;
+        let fetch = async { started = true };
+        assert_eq!(within_deadline(Some(deadline), fetch).await, None);
+        assert!(!started);
     }
 }
@@ -0,0 +1,163 @@
+use anyhow::{bail, Result};
+
+/// A value `gwt list --format` can fill in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Branch,
+    Path,
+    Head,
+    Upstream,
+    Unpushed,
+    Untracked,
+    Locked,
+    CommitAuthor,
+    CommitDate,
+    PrUrl,
+    PrStatus,
+    PrTitle,
+    PrNumber,
+    PrAuthor,
+}
+
+impl Field {
+    const ALL: &'static [(&'static str, Field)] = &[
+        ("branch", Field::Branch),
+        ("path", Field::Path),
+        ("head", Field::Head),
+        ("upstream", Field::Upstream),
+        ("unpushed", Field::Unpushed),
+        ("untracked", Field::Untracked),
+        ("locked", Field::Locked),
+        ("commit_author", Field::CommitAuthor),
+        ("commit_date", Field::CommitDate),
+        ("pr_url", Field::PrUrl),
+        ("pr_status", Field::PrStatus),
+        ("pr_title", Field::PrTitle),
+        ("pr_number", Field::PrNumber),
+        ("pr_author", Field::PrAuthor),
+    ];
+
+    fn from_name(name: &str) -> Option<Field> {
+        Field::ALL
+            .iter()
+            .find(|(field_name, _)| *field_name == name)
+            .map(|(_, field)| *field)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Field(Field),
+}
+
+/// A `--format` template such as `{branch}\t{pr_url}`, parsed up front so a typo fails
+/// before anything is fetched. `{{` and `}}` stand for literal braces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+impl Template {
+    pub fn parse(template: &str) -> Result<Self> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => bail!("Unclosed '{{{}' in --format", name),
+                        }
+                    }
+                    let Some(field) = Field::from_name(&name) else {
+                        let known: Vec<&str> = Field::ALL.iter().map(|(name, _)| *name).collect();
+                        bail!(
+                            "Unknown placeholder '{{{}}}' in --format. Available: {}",
+                            name,
+                            known.join(", ")
+                        );
+                    };
+                    if !literal.is_empty() {
+                        parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(Part::Field(field));
+                }
+                '}' => bail!("Unmatched '}}' in --format; write '}}}}' for a literal brace"),
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+
+        Ok(Template { parts })
+    }
+
+    /// Whether the template shows `field`, so values that cost a git call can be skipped otherwise
+    pub fn uses(&self, field: Field) -> bool {
+        self.parts.contains(&Part::Field(field))
+    }
+
+    /// Fill in the placeholders; values that don't apply come back empty
+    pub fn render(&self, value: impl Fn(Field) -> String) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                Part::Literal(text) => text.clone(),
+                Part::Field(field) => value(*field),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(field: Field) -> String {
+        match field {
+            Field::Branch => "feature/login".to_string(),
+            Field::PrUrl => "https://github.com/org/repo/pull/42".to_string(),
+            _ => String::new(),
+        }
+    }
+
+    #[test]
+    fn test_template_renders_placeholders() {
+        let template = Template::parse("{branch}\t{pr_url} {{literal}} {pr_status}").unwrap();
+        assert_eq!(
+            template.render(sample),
+            "feature/login\thttps://github.com/org/repo/pull/42 {literal} "
+        );
+        assert!(template.uses(Field::PrUrl));
+        assert!(!template.uses(Field::Untracked));
+        assert_eq!(
+            Template::parse("no placeholders").unwrap().render(sample),
+            "no placeholders"
+        );
+    }
+
+    #[test]
+    fn test_template_rejects_unknown_placeholders() {
+        let error = Template::parse("{branch} {pr_link}").unwrap_err().to_string();
+        assert!(error.contains("Unknown placeholder '{pr_link}'"), "{}", error);
+        assert!(error.contains("pr_url"), "{}", error);
+
+        assert!(Template::parse("{branch").is_err());
+        assert!(Template::parse("branch}").is_err());
+    }
+}
@@ -1,14 +1,73 @@
-use crate::{bitbucket_api, bitbucket_data_center_api, github};
+use crate::cli::Provider;
+use crate::config::AttentionTrigger;
+use crate::{bitbucket_api, bitbucket_data_center_api, github, utils};
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 
+#[derive(Default)]
 pub struct PullRequestInfo {
     pub url: String,
     pub status: String,
     pub title: String,
+    /// Review decision such as `CHANGES_REQUESTED`; only fetched for GitHub worktrees
+    pub review_status: Option<String>,
+    /// Combined checks result: `SUCCESS`, `FAILURE` or `PENDING`; only fetched for GitHub worktrees
+    pub ci_status: Option<String>,
+    /// `MERGEABLE`, `CONFLICTING` or `UNKNOWN`; only fetched for GitHub worktrees
+    pub mergeable: Option<String>,
+    /// Who opened it: a GitHub login, Bitbucket Cloud account UUID or Data Center username
+    pub author: Option<String>,
+    /// Last time the pull request changed, where the provider reports it
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl PullRequestInfo {
+    /// Pull request number taken from the URL (`/pull/42` on GitHub, `/pull-requests/42` on Bitbucket)
+    pub fn number(&self) -> Option<&str> {
+        let (_, rest) = self
+            .url
+            .split_once("/pull-requests/")
+            .or_else(|| self.url.split_once("/pull/"))?;
+        let number = rest.split(['/', '?', '#']).next()?;
+        (!number.is_empty() && number.chars().all(|c| c.is_ascii_digit())).then_some(number)
+    }
+
+    /// Why an open pull request needs its author's attention, limited to `triggers`.
+    /// Empty when the statuses weren't fetched.
+    pub fn attention_reasons(&self, triggers: &[AttentionTrigger]) -> Vec<&'static str> {
+        if !matches!(self.status.as_str(), "OPEN" | "DRAFT") {
+            return Vec::new();
+        }
+
+        triggers
+            .iter()
+            .filter_map(|trigger| match trigger {
+                AttentionTrigger::ChangesRequested => {
+                    (self.review_status.as_deref() == Some("CHANGES_REQUESTED")).then_some("changes requested")
+                }
+                AttentionTrigger::FailingChecks => {
+                    (self.ci_status.as_deref() == Some("FAILURE")).then_some("checks failing")
+                }
+            })
+            .collect()
+    }
+
+    /// Whether the open pull request can't be merged until its conflicts are resolved.
+    /// `false` where mergeability isn't fetched or GitHub hasn't computed it yet.
+    pub fn has_conflicts(&self) -> bool {
+        matches!(self.status.as_str(), "OPEN" | "DRAFT") && self.mergeable.as_deref() == Some("CONFLICTING")
+    }
+
+    /// Whether `user` (as returned by the provider's current user lookup) opened this pull request
+    pub fn is_authored_by(&self, user: &str) -> bool {
+        self.author
+            .as_deref()
+            .is_some_and(|author| author.eq_ignore_ascii_case(user))
+    }
 }
 
 pub async fn fetch_pr_for_branch(
-    platform: &str,
+    platform: Provider,
     owner_or_workspace: &str,
     repo: &str,
     branch: &str,
@@ -17,23 +76,22 @@ pub async fn fetch_pr_for_branch(
     bitbucket_data_center_client: &Option<bitbucket_data_center_api::BitbucketDataCenterClient>,
 ) -> Result<Option<PullRequestInfo>> {
     match platform {
-        "github" => fetch_github_pr(github_client, owner_or_workspace, repo, branch),
-        "bitbucket-cloud" => fetch_bitbucket_cloud_pr(bitbucket_client, owner_or_workspace, repo, branch).await,
-        "bitbucket-data-center" => {
+        Provider::Github => fetch_github_pr(github_client, owner_or_workspace, repo, branch).await,
+        Provider::BitbucketCloud => fetch_bitbucket_cloud_pr(bitbucket_client, owner_or_workspace, repo, branch).await,
+        Provider::BitbucketDataCenter => {
             fetch_bitbucket_data_center_pr(bitbucket_data_center_client, owner_or_workspace, repo, branch).await
         }
-        _ => Ok(None),
     }
 }
 
-fn fetch_github_pr(
+async fn fetch_github_pr(
     client: &Option<github::GitHubClient>,
     owner: &str,
     repo: &str,
     branch: &str,
 ) -> Result<Option<PullRequestInfo>> {
     if let Some(ref client) = client {
-        match client.get_pull_requests(owner, repo, branch) {
+        match client.get_pull_requests(owner, repo, branch).await {
             Ok(prs) => {
                 if let Some(pr) = prs.first() {
                     let status = if pr.draft {
@@ -51,12 +109,18 @@ fn fetch_github_pr(
                         url: pr.html_url.clone(),
                         status: status.to_string(),
                         title: pr.title.clone(),
+                        review_status: pr.review_decision.clone(),
+                        ci_status: pr.ci_status.clone(),
+                        mergeable: pr.mergeable.clone(),
+                        author: pr.author.clone(),
+                        updated_at: pr.updated_at,
                     }))
                 } else {
                     Ok(None)
                 }
             }
-            Err(_) => Err(anyhow::anyhow!("Failed to fetch GitHub PRs")),
+            // Keep the cause so a rate limit can be reported as such
+            Err(e) => Err(e.context("Failed to fetch GitHub PRs")),
         }
     } else {
         Ok(None)
@@ -70,20 +134,24 @@ async fn fetch_bitbucket_cloud_pr(
     branch: &str,
 ) -> Result<Option<PullRequestInfo>> {
     if let Some(ref client) = client {
-        match client.get_pull_requests(workspace, repo).await {
+        match client.get_pull_requests(workspace, repo, branch).await {
             Ok(prs) => {
-                if let Some(pr) = prs.iter().find(|pr| pr.source.branch.name == branch) {
+                if let Some(pr) = prs.first() {
                     let url = extract_bitbucket_cloud_url(pr);
                     Ok(Some(PullRequestInfo {
                         url,
                         status: pr.state.to_uppercase(),
                         title: pr.title.clone(),
+                        author: Some(pr.author.uuid.clone()),
+                        updated_at: utils::parse_rfc3339(&pr.updated_on),
+                        ..Default::default()
                     }))
                 } else {
                     Ok(None)
                 }
             }
-            Err(_) => Err(anyhow::anyhow!("Failed to fetch Bitbucket Cloud PRs")),
+            // Keep the cause so a rate limit can be reported as such
+            Err(e) => Err(e.context("Failed to fetch Bitbucket Cloud PRs")),
         }
     } else {
         Ok(None)
@@ -97,26 +165,31 @@ async fn fetch_bitbucket_data_center_pr(
     branch: &str,
 ) -> Result<Option<PullRequestInfo>> {
     if let Some(ref client) = client {
-        match client.get_pull_requests(project, repo).await {
+        match client.get_pull_requests_for_branch(project, repo, branch).await {
             Ok(prs) => {
-                if let Some(pr) = prs.iter().find(|pr| pr.from_ref.display_id == branch) {
-                    let url = extract_bitbucket_data_center_url(pr);
+                if let Some(pr) = prs.first() {
+                    let url = extract_bitbucket_data_center_url(pr, client.base_url());
                     Ok(Some(PullRequestInfo {
                         url,
                         status: pr.state.to_uppercase(),
                         title: pr.title.clone(),
+                        author: Some(pr.author.user.name.clone()),
+                        updated_at: utils::from_epoch_millis(pr.updated_date),
+                        ..Default::default()
                     }))
                 } else {
                     Ok(None)
                 }
             }
-            Err(_) => Err(anyhow::anyhow!("Failed to fetch Bitbucket Data Center PRs")),
+            // Keep the typed error so the list can tell network failures from auth failures
+            Err(e) => Err(e),
         }
     } else {
         Ok(None)
     }
 }
 
+/// Web URL of a Bitbucket Cloud PR, built from the destination repository when `links` lacks one
 pub fn extract_bitbucket_cloud_url(pr: &bitbucket_api::BitbucketPullRequest) -> String {
     if let Some(html_link) = pr.links.get("html") {
         if let Some(href) = html_link.get("href") {
@@ -125,10 +198,17 @@ pub fn extract_bitbucket_cloud_url(pr: &bitbucket_api::BitbucketPullRequest) ->
             }
         }
     }
-    format!("PR #{}", pr.id)
+    format!(
+        "https://bitbucket.org/{}/pull-requests/{}",
+        pr.destination.repository.full_name, pr.id
+    )
 }
 
-pub fn extract_bitbucket_data_center_url(pr: &bitbucket_data_center_api::BitbucketDataCenterPullRequest) -> String {
+/// Web URL of a Data Center PR, built from the server URL and target repository when `links` lacks one
+pub fn extract_bitbucket_data_center_url(
+    pr: &bitbucket_data_center_api::BitbucketDataCenterPullRequest,
+    base_url: &str,
+) -> String {
     if let Some(self_link) = pr.links.get("self") {
         if let Some(links_array) = self_link.as_array() {
             if let Some(first_link) = links_array.first() {
@@ -140,14 +220,189 @@ pub fn extract_bitbucket_data_center_url(pr: &bitbucket_data_center_api::Bitbuck
             }
         }
     }
-    format!("PR #{}", pr.id)
+    let repository = &pr.to_ref.repository;
+    format!(
+        "{}/projects/{}/repos/{}/pull-requests/{}",
+        base_url.trim_end_matches('/'),
+        repository.project.key,
+        repository.slug,
+        pr.id
+    )
 }
 
-
 pub fn clean_branch_name(branch: &str) -> String {
-    if branch.starts_with("refs/heads/") {
-        branch[11..].to_string()
-    } else {
-        branch.to_string()
+    branch.strip_prefix("refs/heads/").unwrap_or(branch).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn pr_with_url(url: &str) -> PullRequestInfo {
+        PullRequestInfo {
+            url: url.to_string(),
+            status: "OPEN".to_string(),
+            title: String::new(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_is_authored_by() {
+        let pr = PullRequestInfo {
+            author: Some("OctoCat".to_string()),
+            ..Default::default()
+        };
+        assert!(pr.is_authored_by("octocat"));
+        assert!(!pr.is_authored_by("someone-else"));
+        assert!(!PullRequestInfo::default().is_authored_by("octocat"));
+    }
+
+    #[test]
+    fn test_has_conflicts() {
+        let pr = |status: &str, mergeable: Option<&str>| PullRequestInfo {
+            status: status.to_string(),
+            mergeable: mergeable.map(str::to_string),
+            ..Default::default()
+        };
+        assert!(pr("OPEN", Some("CONFLICTING")).has_conflicts());
+        assert!(pr("DRAFT", Some("CONFLICTING")).has_conflicts());
+        assert!(!pr("OPEN", Some("MERGEABLE")).has_conflicts());
+        assert!(!pr("OPEN", Some("UNKNOWN")).has_conflicts());
+        assert!(!pr("OPEN", None).has_conflicts());
+        assert!(!pr("CLOSED", Some("CONFLICTING")).has_conflicts());
+    }
+
+    #[test]
+    fn test_attention_reasons() {
+        let pr = |status: &str, review: Option<&str>, ci: Option<&str>| PullRequestInfo {
+            status: status.to_string(),
+            review_status: review.map(str::to_string),
+            ci_status: ci.map(str::to_string),
+            ..Default::default()
+        };
+
+        let both = pr("OPEN", Some("CHANGES_REQUESTED"), Some("FAILURE"));
+        assert_eq!(
+            both.attention_reasons(AttentionTrigger::ALL),
+            vec!["changes requested", "checks failing"]
+        );
+        assert_eq!(
+            both.attention_reasons(&[AttentionTrigger::FailingChecks]),
+            vec!["checks failing"]
+        );
+        assert!(both.attention_reasons(&[]).is_empty());
+        assert!(pr("MERGED", Some("CHANGES_REQUESTED"), Some("FAILURE"))
+            .attention_reasons(AttentionTrigger::ALL)
+            .is_empty());
+        assert!(pr("OPEN", Some("APPROVED"), Some("PENDING"))
+            .attention_reasons(AttentionTrigger::ALL)
+            .is_empty());
+        // Providers without review or CI status never flag anything
+        assert!(pr("OPEN", None, None)
+            .attention_reasons(AttentionTrigger::ALL)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_pull_request_number_from_url() {
+        assert_eq!(pr_with_url("https://github.com/o/r/pull/42").number(), Some("42"));
+        assert_eq!(
+            pr_with_url("https://bitbucket.org/ws/repo/pull-requests/7").number(),
+            Some("7")
+        );
+        assert_eq!(
+            pr_with_url("https://git.acme.com/projects/P/repos/r/pull-requests/133/overview").number(),
+            Some("133")
+        );
+        assert_eq!(pr_with_url("https://example.com/whatever").number(), None);
+    }
+
+    fn cloud_pr(links: serde_json::Value) -> bitbucket_api::BitbucketPullRequest {
+        let repository = json!({ "name": "repo", "full_name": "ws/repo", "uuid": "{1}" });
+        serde_json::from_value(json!({
+            "id": 7,
+            "title": "Add login",
+            "state": "OPEN",
+            "author": { "display_name": "Alice", "uuid": "{2}" },
+            "source": { "branch": { "name": "feature" }, "repository": repository },
+            "destination": { "branch": { "name": "main" }, "repository": repository },
+            "created_on": "2024-05-01T12:00:00Z",
+            "updated_on": "2024-05-01T12:00:00Z",
+            "links": links,
+        }))
+        .unwrap()
+    }
+
+    fn data_center_pr(links: serde_json::Value) -> bitbucket_data_center_api::BitbucketDataCenterPullRequest {
+        let pr_ref = |branch: &str| {
+            json!({
+                "id": format!("refs/heads/{}", branch),
+                "displayId": branch,
+                "latestCommit": "abc123",
+                "type": "BRANCH",
+                "repository": {
+                    "slug": "repo",
+                    "name": "repo",
+                    "id": 1,
+                    "project": { "key": "PROJ", "name": "Project", "id": 1 },
+                },
+            })
+        };
+        serde_json::from_value(json!({
+            "id": 133,
+            "version": 0,
+            "title": "Add login",
+            "state": "OPEN",
+            "open": true,
+            "closed": false,
+            "author": {
+                "user": { "name": "alice", "displayName": "Alice", "id": 1, "slug": "alice" },
+                "role": "AUTHOR",
+                "approved": false,
+                "status": "UNAPPROVED",
+            },
+            "fromRef": pr_ref("feature"),
+            "toRef": pr_ref("main"),
+            "createdDate": 0,
+            "updatedDate": 0,
+            "links": links,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_bitbucket_urls_prefer_links() {
+        let pr = cloud_pr(json!({ "html": { "href": "https://bitbucket.org/ws/repo/pull-requests/7" } }));
+        assert_eq!(
+            extract_bitbucket_cloud_url(&pr),
+            "https://bitbucket.org/ws/repo/pull-requests/7"
+        );
+
+        let pr = data_center_pr(json!({
+            "self": [{ "href": "https://git.acme.com/projects/PROJ/repos/repo/pull-requests/133" }]
+        }));
+        assert_eq!(
+            extract_bitbucket_data_center_url(&pr, "https://ignored.example"),
+            "https://git.acme.com/projects/PROJ/repos/repo/pull-requests/133"
+        );
+    }
+
+    #[test]
+    fn test_bitbucket_urls_without_links() {
+        assert_eq!(
+            extract_bitbucket_cloud_url(&cloud_pr(json!({}))),
+            "https://bitbucket.org/ws/repo/pull-requests/7"
+        );
+        // A malformed link is no better than a missing one
+        assert_eq!(
+            extract_bitbucket_cloud_url(&cloud_pr(json!({ "html": "not-an-object" }))),
+            "https://bitbucket.org/ws/repo/pull-requests/7"
+        );
+
+        let url = extract_bitbucket_data_center_url(&data_center_pr(json!({})), "https://git.acme.com/");
+        assert_eq!(url, "https://git.acme.com/projects/PROJ/repos/repo/pull-requests/133");
+        assert_eq!(pr_with_url(&url).number(), Some("133"));
     }
 }
@@ -0,0 +1,146 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::cli::Provider;
+use crate::{bitbucket_api, bitbucket_data_center_api, github, utils};
+
+const CACHE_FILENAME: &str = "identity.json";
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedUser {
+    user: String,
+    checked_at: DateTime<Utc>,
+}
+
+/// Who the configured credentials belong to, in the form pull request authors are reported in.
+/// Looked up once a day per provider account; `gwt list --mine` would otherwise spend a request on it every run.
+pub async fn current_user(
+    platform: Provider,
+    owner_or_workspace: &str,
+    repo: &str,
+    github_client: &Option<github::GitHubClient>,
+    bitbucket_client: &Option<bitbucket_api::BitbucketClient>,
+    bitbucket_data_center_client: &Option<bitbucket_data_center_api::BitbucketDataCenterClient>,
+) -> Result<String> {
+    // Bitbucket Cloud tokens are stored per repository, so each may belong to someone else
+    let key = match platform {
+        Provider::Github => "github".to_string(),
+        Provider::BitbucketCloud => bitbucket_cloud_key(owner_or_workspace, repo),
+        Provider::BitbucketDataCenter => format!(
+            "bitbucket-data-center:{}",
+            bitbucket_data_center_client
+                .as_ref()
+                .map_or("", |client| client.base_url())
+        ),
+    };
+
+    let cache_path = utils::cache_file(CACHE_FILENAME);
+    if let Some(user) = cache_path
+        .as_deref()
+        .and_then(|path| read_cached_user(path, &key, Utc::now()))
+    {
+        return Ok(user);
+    }
+
+    let user = match platform {
+        Provider::Github => github_client
+            .as_ref()
+            .context("GitHub is not connected")?
+            .get_current_user()?,
+        Provider::BitbucketCloud => {
+            bitbucket_client
+                .as_ref()
+                .context("Bitbucket Cloud is not connected")?
+                .get_current_user()
+                .await?
+        }
+        Provider::BitbucketDataCenter => {
+            bitbucket_data_center_client
+                .as_ref()
+                .context("Bitbucket Data Center is not connected")?
+                .get_current_user()
+                .await?
+        }
+    };
+
+    if let Some(path) = &cache_path {
+        write_cached_user(path, &key, &user, Utc::now());
+    }
+    Ok(user)
+}
+
+/// Stop trusting the cached user of `workspace/repo`'s Bitbucket Cloud token, e.g. after logging out
+pub fn forget_bitbucket_cloud_user(workspace: &str, repo: &str) {
+    if let Some(path) = utils::cache_file(CACHE_FILENAME) {
+        forget(&path, &bitbucket_cloud_key(workspace, repo));
+    }
+}
+
+fn bitbucket_cloud_key(workspace: &str, repo: &str) -> String {
+    format!("bitbucket-cloud:{}/{}", workspace, repo)
+}
+
+fn read_cache(path: &Path) -> BTreeMap<String, CachedUser> {
+    utils::read_json_cache(path).unwrap_or_default()
+}
+
+fn read_cached_user(path: &Path, key: &str, now: DateTime<Utc>) -> Option<String> {
+    read_cache(path)
+        .remove(key)
+        .filter(|cached| utils::cache_is_fresh(cached.checked_at, now))
+        .map(|cached| cached.user)
+}
+
+fn write_cached_user(path: &Path, key: &str, user: &str, now: DateTime<Utc>) {
+    let mut cache = read_cache(path);
+    cache.insert(
+        key.to_string(),
+        CachedUser {
+            user: user.to_string(),
+            checked_at: now,
+        },
+    );
+    utils::write_json_cache(path, &cache);
+}
+
+fn forget(path: &Path, key: &str) {
+    let mut cache = read_cache(path);
+    if cache.remove(key).is_some() {
+        utils::write_json_cache(path, &cache);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_cached_user_per_key_and_expiry() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("gwt").join(CACHE_FILENAME);
+        let now = Utc::now();
+
+        assert_eq!(read_cached_user(&path, "github", now), None);
+
+        write_cached_user(&path, "github", "octocat", now);
+        write_cached_user(&path, "bitbucket-cloud:acme/app", "{1234}", now);
+        assert_eq!(read_cached_user(&path, "github", now).as_deref(), Some("octocat"));
+        assert_eq!(
+            read_cached_user(&path, "bitbucket-cloud:acme/app", now).as_deref(),
+            Some("{1234}")
+        );
+
+        let tomorrow = now + Duration::hours(utils::CACHE_TTL_HOURS);
+        assert_eq!(read_cached_user(&path, "github", tomorrow), None);
+
+        // Forgetting one account leaves the others cached
+        forget(&path, "bitbucket-cloud:acme/app");
+        assert_eq!(read_cached_user(&path, "bitbucket-cloud:acme/app", now), None);
+        assert_eq!(read_cached_user(&path, "github", now).as_deref(), Some("octocat"));
+    }
+}
@@ -0,0 +1,154 @@
+use serde::Serialize;
+use std::path::Path;
+
+use super::list_helpers::PullRequestInfo;
+
+/// Bumped only for changes that break consumers; new fields and sections keep the version
+pub const JSON_VERSION: u32 = 1;
+
+/// `gwt list --json` output. Always an object so the format can grow without breaking consumers.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListJson<'a> {
+    pub version: u32,
+    pub worktrees: Vec<WorktreeJson<'a>>,
+    pub remote_pull_requests: Vec<RemotePullRequestJson<'a>>,
+}
+
+/// One line of `gwt list --json-lines`: an entry of `ListJson`, tagged with what it is
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ListJsonLine<'a> {
+    Worktree(WorktreeJson<'a>),
+    RemotePullRequest(RemotePullRequestJson<'a>),
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorktreeJson<'a> {
+    /// `null` for bare and detached worktrees
+    pub branch: Option<&'a str>,
+    pub path: &'a Path,
+    pub head: &'a str,
+    pub bare: bool,
+    pub detached: bool,
+    /// Only present with `--since`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commits_since: Option<u32>,
+    pub unpushed: u32,
+    /// Tracked branch, e.g. `origin/feature`; `null` without one
+    pub upstream: Option<&'a str>,
+    pub outside_project_root: bool,
+    /// The worktree's branch was deleted out from under it
+    pub orphaned: bool,
+    /// Its pull request has changes requested or failing checks (per `needsAttention`), or
+    /// merge conflicts with `--conflicts`
+    pub needs_attention: bool,
+    pub locked: bool,
+    /// `null` when unlocked or locked without a reason
+    pub lock_reason: Option<&'a str>,
+    /// Author of the checked-out commit; `null` for bare worktrees
+    pub last_commit_author: Option<&'a str>,
+    /// ISO 8601 author date of the checked-out commit
+    pub last_commit_date: Option<&'a str>,
+    /// Untracked, non-ignored files and directories; `null` for bare worktrees
+    pub untracked_files: Option<u32>,
+    pub pull_request: Option<PullRequestJson<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemotePullRequestJson<'a> {
+    pub branch: &'a str,
+    pub pull_request: PullRequestJson<'a>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PullRequestJson<'a> {
+    pub number: Option<u64>,
+    pub status: &'a str,
+    pub title: &'a str,
+    pub url: &'a str,
+    /// e.g. `CHANGES_REQUESTED`; `null` where not fetched (Bitbucket)
+    pub review_status: Option<&'a str>,
+    /// `SUCCESS`, `FAILURE` or `PENDING`; `null` where not fetched or without checks
+    pub ci_status: Option<&'a str>,
+    /// `MERGEABLE`, `CONFLICTING` or `UNKNOWN`; `null` where not fetched (Bitbucket, remote pull requests)
+    pub mergeable: Option<&'a str>,
+    /// GitHub login, Bitbucket Cloud account UUID or Data Center username of whoever opened it
+    pub author: Option<&'a str>,
+    /// RFC 3339; `null` when the provider didn't report it
+    pub updated_at: Option<String>,
+}
+
+impl<'a> From<&'a PullRequestInfo> for PullRequestJson<'a> {
+    fn from(info: &'a PullRequestInfo) -> Self {
+        PullRequestJson {
+            number: info.number().and_then(|number| number.parse().ok()),
+            status: &info.status,
+            title: &info.title,
+            url: &info.url,
+            review_status: info.review_status.as_deref(),
+            ci_status: info.ci_status.as_deref(),
+            mergeable: info.mergeable.as_deref(),
+            author: info.author.as_deref(),
+            updated_at: info.updated_at.map(|time| time.to_rfc3339()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_json_shape() {
+        let info = PullRequestInfo {
+            url: "https://github.com/owner/repo/pull/42".to_string(),
+            status: "OPEN".to_string(),
+            title: "Add login".to_string(),
+            ..Default::default()
+        };
+        let output = ListJson {
+            version: JSON_VERSION,
+            worktrees: vec![WorktreeJson {
+                branch: Some("feature/login"),
+                path: Path::new("/work/feature/login"),
+                head: "abc123",
+                bare: false,
+                detached: false,
+                commits_since: None,
+                unpushed: 0,
+                upstream: Some("origin/feature/login"),
+                outside_project_root: false,
+                orphaned: false,
+                needs_attention: false,
+                locked: false,
+                lock_reason: None,
+                last_commit_author: Some("Alice"),
+                last_commit_date: Some("2024-05-01T12:00:00+02:00"),
+                untracked_files: Some(0),
+                pull_request: Some((&info).into()),
+            }],
+            remote_pull_requests: vec![],
+        };
+
+        let value = serde_json::to_value(&output).unwrap();
+        assert_eq!(value["version"], 1);
+        assert_eq!(value["worktrees"][0]["branch"], "feature/login");
+        assert_eq!(value["worktrees"][0]["pullRequest"]["number"], 42);
+        assert!(value["worktrees"][0].get("commitsSince").is_none());
+        assert_eq!(value["worktrees"][0]["lastCommitAuthor"], "Alice");
+        assert_eq!(value["remotePullRequests"], serde_json::json!([]));
+
+        let line = serde_json::to_value(ListJsonLine::RemotePullRequest(RemotePullRequestJson {
+            branch: "fix/typo",
+            pull_request: (&info).into(),
+        }))
+        .unwrap();
+        assert_eq!(line["kind"], "remotePullRequest");
+        assert_eq!(line["branch"], "fix/typo");
+        assert_eq!(line["pullRequest"]["number"], 42);
+    }
+}
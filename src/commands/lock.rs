@@ -0,0 +1,61 @@
+use anyhow::{bail, Result};
+
+use crate::commands::remove::{find_target_worktree, get_branch_display, lock_reason_suffix};
+use crate::config::PathStyle;
+use crate::{git, project, ui};
+
+/// `git worktree lock` the worktree for `branch_name` (the current one if `None`)
+pub fn lock(branch_name: Option<&str>, reason: Option<&str>, path_style: Option<PathStyle>) -> Result<()> {
+    let (git_dir, worktree) = resolve(branch_name, path_style)?;
+    let branch = get_branch_display(&worktree);
+
+    if let Some(existing) = &worktree.locked {
+        bail!(
+            "Worktree for '{}' is already locked{}",
+            branch,
+            lock_reason_suffix(existing)
+        );
+    }
+
+    let path = worktree.path.to_str().unwrap();
+    match reason {
+        Some(reason) => git::execute_capture(&["worktree", "lock", "--reason", reason, path], Some(&git_dir))?,
+        None => git::execute_capture(&["worktree", "lock", path], Some(&git_dir))?,
+    };
+
+    ui::success(format!(
+        "Locked worktree for '{}'{}",
+        branch,
+        lock_reason_suffix(reason.unwrap_or_default())
+    ));
+    Ok(())
+}
+
+/// `git worktree unlock` the worktree for `branch_name` (the current one if `None`)
+pub fn unlock(branch_name: Option<&str>, path_style: Option<PathStyle>) -> Result<()> {
+    let (git_dir, worktree) = resolve(branch_name, path_style)?;
+    let branch = get_branch_display(&worktree);
+
+    if worktree.locked.is_none() {
+        bail!("Worktree for '{}' is not locked", branch);
+    }
+
+    git::execute_capture(&["worktree", "unlock", worktree.path.to_str().unwrap()], Some(&git_dir))?;
+
+    ui::success(format!("Unlocked worktree for '{}'", branch));
+    Ok(())
+}
+
+/// A directory to run git from and the target worktree, resolved the same way as `gwt remove`
+fn resolve(branch_name: Option<&str>, path_style: Option<PathStyle>) -> Result<(std::path::PathBuf, git::Worktree)> {
+    let git_dir = project::find_git_directory()?;
+    let worktrees = git::list_worktrees(Some(&git_dir))?;
+    let paths = project::PathDisplay::new(path_style)?;
+
+    let worktree = find_target_worktree(&worktrees, branch_name, &paths)?;
+    if worktree.bare {
+        bail!("The bare repository is not a worktree and can't be locked.");
+    }
+
+    Ok((git_dir, worktree.clone()))
+}
@@ -1,6 +1,16 @@
 pub mod add;
+pub mod add_setup;
 pub mod auth;
+pub mod config;
 pub mod init;
 pub mod list;
+pub mod list_format;
 pub mod list_helpers;
+pub mod list_identity;
+pub mod list_json;
+pub mod lock;
+pub mod move_all;
 pub mod remove;
+pub mod status;
+pub mod switch;
+pub mod upgrade;
@@ -0,0 +1,182 @@
+use anyhow::{bail, Context, Result};
+use colored::Colorize;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::config::PathStyle;
+use crate::{git, project, ui};
+
+/// Move the whole project directory to `new_root`, then repair the links between the
+/// repository and its worktrees, which git stores as absolute paths
+pub fn run(new_root: &Path, path_style: Option<PathStyle>) -> Result<()> {
+    let project_root = fs::canonicalize(project::find_project_root()?)?;
+    let new_root = absolute(new_root)?;
+    check_destination(&project_root, &new_root)?;
+
+    // Everything git needs is looked up before the paths it was found through go away
+    let git_dir = project::find_git_directory()?;
+    let worktrees = git::list_worktrees(Some(&git_dir))?;
+    let common_dir = git::get_common_dir(Some(&git_dir))?
+        .ok_or_else(|| anyhow::anyhow!("Could not find the repository's git directory"))?;
+
+    ui::step(format!(
+        "Moving {} to {}...",
+        project_root.display(),
+        new_root.display()
+    ));
+    move_dir(&project_root, &new_root)?;
+
+    let relocate = |path: &Path| relocated(path, &project_root, &new_root);
+
+    // A standard clone keeps its repository in `<main>/.git`; a mirror is a bare `<repo>.git`
+    let repository = relocate(&common_dir);
+    let repair_from = match repository.file_name() {
+        Some(name) if name == ".git" => repository.parent().unwrap_or(&repository).to_path_buf(),
+        _ => repository.clone(),
+    };
+    let linked: Vec<PathBuf> = worktrees
+        .iter()
+        .filter(|wt| !wt.bare)
+        .map(|wt| relocate(&wt.path))
+        .filter(|path| path != &repair_from)
+        .collect();
+
+    if !linked.is_empty() {
+        ui::step(format!("Repairing {} worktree link(s)...", linked.len()));
+        let mut args = vec!["worktree", "repair"];
+        args.extend(linked.iter().filter_map(|path| path.to_str()));
+        git::execute_capture(&args, Some(&repair_from)).with_context(|| {
+            format!(
+                "The project was moved, but 'git worktree repair' failed. Run it yourself from {}",
+                repair_from.display()
+            )
+        })?;
+    }
+
+    let shown_path = project::PathDisplay::new(path_style)?.show(&new_root);
+    ui::success(format!("Moved project to {}", shown_path));
+    println!("  {}", format!("cd {}", shown_path).cyan());
+
+    Ok(())
+}
+
+fn absolute(path: &Path) -> Result<PathBuf> {
+    Ok(if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()?.join(path)
+    })
+}
+
+/// The destination must be new or an empty directory, outside the project, in a directory that exists
+fn check_destination(project_root: &Path, new_root: &Path) -> Result<()> {
+    if new_root.exists() {
+        let is_empty_dir = new_root.is_dir() && fs::read_dir(new_root)?.next().is_none();
+        if !is_empty_dir {
+            bail!("Destination {} already exists and is not empty", new_root.display());
+        }
+    }
+
+    let parent = new_root
+        .parent()
+        .ok_or_else(|| anyhow::anyhow!("Can't move the project to {}", new_root.display()))?;
+    let parent = fs::canonicalize(parent)
+        .with_context(|| format!("Destination directory {} does not exist", parent.display()))?;
+    if parent.starts_with(project_root) {
+        bail!("Can't move the project into itself ({})", new_root.display());
+    }
+
+    Ok(())
+}
+
+/// Where `path` ends up once `from` has moved to `to`; paths outside `from` stay put
+fn relocated(path: &Path, from: &Path, to: &Path) -> PathBuf {
+    match path.strip_prefix(from) {
+        Ok(rest) => to.join(rest),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+/// Rename `from` to `to`, copying instead when they are on different filesystems
+fn move_dir(from: &Path, to: &Path) -> Result<()> {
+    // Only an empty directory can be there (see check_destination); renaming onto it isn't portable
+    if to.exists() {
+        fs::remove_dir(to)?;
+    }
+
+    match fs::rename(from, to) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+            ui::step("Destination is on another disk, copying...");
+            copy_dir(from, to).with_context(|| {
+                format!(
+                    "Copying to {} failed; the project at {} is untouched",
+                    to.display(),
+                    from.display()
+                )
+            })?;
+            fs::remove_dir_all(from)
+                .with_context(|| format!("Copied the project, but could not delete {}", from.display()))
+        }
+        Err(e) => Err(e).with_context(|| format!("Failed to move {} to {}", from.display(), to.display())),
+    }
+}
+
+/// Recursively copy a directory, keeping symlinks as symlinks
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else if file_type.is_symlink() {
+            copy_symlink(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(from)?, to)
+}
+
+#[cfg(windows)]
+fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    let link = fs::read_link(from)?;
+    if from.is_dir() {
+        std::os::windows::fs::symlink_dir(link, to)
+    } else {
+        std::os::windows::fs::symlink_file(link, to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relocated() {
+        let from = Path::new("/old/project");
+        let to = Path::new("/new/project");
+        assert_eq!(
+            relocated(Path::new("/old/project/feature/login"), from, to),
+            PathBuf::from("/new/project/feature/login")
+        );
+        assert_eq!(relocated(from, from, to), PathBuf::from("/new/project"));
+        // Worktrees placed outside the project don't move
+        assert_eq!(
+            relocated(Path::new("/elsewhere/hotfix"), from, to),
+            PathBuf::from("/elsewhere/hotfix")
+        );
+        assert_eq!(
+            relocated(Path::new("/old/project-two"), from, to),
+            PathBuf::from("/old/project-two")
+        );
+    }
+}
@@ -1,15 +1,71 @@
 use anyhow::{bail, Result};
 use colored::Colorize;
-use std::fs;
-use std::io::{self, Write};
-use std::path::{Path, PathBuf};
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
 
+use crate::config::{Confirmations, GitWorktreeConfig, PathStyle};
 use crate::git;
 use crate::hooks;
+use crate::project;
+use crate::ui;
+
+/// Branches whose worktrees can be removed but whose branch is never deleted
+const MAIN_BRANCHES: [&str; 4] = ["main", "master", "dev", "develop"];
+
+/// How `remove_one` treats the branch of the removed worktree
+pub struct RemoveOptions<'a> {
+    /// Ask before force-deleting a branch with unmerged changes
+    pub confirm: bool,
+    /// Force-delete a branch with unmerged changes without asking
+    pub force: bool,
+    /// Leave the branch in place
+    pub keep_branch: bool,
+    pub paths: &'a project::PathDisplay,
+}
+
+/// What removing a worktree does to its branch
+enum BranchFate {
+    /// Detached worktree, no branch to delete
+    Detached,
+    /// Orphaned worktree, whose branch was deleted with raw git
+    AlreadyDeleted,
+    /// Branch without commits (e.g. from `gwt add --orphan`), which has no ref to delete
+    Unborn,
+    /// One of `MAIN_BRANCHES`, never deleted
+    Main,
+    Kept,
+    Delete,
+}
+
+fn branch_fate(worktree: &git::Worktree, git_working_dir: &Path, keep_branch: bool) -> BranchFate {
+    let branch_gone = |branch_ref: &String| {
+        git::local_branch_refs(git_working_dir).is_ok_and(|existing| !existing.contains(branch_ref))
+    };
+    if worktree.branch.is_none() {
+        BranchFate::Detached
+    } else if git::is_unborn_branch(worktree) {
+        BranchFate::Unborn
+    } else if worktree.branch.as_ref().is_some_and(branch_gone) {
+        BranchFate::AlreadyDeleted
+    } else if MAIN_BRANCHES.contains(&get_branch_display(worktree)) {
+        BranchFate::Main
+    } else if keep_branch {
+        BranchFate::Kept
+    } else {
+        BranchFate::Delete
+    }
+}
 
-pub fn run(branch_name: Option<&str>) -> Result<()> {
+pub fn run(
+    branch_name: Option<&str>,
+    force_protected: bool,
+    force_branch: bool,
+    assume_yes: bool,
+    dry_run: bool,
+    path_style: Option<PathStyle>,
+) -> Result<()> {
     // Find a git directory to work with
-    let git_dir = find_git_directory()?;
+    let git_dir = project::find_git_directory()?;
 
     // Get the list of worktrees
     let worktrees = git::list_worktrees(Some(&git_dir))?;
@@ -19,8 +75,10 @@ pub fn run(branch_name: Option<&str>) -> Result<()> {
         return Ok(());
     }
 
+    let paths = project::PathDisplay::new(path_style)?;
+
     // Find the worktree to remove
-    let target_worktree = find_target_worktree(&worktrees, branch_name)?;
+    let target_worktree = find_target_worktree(&worktrees, branch_name, &paths)?;
 
     // Check if this is the bare repository
     if target_worktree.bare {
@@ -29,40 +87,64 @@ pub fn run(branch_name: Option<&str>) -> Result<()> {
 
     let branch_display = get_branch_display(target_worktree);
 
+    // `git worktree remove --force` refuses locked worktrees, which would look like a busy directory
+    if let Some(reason) = &target_worktree.locked {
+        bail!(
+            "Worktree for '{}' is locked{}. Run 'gwt unlock {}' first.",
+            branch_display,
+            lock_reason_suffix(reason),
+            branch_display
+        );
+    }
+
+    let config = GitWorktreeConfig::find_config()?.map(|(_, config)| config);
+    let confirmations = config
+        .as_ref()
+        .and_then(|config| config.confirmations)
+        .unwrap_or_default();
+
+    // Protected branches need an explicit flag before we even ask for confirmation
+    let protected_pattern = match (&target_worktree.branch, &config) {
+        (Some(_), Some(config)) => config.protected_pattern(branch_display).map(str::to_string),
+        _ => None,
+    };
+    if let Some(pattern) = &protected_pattern {
+        if !force_protected {
+            bail!(
+                "Branch '{}' is protected (matches '{}' in protectedBranches). Use --force-protected to remove it.",
+                branch_display,
+                pattern
+            );
+        }
+    }
+
     // Show what will be removed
     println!("{}", "About to remove worktree:".cyan().bold());
-    println!("  {}: {}", "Path".dimmed(), target_worktree.path.display());
+    println!("  {}: {}", "Path".dimmed(), paths.show(&target_worktree.path));
     println!("  {}: {}", "Branch".dimmed(), branch_display.green());
 
+    // Local-only commits are the one thing removal can lose for good, so call them out
+    let unpushed = if target_worktree.branch.is_some() && !config.as_ref().is_some_and(GitWorktreeConfig::is_mirror) {
+        git::count_unpushed_commits(&target_worktree.path).unwrap_or(0)
+    } else {
+        0
+    };
+    if unpushed > 0 {
+        ui::warn(format!(
+            "'{}' has {} commit(s) that are not pushed to any remote.",
+            branch_display, unpushed
+        ));
+    }
+
     // Check if we're currently in the worktree being removed
     let current_dir = std::env::current_dir()?;
     let will_remove_current = current_dir.starts_with(&target_worktree.path);
 
     if will_remove_current {
-        println!(
-            "\n{}",
-            "⚠️  You are currently in this worktree. You will be moved to the project root after removal.".yellow()
-        );
-    }
-
-    // Ask for confirmation
-    print!("\n{}", "Are you sure you want to remove this worktree? (y/N): ".cyan());
-    io::stdout().flush()?;
-
-    let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
-    let confirmation = input.trim().to_lowercase();
-
-    if confirmation != "y" && confirmation != "yes" {
-        println!("{}", "Removal cancelled.".yellow());
-        return Ok(());
+        ui::warn("You are currently in this worktree. You will be moved to the project root after removal.");
     }
 
-    // Find project root
-    let project_root = find_project_root(&target_worktree.path)?;
-
     // Find another worktree to run git commands from
-    let main_branches = ["main", "master", "dev", "develop"];
     let git_working_dir = worktrees
         .iter()
         .find(|wt| {
@@ -71,10 +153,7 @@ pub fn run(branch_name: Option<&str>) -> Result<()> {
                 && wt
                     .branch
                     .as_ref()
-                    .map(|b| {
-                        let clean_branch = if b.starts_with("refs/heads/") { &b[11..] } else { b };
-                        main_branches.contains(&clean_branch)
-                    })
+                    .map(|b| MAIN_BRANCHES.contains(&clean_branch_name(b)))
                     .unwrap_or(false)
         })
         .or_else(|| {
@@ -83,179 +162,432 @@ pub fn run(branch_name: Option<&str>) -> Result<()> {
         })
         .ok_or_else(|| anyhow::anyhow!("No other worktrees found to execute git command from."))?;
 
-    // Remove the worktree
-    println!("\n{}", "Removing worktree...".cyan());
-    git::execute_streaming(
-        &["worktree", "remove", target_worktree.path.to_str().unwrap(), "--force"],
-        Some(&git_working_dir.path),
-    )?;
+    let should_prompt = |destructive: bool| !assume_yes && confirmations.prompts_for(destructive);
 
-    println!(
-        "{}",
-        format!("✓ Worktree removed: {}", target_worktree.path.display()).green()
-    );
+    // Not being asked never means force: without a prompt an unmerged branch is kept
+    let options = RemoveOptions {
+        confirm: should_prompt(true),
+        force: force_branch,
+        keep_branch: false,
+        paths: &paths,
+    };
 
-    // Delete the branch if it's not a main branch
-    if !main_branches.contains(&branch_display) {
-        // First try to delete the branch normally
-        match git::execute_capture(&["branch", "-d", branch_display], Some(&git_working_dir.path)) {
-            Ok(_) => {
-                println!("{}", format!("✓ Branch deleted: {}", branch_display).green());
-            }
-            Err(e) => {
-                // If normal deletion fails, check if it's because of unmerged changes
-                if e.to_string().contains("not fully merged") {
-                    println!(
-                        "{}",
-                        format!("⚠️  Branch '{}' has unmerged changes", branch_display).yellow()
-                    );
-                    
-                    // Ask for confirmation to force delete
-                    print!("{}", "Force delete the branch? (y/N): ".cyan());
-                    io::stdout().flush()?;
-                    
-                    let mut input = String::new();
-                    io::stdin().read_line(&mut input)?;
-                    let force_delete = input.trim().to_lowercase();
-                    
-                    if force_delete == "y" || force_delete == "yes" {
-                        match git::execute_streaming(&["branch", "-D", branch_display], Some(&git_working_dir.path)) {
-                            Ok(_) => {
-                                println!("{}", format!("✓ Branch force deleted: {}", branch_display).green());
-                            }
-                            Err(e) => {
-                                println!(
-                                    "{}",
-                                    format!("❌ Failed to delete branch '{}': {}", branch_display, e).red()
-                                );
-                            }
-                        }
-                    } else {
-                        println!(
-                            "{}",
-                            format!("⚠️  Branch '{}' was not deleted", branch_display).yellow()
-                        );
-                    }
-                } else {
-                    // Some other error occurred
-                    println!(
-                        "{}",
-                        format!("❌ Failed to delete branch '{}': {}", branch_display, e).red()
-                    );
-                }
-            }
-        }
-    } else {
-        println!(
-            "{}",
-            format!("✓ Branch: {} (preserved - main branch)", branch_display).green()
+    if dry_run {
+        let project_root = project::find_project_root()?;
+        return print_dry_run(target_worktree, &git_working_dir.path, &project_root, &options);
+    }
+
+    // Without prompts, a script piping into gwt must say --yes explicitly
+    if confirmations == Confirmations::None && !assume_yes && !io::stdin().is_terminal() {
+        bail!("Not removing without confirmation: stdin is not a terminal. Pass --yes to remove anyway.");
+    }
+
+    // Ask for confirmation; protected branches must be confirmed by typing the branch name
+    let mut confirmed = if !should_prompt(protected_pattern.is_some() || unpushed > 0) {
+        true
+    } else if protected_pattern.is_some() {
+        print!(
+            "\n{}",
+            format!(
+                "'{}' is a protected branch. Type the branch name to confirm removal: ",
+                branch_display
+            )
+            .yellow()
         );
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        input.trim() == branch_display
+    } else {
+        println!();
+        ui::ask_yes_no("Are you sure you want to remove this worktree?")?
+    };
+
+    // Unpushed work needs a second, explicit yes
+    if confirmed && unpushed > 0 && should_prompt(true) {
+        confirmed = ui::ask_yes_no("Remove it anyway? Unpushed commits may be lost.")?;
     }
 
-    // If we removed the current worktree, change to project root before executing hooks
-    if will_remove_current {
-        std::env::set_current_dir(&project_root)?;
+    if !confirmed {
+        println!("{}", "Removal cancelled.".yellow());
+        return Ok(());
     }
 
-    // Execute post-remove hooks
-    hooks::execute_hooks(
-        "postRemove",
-        &project_root,
-        &[
-            ("branchName", branch_display),
-            ("worktreePath", target_worktree.path.to_str().unwrap()),
-        ],
-    )?;
+    // Find project root
+    let project_root = project::find_project_root()?;
+
+    println!();
+    remove_one(target_worktree, &git_working_dir.path, &project_root, &options)?;
 
     // If we removed the current worktree, show message about moving to project root
     if will_remove_current {
+        ui::success(format!("Please navigate to project root: {}", project_root.display()));
+    }
+
+    Ok(())
+}
+
+/// `--all-merged`: remove every worktree whose branch is merged into the main branch, except
+/// main, protected, kept (`keepBranches`), locked and dirty ones
+pub fn run_all_merged(assume_yes: bool, dry_run: bool, path_style: Option<PathStyle>) -> Result<()> {
+    let (config_path, config) = GitWorktreeConfig::find_config()?
+        .ok_or_else(|| anyhow::anyhow!("No git-worktree-config.yaml found. Run 'gwt init' first."))?;
+    let project_root = config_path.parent().unwrap_or(Path::new(".")).to_path_buf();
+    let git_dir = project::find_git_directory()?;
+    let worktrees = git::list_worktrees(Some(&git_dir))?;
+    let paths = project::PathDisplay::new(path_style)?;
+
+    let target = if config.is_mirror() {
+        config.main_branch.clone()
+    } else {
+        git::remote_branch(config.remote(), &config.main_branch)
+    };
+    if !git::commit_exists(&git_dir, &target) {
+        bail!("'{}' does not exist. Run 'git fetch' and try again.", target);
+    }
+
+    let mut merged = Vec::new();
+    let mut skipped = Vec::new();
+    // A branch without commits has nothing that could have been merged
+    for worktree in worktrees
+        .iter()
+        .filter(|wt| !wt.bare && wt.branch.is_some() && !git::is_unborn_branch(wt))
+    {
+        let branch = get_branch_display(worktree);
+        if branch == config.main_branch
+            || MAIN_BRANCHES.contains(&branch)
+            || !git::is_merged_into(&git_dir, branch, &target)
+        {
+            continue;
+        }
+
+        // A fresh branch is an ancestor of the target without being merged work; removing it
+        // would throw away the files its hooks set up
+        let skip_reason = if let Some(pattern) = config.keep_pattern(branch) {
+            Some(format!("matches '{}' in keepBranches", pattern))
+        } else if let Some(pattern) = config.protected_pattern(branch) {
+            Some(format!("matches '{}' in protectedBranches", pattern))
+        } else if worktree.locked.is_some() {
+            Some("locked".to_string())
+        } else if git::has_uncommitted_changes(&worktree.path).unwrap_or(true) {
+            Some("has uncommitted changes".to_string())
+        } else if !git::has_own_history(&git_dir, branch, &target) {
+            Some("no commits yet".to_string())
+        } else {
+            None
+        };
+
+        match skip_reason {
+            Some(reason) => skipped.push((branch, reason)),
+            None => merged.push(worktree),
+        }
+    }
+
+    if merged.is_empty() && skipped.is_empty() {
         println!(
             "{}",
-            format!("✓ Please navigate to project root: {}", project_root.display()).green()
+            format!("No worktrees with branches merged into {}.", target).yellow()
         );
+        return Ok(());
     }
 
-    Ok(())
-}
+    if !merged.is_empty() {
+        println!("{}", format!("Merged into {}:", target).cyan().bold());
+        for worktree in &merged {
+            println!(
+                "  {} -> {}",
+                get_branch_display(worktree).green(),
+                paths.show(&worktree.path).dimmed()
+            );
+        }
+    }
+    if !skipped.is_empty() {
+        println!("{}", "Skipped:".yellow().bold());
+        for (branch, reason) in &skipped {
+            println!("  {} {}", branch, format!("({})", reason).dimmed());
+        }
+    }
 
-fn find_git_directory() -> Result<PathBuf> {
-    let current_dir = std::env::current_dir()?;
+    if merged.is_empty() {
+        return Ok(());
+    }
 
-    // First, try to find git-worktree-config.yaml to determine if we're in a worktree project
-    let mut search_path = current_dir.clone();
-    let mut project_root: Option<PathBuf> = None;
+    // Run git from a worktree that stays
+    let git_working_dir = worktrees
+        .iter()
+        .find(|wt| !merged.iter().any(|removed| removed.path == wt.path))
+        .map(|wt| wt.path.clone())
+        .ok_or_else(|| anyhow::anyhow!("No other worktrees found to execute git command from."))?;
+    let options = RemoveOptions {
+        confirm: false,
+        force: false,
+        keep_branch: false,
+        paths: &paths,
+    };
+
+    if dry_run {
+        println!(
+            "\n{}",
+            "Dry run, nothing was removed. Removing them would:".cyan().bold()
+        );
+        for worktree in &merged {
+            println!("  {}:", get_branch_display(worktree).green());
+            print_removal_plan(worktree, &git_working_dir, &project_root, &options, "    ")?;
+        }
+        return Ok(());
+    }
 
-    loop {
-        let config_path = search_path.join("git-worktree-config.yaml");
-        if config_path.exists() {
-            project_root = Some(search_path);
-            break;
+    let confirmations = config.confirmations.unwrap_or_default();
+    if confirmations == Confirmations::None && !assume_yes && !io::stdin().is_terminal() {
+        bail!("Not removing without confirmation: stdin is not a terminal. Pass --yes to remove anyway.");
+    }
+    if !assume_yes && confirmations.prompts_for(false) {
+        println!();
+        if !ui::ask_yes_no(&format!("Remove these {} worktree(s)?", merged.len()))? {
+            println!("{}", "Removal cancelled.".yellow());
+            return Ok(());
         }
+    }
 
-        if !search_path.pop() {
-            break;
+    let will_remove_current = {
+        let current_dir = std::env::current_dir()?;
+        merged.iter().any(|wt| current_dir.starts_with(&wt.path))
+    };
+
+    let mut failed = 0;
+    for worktree in &merged {
+        println!();
+        let removed = remove_one(worktree, &git_working_dir, &project_root, &options);
+        // One stuck worktree shouldn't stop the rest
+        if let Err(e) = removed {
+            ui::error(format!("{:#}", e));
+            failed += 1;
         }
     }
 
-    if let Some(project_root) = project_root {
-        // Found config file, look for any existing worktree to use for git commands
-        let entries = fs::read_dir(&project_root)?;
+    if will_remove_current {
+        ui::success(format!("Please navigate to project root: {}", project_root.display()));
+    }
+    if failed > 0 {
+        bail!("{} of {} worktree(s) could not be removed", failed, merged.len());
+    }
+    Ok(())
+}
 
-        for entry in entries {
-            let entry = entry?;
-            if entry.file_type()?.is_dir() {
-                let dir_path = entry.path();
-                let git_path = dir_path.join(".git");
-                if git_path.exists() {
-                    return Ok(dir_path);
-                }
-            }
+/// Remove `worktree` and its branch, then run the postRemove hooks. Prompts and checks about
+/// whether it *should* be removed are the caller's job; `git_working_dir` must be another worktree.
+pub fn remove_one(
+    worktree: &git::Worktree,
+    git_working_dir: &Path,
+    project_root: &Path,
+    options: &RemoveOptions,
+) -> Result<()> {
+    let branch_display = get_branch_display(worktree);
+    let will_remove_current = std::env::current_dir()?.starts_with(&worktree.path);
+    // Decided up front: telling a branch without commits apart needs the worktree's index
+    let branch_fate = branch_fate(worktree, git_working_dir, options.keep_branch);
+
+    ui::step("Removing worktree...");
+    git::execute_streaming(
+        &["worktree", "remove", worktree.path.to_str().unwrap(), "--force"],
+        Some(git_working_dir),
+    )
+    .map_err(|error| removal_failed(error, &worktree.path))?;
+
+    ui::success(format!("Worktree removed: {}", options.paths.show(&worktree.path)));
+
+    // Delete the branch if it's not a main branch
+    match branch_fate {
+        BranchFate::Detached => ui::success("Detached worktree, no branch to delete"),
+        BranchFate::AlreadyDeleted => ui::success(format!("Branch: {} (already deleted)", branch_display)),
+        BranchFate::Unborn => ui::success(format!(
+            "Branch: {} (had no commits, nothing to delete)",
+            branch_display
+        )),
+        BranchFate::Main => ui::success(format!("Branch: {} (preserved - main branch)", branch_display)),
+        BranchFate::Kept => ui::success(format!("Branch: {} (kept)", branch_display)),
+        BranchFate::Delete => delete_branch(branch_display, git_working_dir, options)?,
+    }
+
+    // If we removed the current worktree, change to project root before executing hooks
+    if will_remove_current {
+        std::env::set_current_dir(project_root)?;
+    }
+
+    hooks::execute_hooks(
+        "postRemove",
+        project_root,
+        &[
+            ("branchName", branch_display),
+            ("worktreePath", worktree.path.to_str().unwrap()),
+        ],
+    )
+}
+
+/// Say what removing `worktree` would do, without touching anything or prompting
+fn print_dry_run(
+    worktree: &git::Worktree,
+    git_working_dir: &Path,
+    project_root: &Path,
+    options: &RemoveOptions,
+) -> Result<()> {
+    println!("\n{}", "Dry run, nothing was removed. Removing it would:".cyan().bold());
+    print_removal_plan(worktree, git_working_dir, project_root, options, "  ")
+}
+
+/// The steps removing `worktree` takes, one `indent`ed line each: its directory, its branch and
+/// the postRemove hooks
+fn print_removal_plan(
+    worktree: &git::Worktree,
+    git_working_dir: &Path,
+    project_root: &Path,
+    options: &RemoveOptions,
+    indent: &str,
+) -> Result<()> {
+    let branch = get_branch_display(worktree);
+
+    println!("{}- remove the worktree directory", indent);
+    match branch_fate(worktree, git_working_dir, false) {
+        BranchFate::Detached => println!("{}- leave no branch behind (detached worktree)", indent),
+        BranchFate::AlreadyDeleted => println!("{}- skip the branch, it is already deleted", indent),
+        BranchFate::Unborn => println!("{}- skip branch '{}', it has no commits yet", indent, branch),
+        BranchFate::Main => println!("{}- keep branch '{}' (main branch)", indent, branch),
+        BranchFate::Kept => println!("{}- keep branch '{}'", indent, branch),
+        BranchFate::Delete if git::is_branch_merged(git_working_dir, branch) => {
+            println!("{}- delete branch '{}'", indent, branch)
         }
+        BranchFate::Delete if options.force => println!(
+            "{}- force-delete branch '{}' {}",
+            indent,
+            branch,
+            "(unmerged changes)".yellow()
+        ),
+        BranchFate::Delete if options.confirm => println!(
+            "{}- ask before force-deleting branch '{}' {}",
+            indent,
+            branch,
+            "(unmerged changes)".yellow()
+        ),
+        BranchFate::Delete => println!(
+            "{}- keep branch '{}' {}",
+            indent,
+            branch,
+            "(unmerged changes; --force-branch deletes it)".yellow()
+        ),
+    }
 
-        bail!("No existing worktrees found in project root. Create one first using gwt init.");
+    let hooks = hooks::render_hooks(
+        "postRemove",
+        project_root,
+        &[
+            ("branchName", branch),
+            ("worktreePath", worktree.path.to_str().unwrap()),
+        ],
+    )?;
+    if hooks.is_empty() {
+        println!("{}- run no postRemove hooks", indent);
     } else {
-        // No config found, check if we're directly in a git repository
-        if let Some(git_root) = git::get_git_root()? {
-            Ok(git_root)
-        } else {
-            bail!("Not in a git repository or project root with git-worktree-config.yaml");
+        println!("{}- run postRemove hooks:", indent);
+        for hook in hooks {
+            println!("{}    {}", indent, hook);
         }
     }
-}
 
-fn find_project_root(worktree_path: &Path) -> Result<PathBuf> {
-    let mut search_path = worktree_path.to_path_buf();
+    Ok(())
+}
 
-    // Go up one level from the worktree to find the project root
-    if search_path.pop() {
-        let config_path = search_path.join("git-worktree-config.yaml");
-        if config_path.exists() {
-            return Ok(search_path);
+fn delete_branch(branch: &str, git_working_dir: &Path, options: &RemoveOptions) -> Result<()> {
+    // First try to delete the branch normally
+    let error = match git::execute_capture(&["branch", "-d", branch], Some(git_working_dir)) {
+        Ok(_) => {
+            ui::success(format!("Branch deleted: {}", branch));
+            return Ok(());
         }
+        Err(e) => e,
+    };
+
+    // If normal deletion fails, check if it's because of unmerged changes
+    if !error.to_string().contains("not fully merged") {
+        ui::error(format!("Failed to delete branch '{}': {}", branch, error));
+        return Ok(());
     }
 
-    // If not found, try the current directory
-    let current_dir = std::env::current_dir()?;
-    let mut search_path = current_dir;
+    ui::warn(format!("Branch '{}' has unmerged changes", branch));
+    let force_delete = options.force || (options.confirm && ui::ask_yes_no("Force delete the branch?")?);
 
-    loop {
-        let config_path = search_path.join("git-worktree-config.yaml");
-        if config_path.exists() {
-            return Ok(search_path);
+    if !force_delete {
+        ui::warn(format!("Branch '{}' was not deleted", branch));
+    } else {
+        match git::execute_streaming(&["branch", "-D", branch], Some(git_working_dir)) {
+            Ok(_) => ui::success(format!("Branch force deleted: {}", branch)),
+            Err(e) => ui::error(format!("Failed to delete branch '{}': {}", branch, e)),
         }
+    }
+
+    Ok(())
+}
+
+/// Explain a failed `git worktree remove`, which usually means another program holds files
+/// in the worktree open (Windows refuses to delete those)
+fn removal_failed(error: anyhow::Error, path: &Path) -> anyhow::Error {
+    let mut message = format!(
+        "Could not remove the worktree at {}. A program may still be using it (an editor, \
+         terminal or dev server); close it and try again.",
+        path.display()
+    );
 
-        if !search_path.pop() {
-            break;
+    let processes = processes_in(path);
+    if !processes.is_empty() {
+        message.push_str("\nRunning inside it:");
+        for (pid, name) in processes {
+            message.push_str(&format!("\n  {} (pid {})", name, pid));
         }
     }
 
-    bail!("Could not find project root with git-worktree-config.yaml");
+    error.context(message)
+}
+
+/// Other processes whose working directory is inside `dir`, as (pid, name)
+#[cfg(target_os = "linux")]
+fn processes_in(dir: &Path) -> Vec<(u32, String)> {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    let own_pid = std::process::id();
+
+    let mut processes: Vec<(u32, String)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let pid: u32 = entry.file_name().to_str()?.parse().ok()?;
+            let cwd = std::fs::read_link(entry.path().join("cwd")).ok()?;
+            if pid == own_pid || !cwd.starts_with(&dir) {
+                return None;
+            }
+            let name = std::fs::read_to_string(entry.path().join("comm")).ok()?;
+            Some((pid, name.trim().to_string()))
+        })
+        .collect();
+    processes.sort();
+    processes
+}
+
+/// Finding other processes' working directories needs platform APIs we don't link against
+#[cfg(not(target_os = "linux"))]
+fn processes_in(_dir: &Path) -> Vec<(u32, String)> {
+    Vec::new()
 }
 
-fn find_target_worktree<'a>(worktrees: &'a [git::Worktree], branch_name: Option<&str>) -> Result<&'a git::Worktree> {
+/// The worktree named by `branch_name` (branch or directory name), or the current one
+pub fn find_target_worktree<'a>(
+    worktrees: &'a [git::Worktree],
+    branch_name: Option<&str>,
+    paths: &project::PathDisplay,
+) -> Result<&'a git::Worktree> {
     match branch_name {
         None => find_current_worktree(worktrees),
-        Some(target_branch) => find_worktree_by_branch(worktrees, target_branch),
+        Some(target_branch) => find_worktree_by_branch(worktrees, target_branch, paths),
     }
 }
 
@@ -264,59 +596,35 @@ fn find_current_worktree(worktrees: &[git::Worktree]) -> Result<&git::Worktree>
     worktrees
         .iter()
         .find(|wt| current_dir.starts_with(&wt.path))
-        .ok_or_else(|| anyhow::anyhow!("Not in a git worktree. Please specify a branch to remove."))
+        .ok_or_else(|| anyhow::anyhow!("Not in a git worktree. Please specify a branch."))
 }
 
-fn find_worktree_by_branch<'a>(worktrees: &'a [git::Worktree], target_branch: &str) -> Result<&'a git::Worktree> {
-    // First try to find by branch name
-    if let Some(worktree) = find_by_branch_name(worktrees, target_branch) {
+fn find_worktree_by_branch<'a>(
+    worktrees: &'a [git::Worktree],
+    target_branch: &str,
+    paths: &project::PathDisplay,
+) -> Result<&'a git::Worktree> {
+    if let Some(worktree) = git::find_worktree(worktrees, target_branch) {
         return Ok(worktree);
     }
 
-    // Then try to find by path
-    if let Some(worktree) = find_by_path_name(worktrees, target_branch) {
-        return Ok(worktree);
-    }
-
-    // Not found, show available worktrees
-    show_available_worktrees(worktrees);
-    bail!("Worktree for '{}' not found", target_branch)
-}
-
-fn find_by_branch_name<'a>(worktrees: &'a [git::Worktree], target_branch: &str) -> Option<&'a git::Worktree> {
-    worktrees.iter().find(|wt| {
-        wt.branch
-            .as_ref()
-            .map(|b| clean_branch_name(b) == target_branch)
-            .unwrap_or(false)
-    })
+    // The list belongs with the error, on stderr, not in output a script may be reading
+    bail!(
+        "Worktree for '{}' not found\n\nAvailable worktrees:\n{}",
+        target_branch,
+        available_worktrees(worktrees, paths)
+    )
 }
 
-fn find_by_path_name<'a>(worktrees: &'a [git::Worktree], target_branch: &str) -> Option<&'a git::Worktree> {
-    worktrees.iter().find(|wt| {
-        wt.path
-            .file_name()
-            .and_then(|name| name.to_str())
-            .map(|name| name == target_branch)
-            .unwrap_or(false)
-    })
-}
-
-fn show_available_worktrees(worktrees: &[git::Worktree]) {
-    println!("{}", "Error: Worktree not found.".red());
-    println!("\n{}", "Available worktrees:".yellow());
-
-    for worktree in worktrees {
-        let branch_display = get_branch_display(worktree);
-        println!(
-            "  {} -> {}",
-            branch_display.green(),
-            worktree.path.display().to_string().dimmed()
-        );
-    }
+fn available_worktrees(worktrees: &[git::Worktree], paths: &project::PathDisplay) -> String {
+    worktrees
+        .iter()
+        .map(|worktree| format!("  {} -> {}", get_branch_display(worktree), paths.show(&worktree.path)))
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
-fn get_branch_display(worktree: &git::Worktree) -> &str {
+pub fn get_branch_display(worktree: &git::Worktree) -> &str {
     worktree
         .branch
         .as_ref()
@@ -330,6 +638,103 @@ fn get_branch_display(worktree: &git::Worktree) -> &str {
         })
 }
 
+/// ` (reason)` for a lock with a reason, nothing for one without
+pub fn lock_reason_suffix(reason: &str) -> String {
+    if reason.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", reason)
+    }
+}
+
 fn clean_branch_name(branch: &str) -> &str {
     branch.strip_prefix("refs/heads/").unwrap_or(branch)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::process::Command;
+    use tempfile::{tempdir, TempDir};
+
+    fn run_git(dir: &Path, args: &[&str]) {
+        let output = Command::new("git").args(args).current_dir(dir).output().unwrap();
+        assert!(output.status.success(), "git {:?} failed: {:?}", args, output);
+    }
+
+    /// A repository on `main` plus a worktree for `branch`, optionally with a commit main lacks
+    fn setup(branch: &str, unmerged: bool) -> (TempDir, PathBuf, git::Worktree) {
+        let temp_dir = tempdir().unwrap();
+        let main = temp_dir.path().join("main");
+        std::fs::create_dir(&main).unwrap();
+        run_git(&main, &["init", "-q", "-b", "main"]);
+        run_git(&main, &["config", "user.email", "test@example.com"]);
+        run_git(&main, &["config", "user.name", "Test"]);
+        run_git(&main, &["commit", "-q", "--allow-empty", "-m", "initial"]);
+
+        let path = temp_dir.path().join(branch);
+        run_git(&main, &["worktree", "add", "-q", "-b", branch, path.to_str().unwrap()]);
+        if unmerged {
+            run_git(&path, &["commit", "-q", "--allow-empty", "-m", "work"]);
+        }
+
+        let worktrees = git::list_worktrees(Some(&main)).unwrap();
+        let worktree = git::find_worktree(&worktrees, branch).unwrap().clone();
+        (temp_dir, main, worktree)
+    }
+
+    fn branch_exists(main: &Path, branch: &str) -> bool {
+        git::branch_exists(main, "origin", branch).unwrap().0
+    }
+
+    fn options(paths: &project::PathDisplay, force: bool, keep_branch: bool) -> RemoveOptions<'_> {
+        RemoveOptions {
+            confirm: false,
+            force,
+            keep_branch,
+            paths,
+        }
+    }
+
+    #[test]
+    fn test_remove_one_options() {
+        let paths = project::PathDisplay::new(Some(PathStyle::Absolute)).unwrap();
+
+        // Merged branches are deleted without forcing
+        let (temp_dir, main, worktree) = setup("merged", false);
+        remove_one(&worktree, &main, temp_dir.path(), &options(&paths, false, false)).unwrap();
+        assert!(!worktree.path.exists());
+        assert!(!branch_exists(&main, "merged"));
+
+        // Unmerged branches survive unless forced
+        let (temp_dir, main, worktree) = setup("unmerged", true);
+        remove_one(&worktree, &main, temp_dir.path(), &options(&paths, false, false)).unwrap();
+        assert!(!worktree.path.exists());
+        assert!(branch_exists(&main, "unmerged"));
+
+        let (temp_dir, main, worktree) = setup("forced", true);
+        remove_one(&worktree, &main, temp_dir.path(), &options(&paths, true, false)).unwrap();
+        assert!(!branch_exists(&main, "forced"));
+
+        // keep_branch wins over force
+        let (temp_dir, main, worktree) = setup("kept", true);
+        remove_one(&worktree, &main, temp_dir.path(), &options(&paths, true, true)).unwrap();
+        assert!(!worktree.path.exists());
+        assert!(branch_exists(&main, "kept"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_processes_in_finds_programs_running_in_a_directory() {
+        let dir = tempdir().unwrap();
+        let mut child = Command::new("sleep").arg("10").current_dir(dir.path()).spawn().unwrap();
+
+        let processes = processes_in(dir.path());
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        assert_eq!(processes, vec![(child.id(), "sleep".to_string())]);
+        assert!(processes_in(&dir.path().join("missing")).is_empty());
+    }
+}
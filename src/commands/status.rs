@@ -0,0 +1,48 @@
+use anyhow::Result;
+use colored::Colorize;
+use std::path::Path;
+
+use crate::config::GitWorktreeConfig;
+use crate::{git, project};
+
+/// What plain `gwt` prints: a short summary of the project, or how to start one
+pub fn run() -> Result<()> {
+    let Some((config_path, config)) = GitWorktreeConfig::find_config()? else {
+        println!("{}", "Not inside a gwt project.".yellow());
+        println!(
+            "Run {} to set one up, or {} for all commands.",
+            "gwt init <repo-url>".cyan(),
+            "gwt --help".cyan()
+        );
+        return Ok(());
+    };
+
+    let project_root = config_path.parent().unwrap_or(Path::new("."));
+    let worktree_count = project::find_git_directory()
+        .and_then(|git_dir| git::list_worktrees(Some(&git_dir)))
+        .map(|worktrees| worktrees.iter().filter(|wt| !wt.bare).count())
+        .ok();
+
+    println!(
+        "{}: {} ({})",
+        "Project".dimmed(),
+        project_root.display(),
+        config.source_control
+    );
+    println!("{}: {}", "Main branch".dimmed(), config.main_branch.green());
+    match worktree_count {
+        Some(count) => println!("{}: {}", "Worktrees".dimmed(), count),
+        None => println!(
+            "{}: {}",
+            "Worktrees".dimmed(),
+            "unknown (no git worktree found)".yellow()
+        ),
+    }
+    println!(
+        "\nRun {} for details or {} for all commands.",
+        "gwt list".cyan(),
+        "gwt --help".cyan()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,134 @@
+use anyhow::{bail, Result};
+use colored::Colorize;
+use std::path::Path;
+
+use crate::commands::add::{self, AddOptions};
+use crate::config::{GitWorktreeConfig, PathStyle};
+use crate::git;
+use crate::hooks;
+use crate::project;
+use crate::ui;
+use crate::utils::clean_branch_name;
+
+pub fn run(branch_name: &str, create: bool, print_path: bool, safe: bool, path_style: Option<PathStyle>) -> Result<()> {
+    if branch_name.is_empty() {
+        bail!("Error: Branch name is required\nUsage: gwt switch <branch-name>");
+    }
+
+    let git_dir = project::find_git_directory()?;
+    let worktrees = git::list_worktrees(Some(&git_dir))?;
+    let stash_on_switch =
+        GitWorktreeConfig::find_config()?.is_some_and(|(_, config)| config.stash_on_switch == Some(true));
+
+    // Switching only moves the shell; uncommitted changes stay behind in the worktree being left
+    let existing_path = git::find_worktree(&worktrees, branch_name).map(|worktree| worktree.path.clone());
+    let left_dirty = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| git::find_worktree_containing(&worktrees, &cwd).cloned())
+        .filter(|current| existing_path.as_ref() != Some(&current.path))
+        .filter(|current| git::has_uncommitted_changes(&current.path).unwrap_or(false));
+
+    let stash_from = left_dirty
+        .as_ref()
+        .and_then(|current| current.branch.as_deref().map(clean_branch_name))
+        .filter(|_| stash_on_switch);
+    if let Some(current) = left_dirty.as_ref().filter(|_| safe && stash_from.is_none()) {
+        bail!(
+            "{} has uncommitted changes. Commit or stash them before switching, or leave out --safe.",
+            current.path.display()
+        );
+    }
+
+    let target_path = match existing_path {
+        Some(path) => path,
+        None if create => {
+            // add::run decides between local, remote and new branches
+            add::run(
+                branch_name,
+                &AddOptions {
+                    path_style,
+                    ..AddOptions::default()
+                },
+            )?;
+
+            let worktrees = git::list_worktrees(Some(&git_dir))?;
+            git::find_worktree(&worktrees, branch_name)
+                .map(|worktree| worktree.path.clone())
+                .ok_or_else(|| anyhow::anyhow!("Worktree for '{}' was not found after creating it", branch_name))?
+        }
+        None => {
+            bail!(
+                "No worktree found for '{}'. Use 'gwt switch --create {}' to create it.",
+                branch_name,
+                branch_name
+            );
+        }
+    };
+
+    // Stash only once the target exists, so a failed switch never hides the changes
+    if let Some(current) = &left_dirty {
+        match &stash_from {
+            Some(name) => {
+                git::stash_push(&current.path, &stash_message(name))?;
+                ui::step(format!(
+                    "Stashed uncommitted changes in '{}'; switching back restores them",
+                    name
+                ));
+            }
+            None => ui::warn(format!(
+                "{} has uncommitted changes; they stay there and don't come along",
+                current.path.display()
+            )),
+        }
+    }
+
+    if stash_on_switch {
+        restore_stash(&target_path, branch_name);
+    }
+
+    hooks::execute_hooks(
+        "postSwitch",
+        &target_path,
+        &[
+            ("branchName", branch_name),
+            ("worktreePath", target_path.to_str().unwrap()),
+        ],
+    )?;
+
+    // The shell wrapper reads the last line of output, so the path must come last.
+    // It has to cd from anywhere, so it always gets the absolute path.
+    if print_path {
+        println!("{}", target_path.display());
+    } else {
+        let shown_path = project::PathDisplay::new(path_style)?.show(&target_path);
+        ui::success(format!("Worktree for '{}': {}", branch_name, shown_path));
+        println!("  {}", format!("cd {}", shown_path).cyan());
+    }
+
+    Ok(())
+}
+
+/// Marks a stash made by `stashOnSwitch` for the branch it was taken from
+fn stash_message(branch: &str) -> String {
+    format!("gwt: switched away from {}", branch)
+}
+
+/// Pop the changes stashed when leaving `branch`; a failed pop leaves them in the stash list
+fn restore_stash(worktree_path: &Path, branch: &str) {
+    let branch = git::list_worktrees(Some(worktree_path))
+        .ok()
+        .and_then(|worktrees| git::find_worktree(&worktrees, branch).and_then(|wt| wt.branch.clone()))
+        .map_or_else(|| branch.to_string(), |branch| clean_branch_name(&branch));
+
+    let stash = match git::find_stash(worktree_path, &stash_message(&branch)) {
+        Ok(Some(stash)) => stash,
+        Ok(None) | Err(_) => return,
+    };
+    match git::stash_pop(worktree_path, &stash) {
+        Ok(()) => ui::step(format!("Restored the changes stashed when leaving '{}'", branch)),
+        Err(e) => ui::warn(format!(
+            "Could not restore the changes stashed when leaving '{}' ({}): {}",
+            branch, stash, e
+        )),
+    }
+}
@@ -0,0 +1,259 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::{ui, utils};
+
+const CRATE_NAME: &str = env!("CARGO_PKG_NAME");
+const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+const CACHE_FILENAME: &str = "version-check.json";
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionCache {
+    /// `None` while no check has succeeded yet
+    #[serde(default)]
+    pub latest_version: Option<String>,
+    /// Last attempt, successful or not
+    pub checked_at: DateTime<Utc>,
+}
+
+impl VersionCache {
+    pub fn is_fresh(&self, now: DateTime<Utc>) -> bool {
+        utils::cache_is_fresh(self.checked_at, now)
+    }
+
+    /// The cache after a check that failed at `now`: still the last known version, but not
+    /// retried until the TTL is up, so being offline doesn't spawn a check on every command
+    fn after_failed_check(previous: Option<VersionCache>, now: DateTime<Utc>) -> Self {
+        VersionCache {
+            latest_version: previous.and_then(|cache| cache.latest_version),
+            checked_at: now,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct CratesIoResponse {
+    #[serde(rename = "crate")]
+    krate: CratesIoCrate,
+}
+
+#[derive(Debug, Deserialize)]
+struct CratesIoCrate {
+    max_stable_version: Option<String>,
+    max_version: String,
+}
+
+#[tokio::main]
+pub async fn run(refresh: bool, background: bool) -> Result<()> {
+    // Spawned by the update notice: just refresh the cache, quietly
+    if background {
+        let cache = match fetch_latest_version().await {
+            Ok(latest) => VersionCache {
+                latest_version: Some(latest),
+                checked_at: Utc::now(),
+            },
+            Err(_) => VersionCache::after_failed_check(read_cache(), Utc::now()),
+        };
+        write_cache(&cache);
+        return Ok(());
+    }
+
+    let cached = if refresh { None } else { read_cache() };
+
+    let cached_version = cached
+        .filter(|cache| cache.is_fresh(Utc::now()))
+        .and_then(|cache| cache.latest_version);
+    let latest_version = match cached_version {
+        Some(latest) => latest,
+        None => {
+            ui::step("Checking for the latest gwt release...");
+            let latest = fetch_latest_version().await?;
+            write_cache(&VersionCache {
+                latest_version: Some(latest.clone()),
+                checked_at: Utc::now(),
+            });
+            latest
+        }
+    };
+
+    if is_newer(&latest_version, CURRENT_VERSION) {
+        println!(
+            "{}",
+            format!(
+                "A new version of gwt is available: {} → {}",
+                CURRENT_VERSION, latest_version
+            )
+            .yellow()
+        );
+        println!("\nTo update, run:");
+        println!("  {}", format!("cargo install {}", CRATE_NAME).cyan());
+    } else {
+        ui::success(format!("gwt {} is up to date", CURRENT_VERSION));
+    }
+
+    Ok(())
+}
+
+/// Whether the opt-in update notice is enabled via `GWT_CHECK_UPDATES` or `checkUpdates`
+pub fn update_check_enabled(config_enabled: impl FnOnce() -> bool) -> bool {
+    match std::env::var("GWT_CHECK_UPDATES") {
+        Ok(value) => is_truthy(&value),
+        Err(_) => config_enabled(),
+    }
+}
+
+fn is_truthy(value: &str) -> bool {
+    matches!(value.trim().to_lowercase().as_str(), "1" | "true" | "yes" | "on")
+}
+
+/// Print a one-line notice when the cached check knows of a newer release.
+///
+/// A stale cache is refreshed by a detached `gwt upgrade` process, so the current
+/// command never waits on the network; its result shows up on a later run.
+pub fn notify_if_outdated() {
+    let cached = read_cache();
+
+    if !cached.as_ref().is_some_and(|cache| cache.is_fresh(Utc::now())) {
+        spawn_background_refresh();
+    }
+
+    if let Some(latest_version) = cached.and_then(|cache| cache.latest_version) {
+        if is_newer(&latest_version, CURRENT_VERSION) {
+            // stderr keeps the notice out of output that scripts parse
+            eprintln!(
+                "{}",
+                format!(
+                    "gwt {} is available (you have {}). Run 'cargo install {}' to update.",
+                    latest_version, CURRENT_VERSION, CRATE_NAME
+                )
+                .dimmed()
+            );
+        }
+    }
+}
+
+fn spawn_background_refresh() {
+    let Ok(exe) = std::env::current_exe() else {
+        return;
+    };
+
+    let _ = std::process::Command::new(exe)
+        .args(["upgrade", "--background"])
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn();
+}
+
+/// Query crates.io for the latest published version
+pub async fn fetch_latest_version() -> Result<String> {
+    let url = format!("https://crates.io/api/v1/crates/{}", CRATE_NAME);
+
+    // crates.io rejects requests without a User-Agent
+    let response = reqwest::Client::new()
+        .get(&url)
+        .header("User-Agent", format!("gwt/{}", CURRENT_VERSION))
+        .header("Accept", "application/json")
+        .send()
+        .await
+        .context("Failed to reach crates.io")?;
+
+    if !response.status().is_success() {
+        anyhow::bail!("crates.io returned status {} for {}", response.status(), CRATE_NAME);
+    }
+
+    let body: CratesIoResponse = response.json().await.context("Failed to parse crates.io response")?;
+
+    Ok(body.krate.max_stable_version.unwrap_or(body.krate.max_version))
+}
+
+/// Location of the cached version check
+pub fn cache_path() -> Option<PathBuf> {
+    utils::cache_file(CACHE_FILENAME)
+}
+
+pub fn read_cache() -> Option<VersionCache> {
+    utils::read_json_cache(&cache_path()?)
+}
+
+pub fn write_cache(cache: &VersionCache) {
+    if let Some(path) = cache_path() {
+        utils::write_json_cache(&path, cache);
+    }
+}
+
+/// Compare two `major.minor.patch` versions, ignoring any pre-release suffix
+pub fn is_newer(candidate: &str, current: &str) -> bool {
+    match (parse_version(candidate), parse_version(current)) {
+        (Some(candidate), Some(current)) => candidate > current,
+        _ => false,
+    }
+}
+
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let version = version.trim().trim_start_matches('v');
+    let core = version.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|part| part.parse::<u64>());
+
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().unwrap_or(Ok(0)).ok()?;
+    let patch = parts.next().unwrap_or(Ok(0)).ok()?;
+
+    Some((major, minor, patch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("0.2.0", "0.1.0"));
+        assert!(is_newer("1.0.0", "0.9.9"));
+        assert!(is_newer("v0.1.1", "0.1.0"));
+        assert!(!is_newer("0.1.0", "0.1.0"));
+        assert!(!is_newer("0.1.0", "0.2.0"));
+        assert!(!is_newer("0.2.0-beta.1", "0.2.0"));
+        assert!(!is_newer("not-a-version", "0.1.0"));
+    }
+
+    #[test]
+    fn test_is_truthy() {
+        assert!(is_truthy("1"));
+        assert!(is_truthy("true"));
+        assert!(is_truthy(" YES "));
+        assert!(!is_truthy("0"));
+        assert!(!is_truthy("false"));
+        assert!(!is_truthy(""));
+    }
+
+    #[test]
+    fn test_version_cache_freshness() {
+        let now = Utc::now();
+        let fresh = VersionCache {
+            latest_version: Some("0.2.0".to_string()),
+            checked_at: now - Duration::hours(1),
+        };
+        let stale = VersionCache {
+            latest_version: Some("0.2.0".to_string()),
+            checked_at: now - Duration::hours(utils::CACHE_TTL_HOURS + 1),
+        };
+
+        assert!(fresh.is_fresh(now));
+        assert!(!stale.is_fresh(now));
+
+        // A failed check keeps what was known and waits out the TTL before retrying
+        let failed = VersionCache::after_failed_check(Some(stale), now);
+        assert_eq!(failed.latest_version.as_deref(), Some("0.2.0"));
+        assert!(failed.is_fresh(now));
+
+        let failed = VersionCache::after_failed_check(None, now);
+        assert_eq!(failed.latest_version, None);
+        assert!(failed.is_fresh(now));
+    }
+}
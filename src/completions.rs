@@ -1,10 +1,14 @@
 use anyhow::{anyhow, Result};
+use clap::ValueEnum;
 use clap_complete::Shell;
 use colored::Colorize;
 use std::env;
+use std::ffi::OsStr;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::ui;
+
 // Include the generated completion files at compile time
 const BASH_COMPLETION: &str = include_str!(concat!(env!("OUT_DIR"), "/completions/gwt.bash"));
 const ZSH_COMPLETION: &str = include_str!(concat!(env!("OUT_DIR"), "/completions/_gwt"));
@@ -23,6 +27,16 @@ pub fn get_completion_content(shell: Shell) -> &'static str {
     }
 }
 
+/// Every shell gwt can generate completions for, with `detected` moved to the front
+pub fn supported_shells(detected: Option<Shell>) -> Vec<Shell> {
+    let mut shells: Vec<Shell> = Shell::value_variants().to_vec();
+    if let Some(detected) = detected {
+        shells.retain(|shell| *shell != detected);
+        shells.insert(0, detected);
+    }
+    shells
+}
+
 pub fn detect_shell() -> Result<Shell> {
     if let Ok(shell_path) = env::var("SHELL") {
         if shell_path.contains("zsh") {
@@ -102,11 +116,11 @@ pub fn install_completions_for_shell(shell: Shell) -> Result<()> {
     // Write the completion file
     fs::write(&install_path, content)?;
 
-    println!(
-        "✓ Installed {} completions to: {}",
-        shell.to_string().green(),
-        install_path.display().to_string().cyan()
-    );
+    ui::success(format!(
+        "Installed {} completions to: {}",
+        shell,
+        install_path.display()
+    ));
 
     // Shell-specific setup
     match shell {
@@ -137,6 +151,70 @@ pub fn install_completions_for_shell(shell: Shell) -> Result<()> {
     Ok(())
 }
 
+/// `completions install --all`: install for every supported shell found on PATH.
+///
+/// Whether a shell is used is told from its program being on PATH rather than from its completion
+/// directory existing: zsh's and PowerShell's are usually created by the first install.
+pub fn install_completions_for_all_shells() -> Result<()> {
+    let path_var = env::var_os("PATH").unwrap_or_default();
+    let (mut installed, mut skipped, mut failed) = (0, 0, 0);
+
+    for &shell in Shell::value_variants() {
+        if !is_on_path(shell_program(shell), &path_var) {
+            println!("{}", format!("Skipped {}: not found on PATH", shell).dimmed());
+            skipped += 1;
+            continue;
+        }
+
+        // One shell failing (e.g. an unwritable directory) shouldn't stop the others
+        match install_completions_for_shell(shell) {
+            Ok(()) => installed += 1,
+            Err(e) => {
+                ui::error(format!("Could not install {} completions: {}", shell, e));
+                failed += 1;
+            }
+        }
+        println!();
+    }
+
+    let summary = install_summary(installed, skipped, failed);
+    if failed == 0 {
+        ui::success(summary);
+    } else {
+        ui::warn(summary);
+    }
+    Ok(())
+}
+
+/// One line counting the shells `--all` installed for, skipped and failed on
+fn install_summary(installed: usize, skipped: usize, failed: usize) -> String {
+    let mut summary = format!("Installed completions for {} shell(s)", installed);
+    if skipped > 0 {
+        summary.push_str(&format!(", skipped {} not on PATH", skipped));
+    }
+    if failed > 0 {
+        summary.push_str(&format!(", {} failed", failed));
+    }
+    summary
+}
+
+/// The executable that runs `shell`
+fn shell_program(shell: Shell) -> &'static str {
+    match shell {
+        Shell::Zsh => "zsh",
+        Shell::Fish => "fish",
+        Shell::PowerShell => "pwsh",
+        Shell::Elvish => "elvish",
+        _ => "bash",
+    }
+}
+
+/// Whether `program` is in one of the directories of `path_var` (a PATH value)
+fn is_on_path(program: &str, path_var: &OsStr) -> bool {
+    env::split_paths(path_var)
+        .any(|dir| dir.join(program).is_file() || (cfg!(windows) && dir.join(format!("{}.exe", program)).is_file()))
+}
+
 fn setup_zsh_completions() -> Result<()> {
     let home = env::var("HOME")?;
     let zshrc_path = PathBuf::from(&home).join(".zshrc");
@@ -148,7 +226,7 @@ fn setup_zsh_completions() -> Result<()> {
 
     if modified {
         fs::write(&zshrc_path, content)?;
-        println!("✓ Updated ~/.zshrc");
+        ui::success("Updated ~/.zshrc");
     }
 
     show_zsh_activation_instructions();
@@ -167,11 +245,11 @@ fn add_zsh_completion_config(content: &mut String, home: &str) -> Result<bool> {
     let fpath_dir = format!("{}/.local/share/zsh/site-functions", home);
 
     if content.contains(&fpath_dir) {
-        println!("\n✓ Completion path already configured in ~/.zshrc");
+        ui::success("Completion path already configured in ~/.zshrc");
         return Ok(false);
     }
 
-    println!("\n✓ Adding completion path to ~/.zshrc");
+    ui::success("Adding completion path to ~/.zshrc");
 
     // Ensure proper newline before adding content
     if !content.is_empty() && !content.ends_with('\n') {
@@ -218,3 +296,52 @@ pub fn check_completions_installed(shell: Shell) -> Result<bool> {
 
     Ok(true)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supported_shells_puts_detected_first() {
+        let shells = supported_shells(Some(Shell::Fish));
+        assert_eq!(shells[0], Shell::Fish);
+        assert_eq!(shells.len(), Shell::value_variants().len());
+        assert_eq!(shells.iter().filter(|shell| **shell == Shell::Fish).count(), 1);
+
+        assert_eq!(supported_shells(None), Shell::value_variants().to_vec());
+    }
+
+    #[test]
+    fn test_install_summary_counts_separately() {
+        assert_eq!(install_summary(5, 0, 0), "Installed completions for 5 shell(s)");
+        assert_eq!(
+            install_summary(2, 2, 1),
+            "Installed completions for 2 shell(s), skipped 2 not on PATH, 1 failed"
+        );
+    }
+
+    #[test]
+    fn test_is_on_path() {
+        let bin = tempfile::tempdir().unwrap();
+        fs::write(bin.path().join("fish"), "").unwrap();
+        let path_var = env::join_paths([Path::new("/nonexistent"), bin.path()]).unwrap();
+
+        assert!(is_on_path(shell_program(Shell::Fish), &path_var));
+        assert!(!is_on_path(shell_program(Shell::Elvish), &path_var));
+        assert!(!is_on_path("fish", OsStr::new("")));
+    }
+
+    #[test]
+    fn test_embedded_completions_offer_shell_values() {
+        // `completions install/generate <TAB>` should list every shell
+        for shell in [Shell::Bash, Shell::Zsh] {
+            let content = get_completion_content(shell);
+            for name in ["bash", "zsh", "fish", "powershell", "elvish"] {
+                assert!(content.contains(name), "{} completions are missing '{}'", shell, name);
+            }
+        }
+
+        // Fish asks the hidden helper instead
+        assert!(get_completion_content(Shell::Fish).contains("(gwt completions shells 2>/dev/null)"));
+    }
+}
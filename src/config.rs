@@ -1,82 +1,434 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
-use crate::cli::Provider;
+use crate::cli::{HookTemplate, Provider};
+use crate::git;
+use crate::ui;
+use crate::utils;
+
+/// Format version written to new configs. Any change adding a config key must bump it, so older
+/// gwt versions tell keys they don't know yet apart from typos.
+///
+/// 2: `keepBranches`, `listIgnoreBranches`, `pathStyle`, `checkUpdates`, `hostAliases`,
+/// `mainWorktreeName`, `confirmations`, `remoteName`, `defaultTrack`, `backgroundHooks`,
+/// `stashOnSwitch`, `submodules`, `defaultCommand`, `layout` and `needsAttention`
+pub const CONFIG_VERSION: u32 = 2;
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GitWorktreeConfig {
+    /// Config format version; `None` for configs written before it existed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<u32>,
     pub repository_url: String,
     pub main_branch: String,
     pub created_at: DateTime<Utc>,
-    pub source_control: String,
+    #[serde(with = "provider_name")]
+    pub source_control: Provider,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bitbucket_email: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub hooks: Option<Hooks>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pr_limit: Option<u32>,
+    /// Branches (glob patterns allowed) whose worktrees need --force-protected to remove
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protected_branches: Option<Vec<String>>,
+    /// Branches (glob patterns allowed) that bulk removal such as `--all-merged` always skips
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep_branches: Option<Vec<String>>,
+    /// Branches (glob patterns allowed) `gwt list` never looks up pull requests for, e.g. `dependabot/*`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub list_ignore_branches: Option<Vec<String>>,
+    /// Shared file to pull hook definitions from (relative to this config or `~`-expanded)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include: Option<String>,
+    /// How command output shows worktree paths (default: absolute)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path_style: Option<PathStyle>,
+    /// Print a notice when a newer gwt release is available (`GWT_CHECK_UPDATES` overrides)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub check_updates: Option<bool>,
+    /// Directory of the worktree created by `gwt init`, when not named after the main branch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub main_worktree_name: Option<String>,
+    /// SSH host aliases in `repositoryUrl` mapped to the provider's real host
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host_aliases: Option<BTreeMap<String, String>>,
+    /// When `gwt remove` asks for confirmation (default: always)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confirmations: Option<Confirmations>,
+    /// Name of the canonical remote, when it isn't `origin`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_name: Option<String>,
+    /// Whether `gwt add` sets new branches to track their base (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_track: Option<bool>,
+    /// Run `postAdd` hooks detached, logging to a file, instead of waiting for them
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background_hooks: Option<bool>,
+    /// Stash uncommitted changes when `gwt switch` leaves a worktree, and restore them on return
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stash_on_switch: Option<bool>,
+    /// Initialize submodules in every worktree `gwt add` creates (set by `gwt init --submodules`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub submodules: Option<bool>,
+    /// What bare `gwt` runs inside the project (default: status)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_command: Option<DefaultCommand>,
+    /// How the repository was cloned (default: standard)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout: Option<Layout>,
+    /// Pull request states that mark a worktree with ⚠ in `gwt list` (default: all of them)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub needs_attention: Option<Vec<AttentionTrigger>>,
+    /// Hooks loaded from `include`; kept apart so saving never inlines them
+    #[serde(skip)]
+    included_hooks: Option<Hooks>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Hooks {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub post_add: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub post_remove: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_switch: Option<Vec<String>>,
+}
+
+/// Whether worktree paths are printed in full or relative to the project root
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PathStyle {
+    #[default]
+    Absolute,
+    Relative,
+}
+
+impl PathStyle {
+    /// Style requested by the `--relative`/`--absolute` flags, if either was given
+    pub fn from_flags(relative: bool, absolute: bool) -> Option<Self> {
+        match (relative, absolute) {
+            (true, _) => Some(PathStyle::Relative),
+            (_, true) => Some(PathStyle::Absolute),
+            _ => None,
+        }
+    }
+}
+
+/// `sourceControl` is stored by provider name, e.g. `bitbucket-cloud`
+mod provider_name {
+    use super::Provider;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(provider: &Provider, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(provider)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Provider, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
+    }
+}
+
+/// A pull request state worth flagging in `gwt list`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AttentionTrigger {
+    /// A reviewer asked for changes
+    ChangesRequested,
+    /// At least one check failed
+    FailingChecks,
+}
+
+impl AttentionTrigger {
+    pub const ALL: &'static [AttentionTrigger] = &[AttentionTrigger::ChangesRequested, AttentionTrigger::FailingChecks];
+}
+
+/// The command bare `gwt` runs inside a project
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DefaultCommand {
+    /// A short summary of the project
+    #[default]
+    Status,
+    /// `gwt list` with no options
+    List,
+}
+
+/// How `gwt init` cloned the repository
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Layout {
+    /// A regular clone whose checkout is the first worktree
+    #[default]
+    Standard,
+    /// A `git clone --mirror` repository with every worktree added on top of it
+    Mirror,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Confirmations {
+    /// Never prompt (still refuses without `--yes` when stdin is not a terminal)
+    None,
+    /// Prompt only when work could be lost: unpushed commits, unmerged or protected branches
+    DestructiveOnly,
+    /// Prompt for every removal
+    #[default]
+    Always,
+}
+
+impl Confirmations {
+    /// Whether to ask before a step, given whether the step can lose work
+    pub fn prompts_for(self, destructive: bool) -> bool {
+        match self {
+            Confirmations::None => false,
+            Confirmations::DestructiveOnly => destructive,
+            Confirmations::Always => true,
+        }
+    }
+}
+
+/// Hook types gwt runs, as spelled in the config file
+pub const HOOK_TYPES: &[&str] = &["postAdd", "postRemove", "postSwitch"];
+
+/// Per-worktree overrides read from `.gwt.local.yaml`, layered on top of the project config
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hooks: Option<Hooks>,
+}
+
+/// Hook definitions shared between projects through the `include` key
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IncludedConfig {
+    hooks: Option<Hooks>,
+}
+
+impl Hooks {
+    /// The hooks a new config starts with; `None` for `HookTemplate::None`
+    pub fn template(template: HookTemplate) -> Option<Hooks> {
+        let commands = |commands: &[&str]| Some(commands.iter().map(|command| command.to_string()).collect());
+        let removed = commands(&["# echo 'Removed worktree for branch ${branchName}'"]);
+
+        match template {
+            HookTemplate::Default => Some(Hooks {
+                post_add: commands(&["# npm install"]),
+                post_remove: removed,
+                post_switch: None,
+            }),
+            HookTemplate::Node => Some(Hooks {
+                post_add: commands(&["npm install"]),
+                post_remove: removed,
+                post_switch: None,
+            }),
+            HookTemplate::Rust => Some(Hooks {
+                post_add: commands(&["cargo fetch"]),
+                post_remove: removed,
+                post_switch: None,
+            }),
+            HookTemplate::None => None,
+        }
+    }
+
+    /// Overlay `overrides` on these hooks; any hook type set in `overrides` replaces ours entirely
+    pub fn merged_with(&self, overrides: &Hooks) -> Hooks {
+        Hooks {
+            post_add: overrides.post_add.clone().or_else(|| self.post_add.clone()),
+            post_remove: overrides.post_remove.clone().or_else(|| self.post_remove.clone()),
+            post_switch: overrides.post_switch.clone().or_else(|| self.post_switch.clone()),
+        }
+    }
+
+    /// Commands for a hook type such as `postAdd`; `None` for unknown types
+    pub fn get(&self, hook_type: &str) -> Option<&Option<Vec<String>>> {
+        match hook_type {
+            "postAdd" => Some(&self.post_add),
+            "postRemove" => Some(&self.post_remove),
+            "postSwitch" => Some(&self.post_switch),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, hook_type: &str) -> Option<&mut Option<Vec<String>>> {
+        match hook_type {
+            "postAdd" => Some(&mut self.post_add),
+            "postRemove" => Some(&mut self.post_remove),
+            "postSwitch" => Some(&mut self.post_switch),
+            _ => None,
+        }
+    }
+}
+
+impl LocalConfig {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).context("Failed to read local config file")?;
+
+        let config = parse_yaml(path, &content, &["hooks"])?;
+        warn_unknown_keys(path, &content, &["hooks"], None);
+        Ok(config)
+    }
+
+    /// Find the local overrides for the worktree containing `dir`.
+    /// The search stops at the worktree root so overrides never leak between worktrees.
+    pub fn find(dir: &Path) -> Result<Option<Self>> {
+        let mut search_path = dir.to_path_buf();
+
+        loop {
+            let local_path = search_path.join(LOCAL_CONFIG_FILENAME);
+            if local_path.exists() {
+                return Ok(Some(Self::load(&local_path)?));
+            }
+
+            let is_boundary = search_path.join(".git").exists() || search_path.join(CONFIG_FILENAME).exists();
+            if is_boundary || !search_path.pop() {
+                return Ok(None);
+            }
+        }
+    }
 }
 
 impl GitWorktreeConfig {
     pub fn new(repository_url: String, main_branch: String, provider: Provider) -> Self {
-        // Convert provider enum to string
-        let source_control = match provider {
-            Provider::Github => "github".to_string(),
-            Provider::BitbucketCloud => "bitbucket-cloud".to_string(),
-            Provider::BitbucketDataCenter => "bitbucket-data-center".to_string(),
-        };
-
         Self {
+            version: Some(CONFIG_VERSION),
             repository_url,
             main_branch,
             created_at: Utc::now(),
-            source_control,
+            source_control: provider,
             bitbucket_email: None,
-            hooks: Some(Hooks {
-                post_add: Some(vec!["# npm install".to_string()]),
-                post_remove: Some(vec!["# echo 'Removed worktree for branch ${branchName}'".to_string()]),
-            }),
+            hooks: Hooks::template(HookTemplate::Default),
+            pr_limit: None,
+            protected_branches: None,
+            keep_branches: None,
+            list_ignore_branches: None,
+            include: None,
+            path_style: None,
+            check_updates: None,
+            main_worktree_name: None,
+            host_aliases: None,
+            confirmations: None,
+            remote_name: None,
+            default_track: None,
+            background_hooks: None,
+            stash_on_switch: None,
+            submodules: None,
+            default_command: None,
+            layout: None,
+            needs_attention: None,
+            included_hooks: None,
         }
     }
 
-    pub fn save(&self, path: &Path) -> Result<()> {
-        let yaml_string = serde_yaml::to_string(self).context("Failed to serialize config to YAML")?;
+    /// The `needsAttention` triggers; `needsAttention: []` turns the marker off
+    pub fn attention_triggers(&self) -> &[AttentionTrigger] {
+        self.needs_attention.as_deref().unwrap_or(AttentionTrigger::ALL)
+    }
+
+    /// Whether branches live directly in `refs/heads` of a mirror rather than under a remote
+    pub fn is_mirror(&self) -> bool {
+        self.layout == Some(Layout::Mirror)
+    }
+
+    /// The remote branches are fetched from and pushed to (`remoteName`, default `origin`)
+    pub fn remote(&self) -> &str {
+        self.remote_name.as_deref().unwrap_or(DEFAULT_REMOTE)
+    }
+
+    /// `repositoryUrl` with any `hostAliases` host replaced, for provider detection and API calls.
+    /// git itself keeps using the original URL so SSH config aliases still apply.
+    pub fn resolved_repository_url(&self) -> String {
+        match &self.host_aliases {
+            Some(aliases) => utils::resolve_host_alias(&self.repository_url, aliases),
+            None => self.repository_url.clone(),
+        }
+    }
+
+    /// The first `protectedBranches` pattern matching `branch`, if any
+    pub fn protected_pattern(&self, branch: &str) -> Option<&str> {
+        self.protected_branches
+            .iter()
+            .flatten()
+            .find(|pattern| utils::glob_match(pattern, branch))
+            .map(String::as_str)
+    }
 
-        fs::write(path, yaml_string).context("Failed to write config file")?;
+    /// The first `keepBranches` pattern matching `branch`, if any
+    pub fn keep_pattern(&self, branch: &str) -> Option<&str> {
+        self.keep_branches
+            .iter()
+            .flatten()
+            .find(|pattern| utils::glob_match(pattern, branch))
+            .map(String::as_str)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_yaml()?).context("Failed to write config file")?;
 
         Ok(())
     }
 
+    /// The file contents `save` would write
+    pub fn to_yaml(&self) -> Result<String> {
+        serde_yaml::to_string(self).context("Failed to serialize config to YAML")
+    }
+
     pub fn load(path: &Path) -> Result<Self> {
         let content = fs::read_to_string(path).context("Failed to read config file")?;
 
-        let config: Self = serde_yaml::from_str(&content).context("Failed to parse YAML config")?;
+        let mut config: Self = parse_yaml(path, &content, CONFIG_KEYS)?;
+        warn_unknown_keys(path, &content, CONFIG_KEYS, config.version);
+
+        if let Some(include) = &config.include {
+            let base_dir = path.parent().unwrap_or(Path::new("."));
+            config.included_hooks = load_included(&resolve_include_path(include, base_dir))?.hooks;
+        }
 
         Ok(config)
     }
 
+    /// Hooks to run: this config's hooks layered over any included ones
+    pub fn effective_hooks(&self) -> Option<Hooks> {
+        match (&self.included_hooks, &self.hooks) {
+            (Some(shared), Some(own)) => Some(shared.merged_with(own)),
+            (shared, own) => own.clone().or_else(|| shared.clone()),
+        }
+    }
+
     pub fn find_config() -> Result<Option<(PathBuf, Self)>> {
-        let mut current_dir = std::env::current_dir()?;
+        let current_dir = std::env::current_dir()?;
+        if let Some(found) = Self::find_config_from(&current_dir)? {
+            return Ok(Some(found));
+        }
+
+        // Worktrees can live outside the project root, but the repository's shared git
+        // directory always sits inside it, so search upward from there as well.
+        match git::get_common_dir(None)? {
+            Some(common_dir) => Self::find_config_from(&common_dir),
+            None => Ok(None),
+        }
+    }
+
+    fn find_config_from(start: &Path) -> Result<Option<(PathBuf, Self)>> {
+        let mut search_path = start.to_path_buf();
 
         loop {
-            let config_path = current_dir.join("git-worktree-config.yaml");
+            let config_path = search_path.join(CONFIG_FILENAME);
             if config_path.exists() {
                 let config = Self::load(&config_path)?;
                 return Ok(Some((config_path, config)));
             }
 
-            if !current_dir.pop() {
+            if !search_path.pop() {
                 break;
             }
         }
@@ -85,7 +437,184 @@ impl GitWorktreeConfig {
     }
 }
 
+fn resolve_include_path(include: &str, base_dir: &Path) -> PathBuf {
+    let expanded = match include.strip_prefix("~/") {
+        Some(rest) => std::env::var_os("HOME").map(|home| PathBuf::from(home).join(rest)),
+        None if include == "~" => std::env::var_os("HOME").map(PathBuf::from),
+        None => None,
+    };
+
+    // Relative paths are resolved against the directory of the including config
+    expanded.unwrap_or_else(|| base_dir.join(include))
+}
+
+fn load_included(path: &Path) -> Result<IncludedConfig> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read included config file: {}", path.display()))?;
+
+    parse_yaml(path, &content, &["hooks"])
+}
+
+/// Top-level keys understood in git-worktree-config.yaml
+const CONFIG_KEYS: &[&str] = &[
+    "version",
+    "repositoryUrl",
+    "mainBranch",
+    "createdAt",
+    "sourceControl",
+    "bitbucketEmail",
+    "hooks",
+    "prLimit",
+    "protectedBranches",
+    "keepBranches",
+    "listIgnoreBranches",
+    "include",
+    "pathStyle",
+    "checkUpdates",
+    "hostAliases",
+    "mainWorktreeName",
+    "confirmations",
+    "remoteName",
+    "defaultTrack",
+    "backgroundHooks",
+    "stashOnSwitch",
+    "submodules",
+    "defaultCommand",
+    "layout",
+    "needsAttention",
+];
+
+/// Parse YAML, turning serde errors into a message that points at the offending line
+fn parse_yaml<T: serde::de::DeserializeOwned>(path: &Path, content: &str, known_keys: &[&str]) -> Result<T> {
+    serde_yaml::from_str(content).map_err(|e| anyhow::anyhow!(describe_yaml_error(path, content, &e, known_keys)))
+}
+
+fn describe_yaml_error(path: &Path, content: &str, error: &serde_yaml::Error, known_keys: &[&str]) -> String {
+    let mut message = format!("Failed to parse {}: {}", path.display(), error);
+
+    if let Some(location) = error.location() {
+        if let Some(line) = content.lines().nth(location.line().saturating_sub(1)) {
+            let gutter = location.line().to_string();
+            message.push_str(&format!(
+                "\n\n {} | {}\n {} | {}^",
+                gutter,
+                line,
+                " ".repeat(gutter.len()),
+                " ".repeat(location.column().saturating_sub(1))
+            ));
+        }
+    }
+
+    // Tabs are never valid indentation in YAML but are easy to miss
+    if let Some(line_number) = content
+        .lines()
+        .position(|line| line.trim_start_matches(' ').starts_with('\t'))
+    {
+        message.push_str(&format!(
+            "\n\nHint: line {} is indented with a tab; YAML only allows spaces for indentation.",
+            line_number + 1
+        ));
+    }
+
+    // Keys are camelCase, so a typo'd key is silently ignored and then reported as missing
+    for key in unknown_top_level_keys(content, known_keys) {
+        message.push_str(&format!("\n\nHint: unknown key '{}'", key));
+        if let Some(suggestion) = closest_key(&key, known_keys) {
+            message.push_str(&format!(", did you mean '{}'?", suggestion));
+        }
+    }
+
+    message
+}
+
+/// Warn about keys that parsed fine but mean nothing to gwt, such as a typo'd optional key that
+/// would otherwise be silently ignored. They stay warnings so that a config written by a newer
+/// gwt still loads. Each file is reported once, however often a command loads it.
+fn warn_unknown_keys(path: &Path, content: &str, known_keys: &[&str], version: Option<u32>) {
+    static WARNED: Mutex<BTreeSet<PathBuf>> = Mutex::new(BTreeSet::new());
+
+    let warnings = unknown_key_warnings(path, content, known_keys, version);
+    let first_time = WARNED
+        .lock()
+        .map_or(true, |mut warned| warned.insert(path.to_path_buf()));
+    if first_time {
+        warnings.iter().for_each(ui::warn);
+    }
+}
+
+fn unknown_key_warnings(path: &Path, content: &str, known_keys: &[&str], version: Option<u32>) -> Vec<String> {
+    let unknown = unknown_top_level_keys(content, known_keys);
+
+    // Keys from a newer format are expected, not typos; one hint beats a warning per key
+    if let Some(version) = version.filter(|version| *version > CONFIG_VERSION && !unknown.is_empty()) {
+        return vec![format!(
+            "{} is config version {}, newer than this gwt understands ({}); ignoring {}. Upgrade gwt to use them.",
+            path.display(),
+            version,
+            CONFIG_VERSION,
+            unknown.join(", ")
+        )];
+    }
+
+    unknown
+        .iter()
+        .map(|key| {
+            let mut warning = format!("Ignoring unknown key '{}' in {}", key, path.display());
+            if let Some(suggestion) = closest_key(key, known_keys) {
+                warning.push_str(&format!(", did you mean '{}'?", suggestion));
+            }
+            warning
+        })
+        .collect()
+}
+
+fn unknown_top_level_keys(content: &str, known_keys: &[&str]) -> Vec<String> {
+    let Ok(serde_yaml::Value::Mapping(mapping)) = serde_yaml::from_str::<serde_yaml::Value>(content) else {
+        return Vec::new();
+    };
+
+    mapping
+        .keys()
+        .filter_map(|key| key.as_str())
+        .filter(|key| !known_keys.contains(key))
+        .map(str::to_string)
+        .collect()
+}
+
+/// The known key closest to `key`, if it is near enough to be a likely typo
+fn closest_key<'a>(key: &str, known_keys: &[&'a str]) -> Option<&'a str> {
+    known_keys
+        .iter()
+        .map(|known| (*known, edit_distance(&key.to_lowercase(), &known.to_lowercase())))
+        .filter(|(_, distance)| *distance <= 3)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(known, _)| known)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
 pub const CONFIG_FILENAME: &str = "git-worktree-config.yaml";
+pub const LOCAL_CONFIG_FILENAME: &str = ".gwt.local.yaml";
+
+/// Maximum number of pull requests fetched from a provider unless overridden
+pub const DEFAULT_PR_LIMIT: u32 = 100;
+
+/// Remote name git uses unless `remoteName` says otherwise
+pub const DEFAULT_REMOTE: &str = "origin";
 
 #[cfg(test)]
 mod tests {
@@ -102,7 +631,7 @@ mod tests {
 
         assert_eq!(config.repository_url, "git@github.com:test/repo.git");
         assert_eq!(config.main_branch, "main");
-        assert_eq!(config.source_control, "github");
+        assert_eq!(config.source_control, Provider::Github);
         assert_eq!(config.bitbucket_email, None);
         assert!(config.hooks.is_some());
 
@@ -121,7 +650,7 @@ mod tests {
 
         assert_eq!(config.repository_url, "https://bitbucket.org/workspace/repo.git");
         assert_eq!(config.main_branch, "main");
-        assert_eq!(config.source_control, "bitbucket-cloud");
+        assert_eq!(config.source_control, Provider::BitbucketCloud);
         assert_eq!(config.bitbucket_email, None);
     }
 
@@ -138,7 +667,7 @@ mod tests {
             "https://bitbucket.company.com/scm/project/repo.git"
         );
         assert_eq!(config.main_branch, "main");
-        assert_eq!(config.source_control, "bitbucket-data-center");
+        assert_eq!(config.source_control, Provider::BitbucketDataCenter);
         assert_eq!(config.bitbucket_email, None);
     }
 
@@ -197,6 +726,260 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_hooks_merged_with_overrides() {
+        let project = Hooks {
+            post_add: Some(vec!["npm install".to_string()]),
+            post_remove: Some(vec!["echo removed".to_string()]),
+            post_switch: None,
+        };
+        let overrides = Hooks {
+            post_add: Some(vec!["make setup".to_string()]),
+            post_remove: None,
+            post_switch: None,
+        };
+
+        let merged = project.merged_with(&overrides);
+        assert_eq!(merged.post_add, Some(vec!["make setup".to_string()]));
+        assert_eq!(merged.post_remove, Some(vec!["echo removed".to_string()]));
+    }
+
+    #[test]
+    fn test_hook_templates() {
+        let post_add = |template| Hooks::template(template).and_then(|hooks| hooks.post_add);
+        assert_eq!(post_add(HookTemplate::Default), Some(vec!["# npm install".to_string()]));
+        assert_eq!(post_add(HookTemplate::Node), Some(vec!["npm install".to_string()]));
+        assert_eq!(post_add(HookTemplate::Rust), Some(vec!["cargo fetch".to_string()]));
+        assert!(Hooks::template(HookTemplate::None).is_none());
+    }
+
+    #[test]
+    fn test_protected_pattern() {
+        let mut config = GitWorktreeConfig::new(
+            "git@github.com:test/repo.git".to_string(),
+            "main".to_string(),
+            Provider::Github,
+        );
+        assert_eq!(config.protected_pattern("production"), None);
+
+        config.protected_branches = Some(vec!["production".to_string(), "release/*".to_string()]);
+        assert_eq!(config.protected_pattern("production"), Some("production"));
+        assert_eq!(config.protected_pattern("release/2.0"), Some("release/*"));
+        assert_eq!(config.protected_pattern("feature/release"), None);
+    }
+
+    #[test]
+    fn test_keep_pattern() {
+        let config: GitWorktreeConfig = serde_yaml::from_str(
+            "repositoryUrl: git@github.com:test/repo.git\nmainBranch: main\ncreatedAt: 2024-01-01T00:00:00Z\nsourceControl: github\nkeepBranches:\n  - integration\n  - \"env/*\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.keep_pattern("integration"), Some("integration"));
+        assert_eq!(config.keep_pattern("env/staging"), Some("env/*"));
+        assert_eq!(config.keep_pattern("feature/env"), None);
+        assert_eq!(config.keep_pattern("integration-old"), None);
+    }
+
+    #[test]
+    fn test_confirmations_prompts_for() {
+        assert!(!Confirmations::None.prompts_for(true));
+        assert!(Confirmations::DestructiveOnly.prompts_for(true));
+        assert!(!Confirmations::DestructiveOnly.prompts_for(false));
+        assert!(Confirmations::Always.prompts_for(false));
+
+        let config: GitWorktreeConfig = serde_yaml::from_str(
+            "repositoryUrl: git@github.com:test/repo.git\nmainBranch: main\ncreatedAt: 2024-01-01T00:00:00Z\nsourceControl: github\nconfirmations: destructive-only\n",
+        )
+        .unwrap();
+        assert_eq!(config.confirmations, Some(Confirmations::DestructiveOnly));
+    }
+
+    #[test]
+    fn test_resolved_repository_url_uses_host_aliases() {
+        let mut config = GitWorktreeConfig::new(
+            "git@github-work:test/repo.git".to_string(),
+            "main".to_string(),
+            Provider::Github,
+        );
+        assert_eq!(config.resolved_repository_url(), "git@github-work:test/repo.git");
+
+        config.host_aliases = Some(BTreeMap::from([("github-work".to_string(), "github.com".to_string())]));
+        assert_eq!(config.resolved_repository_url(), "git@github.com:test/repo.git");
+        // git keeps using the alias
+        assert_eq!(config.repository_url, "git@github-work:test/repo.git");
+    }
+
+    #[test]
+    fn test_config_include_merges_shared_hooks() {
+        let temp_dir = tempdir().unwrap();
+        let shared_dir = temp_dir.path().join("shared");
+        fs::create_dir_all(&shared_dir).unwrap();
+        fs::write(
+            shared_dir.join("hooks.yaml"),
+            "hooks:\n  postAdd:\n    - npm install\n  postRemove:\n    - echo shared\n",
+        )
+        .unwrap();
+
+        let mut config = GitWorktreeConfig::new(
+            "git@github.com:test/repo.git".to_string(),
+            "main".to_string(),
+            Provider::Github,
+        );
+        config.include = Some("shared/hooks.yaml".to_string());
+        config.hooks = Some(Hooks {
+            post_add: None,
+            post_remove: Some(vec!["echo local".to_string()]),
+            post_switch: None,
+        });
+        let config_path = temp_dir.path().join(CONFIG_FILENAME);
+        config.save(&config_path).unwrap();
+
+        let loaded = GitWorktreeConfig::load(&config_path).unwrap();
+        let hooks = loaded.effective_hooks().unwrap();
+        assert_eq!(hooks.post_add, Some(vec!["npm install".to_string()]));
+        assert_eq!(hooks.post_remove, Some(vec!["echo local".to_string()]));
+
+        // Saving must not inline the included hooks
+        loaded.save(&config_path).unwrap();
+        assert!(!fs::read_to_string(&config_path).unwrap().contains("npm install"));
+
+        // A missing include is reported with its resolved path
+        fs::remove_file(shared_dir.join("hooks.yaml")).unwrap();
+        let err = GitWorktreeConfig::load(&config_path).unwrap_err();
+        assert!(format!("{:#}", err).contains("shared/hooks.yaml"));
+    }
+
+    #[test]
+    fn test_provider_round_trips_by_name() {
+        for provider in [
+            Provider::Github,
+            Provider::BitbucketCloud,
+            Provider::BitbucketDataCenter,
+        ] {
+            assert_eq!(provider.to_string().parse::<Provider>(), Ok(provider));
+        }
+        assert_eq!(Provider::BitbucketDataCenter.to_string(), "bitbucket-data-center");
+        assert!("gitlab"
+            .parse::<Provider>()
+            .unwrap_err()
+            .contains("unknown provider 'gitlab'"));
+
+        let config = GitWorktreeConfig::new("repo".to_string(), "main".to_string(), Provider::BitbucketCloud);
+        assert!(config.to_yaml().unwrap().contains("sourceControl: bitbucket-cloud"));
+    }
+
+    #[test]
+    fn test_unknown_source_control_is_an_error() {
+        let temp_dir = tempdir().unwrap();
+        let config_path = temp_dir.path().join(CONFIG_FILENAME);
+        fs::write(
+            &config_path,
+            "repositoryUrl: git@github.com:test/repo.git\nmainBranch: main\ncreatedAt: 2024-01-01T00:00:00Z\nsourceControl: githib\n",
+        )
+        .unwrap();
+
+        let message = GitWorktreeConfig::load(&config_path).unwrap_err().to_string();
+        assert!(message.contains("unknown provider 'githib'"), "{}", message);
+    }
+
+    #[test]
+    fn test_yaml_error_points_at_line() {
+        let temp_dir = tempdir().unwrap();
+        let config_path = temp_dir.path().join(CONFIG_FILENAME);
+        fs::write(
+            &config_path,
+            "repositoryUrl: git@github.com:test/repo.git\nmainBranch: [main]\nsourceControl: github\n",
+        )
+        .unwrap();
+
+        let message = GitWorktreeConfig::load(&config_path).unwrap_err().to_string();
+        assert!(message.contains("line 2 column 13"), "{}", message);
+        assert!(
+            message.contains(" 2 | mainBranch: [main]\n   |             ^"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn test_yaml_error_hints_at_tabs_and_typos() {
+        let temp_dir = tempdir().unwrap();
+        let config_path = temp_dir.path().join(CONFIG_FILENAME);
+
+        fs::write(
+            &config_path,
+            "repositoryUrl: git@github.com:test/repo.git\nmainBranch: main\nhooks:\n\tpostAdd: []\n",
+        )
+        .unwrap();
+        let message = GitWorktreeConfig::load(&config_path).unwrap_err().to_string();
+        assert!(message.contains("line 4 is indented with a tab"), "{}", message);
+
+        fs::write(
+            &config_path,
+            "repositoryUrl: git@github.com:test/repo.git\nmainBranh: main\ncreatedAt: 2024-01-01T00:00:00Z\nsourceControl: github\n",
+        )
+        .unwrap();
+        let message = GitWorktreeConfig::load(&config_path).unwrap_err().to_string();
+        assert!(message.contains("missing field `mainBranch`"), "{}", message);
+        assert!(
+            message.contains("unknown key 'mainBranh', did you mean 'mainBranch'?"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn test_unknown_keys_in_a_valid_config_are_reported() {
+        let temp_dir = tempdir().unwrap();
+        let config_path = temp_dir.path().join(CONFIG_FILENAME);
+        let content = "repositoryUrl: git@github.com:test/repo.git\nmainBranch: main\ncreatedAt: 2024-01-01T00:00:00Z\nsourceControl: github\nprLimt: 50\nfutureOption: true\n";
+        fs::write(&config_path, content).unwrap();
+
+        let config = GitWorktreeConfig::load(&config_path).unwrap();
+        assert_eq!(config.pr_limit, None);
+
+        let warnings = unknown_key_warnings(&config_path, content, CONFIG_KEYS, Some(CONFIG_VERSION));
+        assert_eq!(warnings.len(), 2, "{:?}", warnings);
+        assert!(
+            warnings[0].starts_with("Ignoring unknown key 'prLimt' in ")
+                && warnings[0].ends_with(", did you mean 'prLimit'?"),
+            "{}",
+            warnings[0]
+        );
+        assert!(!warnings[1].contains("did you mean"), "{}", warnings[1]);
+
+        // A newer config's keys aren't typos: one upgrade hint instead
+        let warnings = unknown_key_warnings(&config_path, content, CONFIG_KEYS, Some(CONFIG_VERSION + 1));
+        assert_eq!(warnings.len(), 1, "{:?}", warnings);
+        assert!(warnings[0].contains("ignoring prLimt, futureOption"), "{}", warnings[0]);
+    }
+
+    #[test]
+    fn test_local_config_found_within_worktree_only() {
+        let temp_dir = tempdir().unwrap();
+        let worktree = temp_dir.path().join("feature");
+        let nested = worktree.join("src/deep");
+        fs::create_dir_all(&nested).unwrap();
+        fs::create_dir_all(worktree.join(".git")).unwrap();
+
+        // Nothing to find yet
+        assert!(LocalConfig::find(&nested).unwrap().is_none());
+
+        fs::write(
+            worktree.join(LOCAL_CONFIG_FILENAME),
+            "hooks:\n  postAdd:\n    - make setup\n",
+        )
+        .unwrap();
+
+        let local = LocalConfig::find(&nested).unwrap().unwrap();
+        assert_eq!(local.hooks.unwrap().post_add, Some(vec!["make setup".to_string()]));
+
+        // A file above the worktree root must not be picked up
+        fs::write(temp_dir.path().join(LOCAL_CONFIG_FILENAME), "hooks: {}\n").unwrap();
+        fs::remove_file(worktree.join(LOCAL_CONFIG_FILENAME)).unwrap();
+        assert!(LocalConfig::find(&nested).unwrap().is_none());
+    }
+
     #[test]
     fn test_config_not_found() {
         let temp_dir = tempdir().unwrap();
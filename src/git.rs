@@ -1,8 +1,11 @@
 use anyhow::{bail, Context, Result};
-use colored::Colorize;
+use std::collections::HashSet;
+use std::io::{IsTerminal, Read, Write};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
+use crate::ui;
+
 /// Execute a git command with real-time output streaming
 pub fn execute_streaming(args: &[&str], cwd: Option<&Path>) -> Result<()> {
     let mut cmd = Command::new("git");
@@ -40,10 +43,181 @@ pub fn execute_capture(args: &[&str], cwd: Option<&Path>) -> Result<String> {
     Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
 }
 
-/// Clone a repository with streaming output
-pub fn clone(repo_url: &str, target_dir: &str) -> Result<()> {
-    println!("{}", format!("Cloning {}...", repo_url).cyan());
-    execute_streaming(&["clone", repo_url, target_dir], None)
+/// Run a git command that is allowed to fail, returning its trimmed stdout, exit code and
+/// trimmed stderr. The code is -1 when git was killed by a signal.
+pub fn execute_capture_with_status(args: &[&str], cwd: Option<&Path>) -> Result<(String, i32, String)> {
+    let mut cmd = Command::new("git");
+    cmd.args(args);
+
+    if let Some(dir) = cwd {
+        cmd.current_dir(dir);
+    }
+
+    let output = cmd.output().context("Failed to execute git command")?;
+    Ok((
+        String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        output.status.code().unwrap_or(-1),
+        String::from_utf8_lossy(&output.stderr).trim().to_string(),
+    ))
+}
+
+/// Clone a repository with streaming output, naming its remote `remote`
+pub fn clone(repo_url: &str, target_dir: &str, remote: &str) -> Result<()> {
+    ui::step(format!("Cloning {}...", repo_url));
+    clone_with_retries(&["clone", "--origin", remote, repo_url, target_dir], target_dir)
+}
+
+/// Mirror-clone a repository into the bare directory `target_dir`
+pub fn clone_mirror(repo_url: &str, target_dir: &str, remote: &str) -> Result<()> {
+    ui::step(format!("Cloning mirror of {}...", repo_url));
+    clone_with_retries(
+        &["clone", "--mirror", "--origin", remote, repo_url, target_dir],
+        target_dir,
+    )
+}
+
+/// Attempts at a clone that keeps failing on network errors
+const CLONE_ATTEMPTS: u32 = 3;
+
+/// Run a `git clone`, retrying with backoff when it fails on what looks like a network hiccup.
+/// `target_dir` must not exist beforehand: whatever a failed attempt leaves there is removed.
+fn clone_with_retries(args: &[&str], target_dir: &str) -> Result<()> {
+    let mut args = args.to_vec();
+    // git only reports progress to a terminal, and its stderr is now a pipe
+    if std::io::stderr().is_terminal() {
+        args.insert(1, "--progress");
+    }
+
+    let mut attempt = 1;
+    loop {
+        let stderr = match execute_streaming_with_stderr(&args)? {
+            None => return Ok(()),
+            Some(stderr) => stderr,
+        };
+        let reason = failure_reason(&stderr);
+
+        if !is_transient_clone_failure(&stderr) {
+            bail!("Clone failed: {}", reason);
+        }
+        if attempt == CLONE_ATTEMPTS {
+            bail!("Clone failed after {} attempts: {}", CLONE_ATTEMPTS, reason);
+        }
+
+        // git normally cleans up after itself, but not when it is interrupted halfway
+        if Path::new(target_dir).exists() {
+            std::fs::remove_dir_all(target_dir)
+                .with_context(|| format!("Clone failed: {}; could not clean up {}", reason, target_dir))?;
+        }
+
+        let delay = std::time::Duration::from_secs(2u64.pow(attempt));
+        attempt += 1;
+        // git's own message is already on screen
+        ui::warn(format!(
+            "Network error, retrying clone (attempt {}/{}) in {}s...",
+            attempt,
+            CLONE_ATTEMPTS,
+            delay.as_secs()
+        ));
+        std::thread::sleep(delay);
+    }
+}
+
+/// Run git with its output shown live, returning what it wrote to stderr when it fails
+fn execute_streaming_with_stderr(args: &[&str]) -> Result<Option<String>> {
+    let mut child = Command::new("git")
+        .args(args)
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to execute git command")?;
+
+    // Pass stderr through as it arrives, progress lines included, while keeping a copy
+    let mut captured = Vec::new();
+    if let Some(mut pipe) = child.stderr.take() {
+        let mut buffer = [0; 4096];
+        let mut terminal = std::io::stderr();
+        loop {
+            let read = pipe.read(&mut buffer).context("Failed to read git output")?;
+            if read == 0 {
+                break;
+            }
+            let _ = terminal.write_all(&buffer[..read]);
+            captured.extend_from_slice(&buffer[..read]);
+        }
+    }
+
+    let status = child.wait().context("Failed to execute git command")?;
+    Ok((!status.success()).then(|| String::from_utf8_lossy(&captured).into_owned()))
+}
+
+/// The line of git's stderr that says what went wrong
+fn failure_reason(stderr: &str) -> String {
+    let lines: Vec<&str> = stderr.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+    lines
+        .iter()
+        .rev()
+        .find(|line| line.starts_with("fatal:"))
+        .or(lines.last())
+        .map_or_else(
+            || "git exited without saying why".to_string(),
+            |line| line.trim_start_matches("fatal:").trim().to_string(),
+        )
+}
+
+/// Whether a failed clone is worth retrying: network trouble, as opposed to a wrong URL,
+/// a missing repository or refused credentials, which fail the same way every time
+fn is_transient_clone_failure(stderr: &str) -> bool {
+    const FATAL: &[&str] = &[
+        "not found",
+        "does not exist",
+        "does not appear to be a git repository",
+        "authentication failed",
+        "permission denied",
+        "could not read username",
+        "could not read password",
+        "host key verification failed",
+        "already exists and is not an empty directory",
+        "the requested url returned error: 401",
+        "the requested url returned error: 403",
+        "the requested url returned error: 404",
+    ];
+    const TRANSIENT: &[&str] = &[
+        "could not resolve host",
+        "temporary failure in name resolution",
+        "timed out",
+        "connection reset",
+        "connection refused",
+        "network is unreachable",
+        "failed to connect",
+        "early eof",
+        "the remote end hung up unexpectedly",
+        "rpc failed",
+        "unexpected disconnect",
+        "gnutls",
+        "ssl_read",
+        "the requested url returned error: 5",
+    ];
+
+    let stderr = stderr.to_lowercase();
+    !FATAL.iter().any(|marker| stderr.contains(marker)) && TRANSIENT.iter().any(|marker| stderr.contains(marker))
+}
+
+/// Ask the remote at `url` for its HEAD without cloning, returning git's stderr on failure.
+/// Credential prompts are disabled, so a missing login fails with "terminal prompts disabled"
+/// instead of asking; the clone that follows can still ask.
+pub fn ls_remote(url: &str) -> std::result::Result<(), String> {
+    let output = Command::new("git")
+        .args(["ls-remote", url, "HEAD"])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Failed to execute git command: {}", e))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
 }
 
 /// Get the default branch name of a repository
@@ -85,17 +259,106 @@ pub fn delete_branch(git_dir: &Path, branch_name: &str) -> Result<()> {
     execute_streaming(&["branch", "-D", branch_name], Some(git_dir))
 }
 
-/// Check if a branch exists
-pub fn branch_exists(git_dir: &Path, branch_name: &str) -> Result<(bool, bool)> {
-    let local = execute_capture(&["branch", "--list", branch_name], Some(git_dir)).unwrap_or_default();
+/// Remote-tracking ref for `branch` on `remote`, e.g. `upstream/main`
+pub fn remote_branch(remote: &str, branch: &str) -> String {
+    format!("{}/{}", remote, branch)
+}
+
+/// The branch `remote`'s HEAD points at, e.g. `main`, if git knows it (set by clone or
+/// `git remote set-head`)
+pub fn remote_default_branch(git_dir: &Path, remote: &str) -> Option<String> {
+    let head = execute_capture(
+        &["symbolic-ref", "--short", &format!("refs/remotes/{}/HEAD", remote)],
+        Some(git_dir),
+    )
+    .ok()?;
+    head.strip_prefix(&format!("{}/", remote)).map(str::to_string)
+}
 
-    let remote = execute_capture(
-        &["branch", "-r", "--list", &format!("origin/{}", branch_name)],
+/// Whether `git branch -d` would accept deleting `branch`: it is merged into its upstream,
+/// or into HEAD of `git_dir` when it has none
+pub fn is_branch_merged(git_dir: &Path, branch: &str) -> bool {
+    let target = execute_capture(
+        &["rev-parse", "--abbrev-ref", &format!("{}@{{upstream}}", branch)],
         Some(git_dir),
     )
-    .unwrap_or_default();
+    .unwrap_or_else(|_| "HEAD".to_string());
+    execute_capture(&["merge-base", "--is-ancestor", branch, &target], Some(git_dir)).is_ok()
+}
+
+/// Whether every commit of `branch` is already in `target`
+pub fn is_merged_into(git_dir: &Path, branch: &str, target: &str) -> bool {
+    execute_capture(&["merge-base", "--is-ancestor", branch, target], Some(git_dir)).is_ok()
+}
 
-    Ok((!local.is_empty(), !remote.is_empty()))
+/// Whether `branch` ever had commits of its own, as opposed to still pointing where it was
+/// created: it tracks a remote branch other than `target`, or its reflog records more than its
+/// creation. Without a reflog (bare repositories keep none by default) it counts as fresh.
+pub fn has_own_history(git_dir: &Path, branch: &str, target: &str) -> bool {
+    if upstream_of(git_dir, branch).is_some_and(|upstream| upstream != target) {
+        return true;
+    }
+
+    let full_ref = format!("refs/heads/{}", branch);
+    execute_capture(&["reflog", "show", "--format=%H", &full_ref], Some(git_dir))
+        .is_ok_and(|entries| entries.lines().count() > 1)
+}
+
+/// Check if a branch exists locally and on `remote`
+pub fn branch_exists(git_dir: &Path, remote: &str, branch_name: &str) -> Result<(bool, bool)> {
+    let local = ref_exists(git_dir, &format!("refs/heads/{}", branch_name))?;
+    let remote = ref_exists(git_dir, &format!("refs/remotes/{}", remote_branch(remote, branch_name)))?;
+    Ok((local, remote))
+}
+
+/// Whether the exact ref `full_ref` exists. Unlike `git branch --list`, names are never
+/// treated as patterns, and a broken repository is an error rather than "no such branch".
+fn ref_exists(git_dir: &Path, full_ref: &str) -> Result<bool> {
+    // show-ref exits with 1 when the ref is missing, and with something else when it can't tell
+    match execute_capture_with_status(&["show-ref", "--verify", "--quiet", full_ref], Some(git_dir))? {
+        (_, 0, _) => Ok(true),
+        (_, 1, _) => Ok(false),
+        (_, code, stderr) => bail!("Could not look up {} (git exited with {}): {}", full_ref, code, stderr),
+    }
+}
+
+/// Full names (`refs/heads/...`) of the local branches that exist
+pub fn local_branch_refs(git_dir: &Path) -> Result<HashSet<String>> {
+    let output = execute_capture(&["for-each-ref", "--format=%(refname)", "refs/heads"], Some(git_dir))?;
+    Ok(output.lines().map(str::to_string).collect())
+}
+
+/// Whether a remote-tracking branch such as `upstream/feature` exists locally
+pub fn remote_ref_exists(git_dir: &Path, remote_ref: &str) -> Result<bool> {
+    ref_exists(git_dir, &format!("refs/remotes/{}", remote_ref))
+}
+
+/// Whether `rev` names a commit that exists in the repository at `git_dir`
+pub fn commit_exists(git_dir: &Path, rev: &str) -> bool {
+    execute_capture(&["cat-file", "-e", &format!("{}^{{commit}}", rev)], Some(git_dir)).is_ok()
+}
+
+/// List the branches on `remote`, without the `<remote>/` prefix
+pub fn list_remote_branches(git_dir: &Path, remote: &str) -> Result<Vec<String>> {
+    let prefix = format!("{}/", remote);
+    let output = execute_capture(
+        &[
+            "branch",
+            "-r",
+            "--list",
+            &format!("{}*", prefix),
+            "--format=%(refname:short)",
+        ],
+        Some(git_dir),
+    )?;
+
+    Ok(output
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix(&prefix))
+        // <remote>/HEAD is a symbolic ref, not a branch
+        .filter(|branch| !branch.is_empty() && *branch != "HEAD")
+        .map(str::to_string)
+        .collect())
 }
 
 /// Get the current git root directory
@@ -106,12 +369,175 @@ pub fn get_git_root() -> Result<Option<PathBuf>> {
     }
 }
 
+/// Whether `dir` is a bare repository (which has no `.git` entry of its own)
+pub fn is_bare_repository(dir: &Path) -> bool {
+    execute_capture(&["rev-parse", "--is-bare-repository"], Some(dir))
+        .map(|output| output == "true")
+        .unwrap_or(false)
+}
+
+/// Get the git directory shared by all worktrees of the repository containing `dir`
+pub fn get_common_dir(dir: Option<&Path>) -> Result<Option<PathBuf>> {
+    match execute_capture(&["rev-parse", "--path-format=absolute", "--git-common-dir"], dir) {
+        Ok(path) => Ok(Some(PathBuf::from(path))),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Author and date of the commit checked out in a worktree
+#[derive(Debug, Clone)]
+pub struct LastCommit {
+    pub author: String,
+    /// ISO 8601 author date
+    pub date: String,
+}
+
+/// The commit HEAD points to in `dir`, or `None` when there is none (e.g. an orphaned branch)
+pub fn last_commit(dir: &Path) -> Option<LastCommit> {
+    let output = execute_capture(&["log", "-1", "--format=%an%x00%aI", "HEAD"], Some(dir)).ok()?;
+    let (author, date) = output.split_once('\0')?;
+    Some(LastCommit {
+        author: author.to_string(),
+        date: date.to_string(),
+    })
+}
+
+/// Whether `worktree` is on a branch with no commits yet, such as a fresh `gwt add --orphan` branch.
+/// Its ref doesn't exist until the first commit, as with a branch deleted out from under a
+/// worktree; but that one leaves its files in the index, while a new branch starts out empty.
+pub fn is_unborn_branch(worktree: &Worktree) -> bool {
+    let no_head = !worktree.head.is_empty() && worktree.head.chars().all(|c| c == '0');
+    !worktree.bare
+        && worktree.branch.is_some()
+        && no_head
+        && execute_capture(&["ls-files"], Some(&worktree.path)).is_ok_and(|files| files.is_empty())
+}
+
+/// Whether the worktree at `dir` has modified, staged or untracked (non-ignored) files
+pub fn has_uncommitted_changes(dir: &Path) -> Result<bool> {
+    Ok(!execute_capture(&["status", "--porcelain"], Some(dir))?.is_empty())
+}
+
+/// Stash all changes in the worktree at `dir`, untracked files included, under `message`
+pub fn stash_push(dir: &Path, message: &str) -> Result<()> {
+    execute_capture(
+        &["stash", "push", "--include-untracked", "--message", message],
+        Some(dir),
+    )?;
+    Ok(())
+}
+
+/// Newest stash entry (`stash@{n}`) saved with `message`. Stashes are shared by all worktrees.
+pub fn find_stash(dir: &Path, message: &str) -> Result<Option<String>> {
+    let output = execute_capture(&["stash", "list", "--format=%gd%x09%gs"], Some(dir))?;
+
+    // Subjects read "On <branch>: <message>", or "WIP on ..." without a message
+    let suffix = format!(": {}", message);
+    Ok(output.lines().find_map(|line| {
+        let (stash_ref, subject) = line.split_once('\t')?;
+        subject.ends_with(&suffix).then(|| stash_ref.to_string())
+    }))
+}
+
+/// Apply and drop `stash_ref` in the worktree at `dir`; on conflicts git keeps the stash
+pub fn stash_pop(dir: &Path, stash_ref: &str) -> Result<()> {
+    execute_capture(&["stash", "pop", stash_ref], Some(dir))?;
+    Ok(())
+}
+
+/// Check out the submodules of the worktree at `dir`, recursively, streaming git's output
+pub fn update_submodules(dir: &Path) -> Result<()> {
+    execute_streaming(&["submodule", "update", "--init", "--recursive"], Some(dir))
+}
+
+/// Untracked, non-ignored entries in the worktree at `dir`; an untracked directory counts once
+pub fn count_untracked_files(dir: &Path) -> Result<u32> {
+    let output = execute_capture(
+        &[
+            "ls-files",
+            "--others",
+            "--exclude-standard",
+            "--directory",
+            "--no-empty-directory",
+        ],
+        Some(dir),
+    )?;
+    Ok(output.lines().count() as u32)
+}
+
+/// The git directory private to the worktree at `dir`, e.g. `<repo>/.git/worktrees/<name>`
+pub fn worktree_git_dir(dir: &Path) -> Option<PathBuf> {
+    execute_capture(&["rev-parse", "--absolute-git-dir"], Some(dir))
+        .ok()
+        .map(PathBuf::from)
+}
+
+/// Count commits on HEAD in `dir` that are not reachable from `rev`.
+/// Returns `None` when `rev` does not resolve (e.g. the remote branch was never fetched).
+pub fn count_commits_since(rev: &str, dir: &Path) -> Result<Option<u32>> {
+    let commit = format!("{}^{{commit}}", rev);
+    if execute_capture(&["rev-parse", "--verify", "--quiet", &commit], Some(dir)).is_err() {
+        return Ok(None);
+    }
+
+    let count = execute_capture(&["rev-list", "--count", &format!("{}..HEAD", rev)], Some(dir))?;
+    Ok(Some(count.parse()?))
+}
+
+/// The upstream of a local branch (e.g. `origin/feature`), if it tracks one
+pub fn upstream_of(dir: &Path, branch: &str) -> Option<String> {
+    let upstream = format!("{}@{{upstream}}", branch);
+    execute_capture(
+        &["rev-parse", "--abbrev-ref", "--symbolic-full-name", &upstream],
+        Some(dir),
+    )
+    .ok()
+}
+
+/// Count commits on HEAD in `dir` that no remote-tracking branch contains, i.e. work that
+/// exists only locally (whether the branch has no upstream or is ahead of it)
+pub fn count_unpushed_commits(dir: &Path) -> Result<u32> {
+    let count = execute_capture(&["rev-list", "--count", "HEAD", "--not", "--remotes"], Some(dir))?;
+    Ok(count.parse()?)
+}
+
+/// Find a worktree by branch name, falling back to its directory name
+pub fn find_worktree<'a>(worktrees: &'a [Worktree], name: &str) -> Option<&'a Worktree> {
+    find_branch_worktree(worktrees, name).or_else(|| {
+        worktrees
+            .iter()
+            .find(|wt| wt.path.file_name().and_then(|n| n.to_str()) == Some(name))
+    })
+}
+
+/// The worktree that has `branch` checked out, if any
+pub fn find_branch_worktree<'a>(worktrees: &'a [Worktree], branch: &str) -> Option<&'a Worktree> {
+    worktrees.iter().find(|wt| {
+        wt.branch
+            .as_deref()
+            .map(|b| b.strip_prefix("refs/heads/").unwrap_or(b) == branch)
+            .unwrap_or(false)
+    })
+}
+
+/// Find the worktree whose directory contains `path`, preferring the most deeply nested one
+pub fn find_worktree_containing<'a>(worktrees: &'a [Worktree], path: &Path) -> Option<&'a Worktree> {
+    // git reports canonical paths, but compare canonical forms in case the list came from elsewhere
+    let canonical = |path: &Path| std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    worktrees
+        .iter()
+        .filter(|wt| !wt.bare && path.starts_with(canonical(&wt.path)))
+        .max_by_key(|wt| wt.path.components().count())
+}
+
 #[derive(Debug, Clone)]
 pub struct Worktree {
     pub path: PathBuf,
     pub head: String,
     pub branch: Option<String>,
     pub bare: bool,
+    /// Set by `git worktree lock`: the reason given, or empty without one
+    pub locked: Option<String>,
 }
 
 fn parse_worktree_list(output: &str) -> Result<Vec<Worktree>> {
@@ -124,16 +550,19 @@ fn parse_worktree_list(output: &str) -> Result<Vec<Worktree>> {
         head: Option<String>,
         branch: Option<String>,
         bare: bool,
+        locked: Option<String>,
     }
 
     impl PartialWorktree {
         fn into_worktree(self) -> Option<Worktree> {
+            // Bare entries have no HEAD line
             match (self.path, self.head) {
-                (Some(path), Some(head)) => Some(Worktree {
+                (Some(path), head) if head.is_some() || self.bare => Some(Worktree {
                     path,
-                    head,
+                    head: head.unwrap_or_default(),
                     branch: self.branch,
                     bare: self.bare,
+                    locked: self.locked,
                 }),
                 _ => None,
             }
@@ -168,6 +597,11 @@ fn parse_worktree_list(output: &str) -> Result<Vec<Worktree>> {
                     wt.bare = true;
                 }
             }
+            WorktreeLine::Locked(reason) => {
+                if let Some(ref mut wt) = current_worktree {
+                    wt.locked = Some(reason);
+                }
+            }
             WorktreeLine::Other => {}
         }
     }
@@ -187,6 +621,7 @@ enum WorktreeLine {
     Head(String),
     Branch(String),
     Bare,
+    Locked(String),
     Other,
 }
 
@@ -199,7 +634,115 @@ fn parse_worktree_line(line: &str) -> WorktreeLine {
         WorktreeLine::Branch(branch.to_string())
     } else if line == "bare" {
         WorktreeLine::Bare
+    } else if line == "locked" {
+        WorktreeLine::Locked(String::new())
+    } else if let Some(reason) = line.strip_prefix("locked ") {
+        WorktreeLine::Locked(reason.to_string())
     } else {
         WorktreeLine::Other
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_branch_exists_matches_names_exactly() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path();
+        let git = |args: &[&str]| {
+            let (_, code, stderr) = execute_capture_with_status(args, Some(repo)).unwrap();
+            assert_eq!(code, 0, "git {:?} failed: {}", args, stderr);
+        };
+        git(&["init", "-q", "-b", "main"]);
+        git(&[
+            "-c",
+            "user.name=Test",
+            "-c",
+            "user.email=test@example.com",
+            "commit",
+            "-q",
+            "--allow-empty",
+            "-m",
+            "initial",
+        ]);
+        git(&["branch", "feature/login"]);
+        git(&["update-ref", "refs/remotes/origin/feature/login", "HEAD"]);
+
+        assert_eq!(branch_exists(repo, "origin", "feature/login").unwrap(), (true, true));
+        assert_eq!(branch_exists(repo, "origin", "main").unwrap(), (true, false));
+        // `git branch --list` would have taken these as patterns and matched feature/login
+        assert_eq!(branch_exists(repo, "origin", "feature/*").unwrap(), (false, false));
+        assert_eq!(branch_exists(repo, "origin", "feature").unwrap(), (false, false));
+
+        let (stdout, code, _) =
+            execute_capture_with_status(&["rev-parse", "--verify", "--quiet", "nope"], Some(repo)).unwrap();
+        assert_eq!((stdout.as_str(), code), ("", 1));
+    }
+
+    #[test]
+    fn test_has_own_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path();
+        let git = |args: &[&str]| {
+            let mut full = vec!["-c", "user.name=Test", "-c", "user.email=test@example.com"];
+            full.extend_from_slice(args);
+            let (_, code, stderr) = execute_capture_with_status(&full, Some(repo)).unwrap();
+            assert_eq!(code, 0, "git {:?} failed: {}", args, stderr);
+        };
+        git(&["init", "-q", "-b", "main"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "initial"]);
+        git(&["branch", "fresh"]);
+        git(&["branch", "worked"]);
+        git(&["checkout", "-q", "worked"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "work"]);
+        git(&["checkout", "-q", "main"]);
+        git(&["merge", "-q", "--ff-only", "worked"]);
+
+        // Both are merged into main, but only one was ever worked on
+        assert!(is_merged_into(repo, "fresh", "main"));
+        assert!(is_merged_into(repo, "worked", "main"));
+        assert!(!has_own_history(repo, "fresh", "main"));
+        assert!(has_own_history(repo, "worked", "main"));
+    }
+
+    #[test]
+    fn test_is_transient_clone_failure() {
+        assert!(is_transient_clone_failure(
+            "Cloning into 'repo'...\nfatal: unable to access 'https://github.com/org/repo.git/': Could not resolve host: github.com"
+        ));
+        assert!(is_transient_clone_failure(
+            "error: RPC failed; curl 56 GnuTLS recv error (-54): Error in the pull function.\nfatal: early EOF"
+        ));
+        assert!(is_transient_clone_failure(
+            "ssh: connect to host github.com port 22: Connection timed out\nfatal: Could not read from remote repository."
+        ));
+        assert!(is_transient_clone_failure(
+            "fatal: unable to access 'https://git.acme.com/scm/p/r.git/': The requested URL returned error: 503"
+        ));
+
+        assert!(!is_transient_clone_failure(
+            "remote: Repository not found.\nfatal: repository 'https://github.com/org/nope.git/' not found"
+        ));
+        assert!(!is_transient_clone_failure(
+            "fatal: Authentication failed for 'https://bitbucket.org/ws/repo.git/'"
+        ));
+        assert!(!is_transient_clone_failure(
+            "git@github.com: Permission denied (publickey).\nfatal: Could not read from remote repository."
+        ));
+        assert!(!is_transient_clone_failure(
+            "fatal: repository '/tmp/missing' does not exist"
+        ));
+    }
+
+    #[test]
+    fn test_failure_reason() {
+        assert_eq!(
+            failure_reason("Cloning into 'repo'...\nfatal: repository 'x' not found\n"),
+            "repository 'x' not found"
+        );
+        assert_eq!(failure_reason("error: something odd\n"), "error: something odd");
+        assert_eq!(failure_reason(""), "git exited without saying why");
+    }
+}
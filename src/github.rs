@@ -1,4 +1,5 @@
 use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -8,13 +9,36 @@ pub struct PullRequest {
     pub state: String,
     pub html_url: String,
     pub draft: bool,
+    /// e.g. `APPROVED` or `CHANGES_REQUESTED`; only fetched per branch
+    pub review_decision: Option<String>,
+    /// Checks rolled up to `SUCCESS`, `FAILURE` or `PENDING`; only fetched per branch
+    pub ci_status: Option<String>,
+    /// `MERGEABLE`, `CONFLICTING` or `UNKNOWN` (while GitHub computes it); only fetched per branch
+    pub mergeable: Option<String>,
+    /// Login of whoever opened it
+    pub author: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
-pub struct GitHubClient;
+use crate::config::DEFAULT_PR_LIMIT;
+use crate::rate_limit::RateLimited;
+use crate::utils;
+
+pub struct GitHubClient {
+    pr_limit: u32,
+}
 
 impl GitHubClient {
     pub fn new() -> Self {
-        Self
+        Self {
+            pr_limit: DEFAULT_PR_LIMIT,
+        }
+    }
+
+    /// Cap the number of pull requests fetched when listing all open PRs
+    pub fn with_pr_limit(mut self, pr_limit: u32) -> Self {
+        self.pr_limit = pr_limit;
+        self
     }
 
     fn get_gh_token() -> Option<String> {
@@ -38,41 +62,70 @@ impl GitHubClient {
         Self::get_gh_token().is_some()
     }
 
-    pub fn get_pull_requests(&self, owner: &str, repo: &str, branch: &str) -> Result<Vec<PullRequest>> {
-        // Use gh CLI instead of HTTP API
-        let output = std::process::Command::new("gh")
-            .args([
-                "pr",
-                "list",
-                "--repo",
-                &format!("{}/{}", owner, repo),
-                "--head",
-                branch,
-                "--state",
-                "all",
-                "--json",
-                "number,title,state,url,isDraft",
-            ])
-            .output()
-            .context("Failed to execute gh command")?;
+    /// Run `gh pr list` with `args`, retrying once after a short rate-limit wait. `gh` is killed
+    /// if the future is dropped, so a caller's timeout cuts a hanging lookup short.
+    async fn gh_pr_list(args: &[&str]) -> Result<String> {
+        let mut retried = false;
+
+        loop {
+            let output = tokio::process::Command::new("gh")
+                .args(["pr", "list"])
+                .args(args)
+                .kill_on_drop(true)
+                .output()
+                .await
+                .context("Failed to execute gh command")?;
+
+            if output.status.success() {
+                return Ok(String::from_utf8(output.stdout)?);
+            }
 
-        if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            if stderr.contains("not authenticated") || stderr.contains("authentication") {
-                return Err(anyhow!(
-                    "GitHub authentication failed. Run 'gh auth login' to authenticate."
-                ));
+            if let Some(mut limited) = RateLimited::from_gh_stderr(&stderr) {
+                limited.reset_at = Self::rate_limit_reset().await;
+                match limited.backoff(Utc::now()) {
+                    Some(wait) if !retried => {
+                        limited.warn_retrying(wait);
+                        tokio::time::sleep(wait).await;
+                        retried = true;
+                        continue;
+                    }
+                    _ => return Err(limited.into()),
+                }
             }
-            return Err(anyhow!("Failed to fetch pull requests: {}", stderr));
+            return Err(describe_gh_pr_list_failure(&stderr));
         }
+    }
 
-        let stdout = String::from_utf8(output.stdout)?;
-        if stdout.trim().is_empty() {
-            return Ok(vec![]);
-        }
+    /// When the GraphQL limit used by `gh pr list` resets; checking it doesn't count against the limit
+    async fn rate_limit_reset() -> Option<DateTime<Utc>> {
+        let output = tokio::process::Command::new("gh")
+            .args(["api", "rate_limit", "--jq", ".resources.graphql.reset"])
+            .kill_on_drop(true)
+            .output()
+            .await
+            .ok()
+            .filter(|output| output.status.success())?;
 
-        let prs: Vec<serde_json::Value> =
-            serde_json::from_str(&stdout).context("Failed to parse pull requests from gh output")?;
+        let timestamp = String::from_utf8(output.stdout).ok()?.trim().parse::<i64>().ok()?;
+        Utc.timestamp_opt(timestamp, 0).single()
+    }
+
+    pub async fn get_pull_requests(&self, owner: &str, repo: &str, branch: &str) -> Result<Vec<PullRequest>> {
+        // Use gh CLI instead of HTTP API
+        let stdout = Self::gh_pr_list(&[
+            "--repo",
+            &format!("{}/{}", owner, repo),
+            "--head",
+            branch,
+            "--state",
+            "all",
+            "--json",
+            "number,title,state,url,isDraft,reviewDecision,statusCheckRollup,mergeable,author,updatedAt",
+        ])
+        .await?;
+
+        let prs = parse_pr_list(&stdout)?;
 
         Ok(prs
             .into_iter()
@@ -82,46 +135,42 @@ impl GitHubClient {
                 state: pr["state"].as_str().unwrap_or("").to_string(),
                 html_url: pr["url"].as_str().unwrap_or("").to_string(), // Changed from html_url to url
                 draft: pr["isDraft"].as_bool().unwrap_or(false),        // Changed from draft to isDraft
+                review_decision: pr["reviewDecision"]
+                    .as_str()
+                    .filter(|decision| !decision.is_empty())
+                    .map(str::to_string),
+                ci_status: rollup_status(&pr["statusCheckRollup"]),
+                mergeable: pr["mergeable"]
+                    .as_str()
+                    .filter(|mergeable| !mergeable.is_empty())
+                    .map(str::to_string),
+                author: pr["author"]["login"].as_str().map(str::to_string),
+                updated_at: pr["updatedAt"].as_str().and_then(utils::parse_rfc3339),
             })
             .collect())
     }
 
-    pub fn get_all_pull_requests(&self, owner: &str, repo: &str) -> Result<Vec<(PullRequest, String)>> {
+    pub async fn get_all_pull_requests(&self, owner: &str, repo: &str) -> Result<Vec<(PullRequest, String)>> {
         // Fetch all open pull requests with branch information
-        let output = std::process::Command::new("gh")
-            .args([
-                "pr",
-                "list",
-                "--repo",
-                &format!("{}/{}", owner, repo),
-                "--state",
-                "open",
-                "--json",
-                "number,title,state,url,isDraft,headRefName",
-                "--limit",
-                "100",
-            ])
-            .output()
-            .context("Failed to execute gh command")?;
+        let stdout = Self::gh_pr_list(&[
+            "--repo",
+            &format!("{}/{}", owner, repo),
+            "--state",
+            "open",
+            "--json",
+            "number,title,state,url,isDraft,headRefName,author,updatedAt",
+            "--limit",
+            &self.pr_limit.to_string(),
+        ])
+        .await?;
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            if stderr.contains("not authenticated") || stderr.contains("authentication") {
-                return Err(anyhow!(
-                    "GitHub authentication failed. Run 'gh auth login' to authenticate."
-                ));
-            }
-            return Err(anyhow!("Failed to fetch pull requests: {}", stderr));
-        }
+        let prs = parse_pr_list(&stdout)?;
 
-        let stdout = String::from_utf8(output.stdout)?;
-        if stdout.trim().is_empty() {
-            return Ok(vec![]);
+        // gh stops silently at --limit, so a full result set may have been truncated
+        if prs.len() >= self.pr_limit as usize {
+            utils::warn_pr_limit_reached(prs.len());
         }
 
-        let prs: Vec<serde_json::Value> =
-            serde_json::from_str(&stdout).context("Failed to parse pull requests from gh output")?;
-
         Ok(prs
             .into_iter()
             .map(|pr| {
@@ -131,6 +180,11 @@ impl GitHubClient {
                     state: pr["state"].as_str().unwrap_or("").to_string(),
                     html_url: pr["url"].as_str().unwrap_or("").to_string(),
                     draft: pr["isDraft"].as_bool().unwrap_or(false),
+                    review_decision: None,
+                    ci_status: None,
+                    mergeable: None,
+                    author: pr["author"]["login"].as_str().map(str::to_string),
+                    updated_at: pr["updatedAt"].as_str().and_then(utils::parse_rfc3339),
                 };
                 let branch = pr["headRefName"].as_str().unwrap_or("").to_string();
                 (pull_request, branch)
@@ -138,6 +192,134 @@ impl GitHubClient {
             .collect())
     }
 
+    /// Open a pull request with `gh pr create` and return its URL
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_pull_request(
+        &self,
+        owner: &str,
+        repo: &str,
+        head: &str,
+        base: &str,
+        title: &str,
+        body: &str,
+        draft: bool,
+    ) -> Result<String> {
+        let repo_arg = format!("{}/{}", owner, repo);
+        let mut args = vec![
+            "pr", "create", "--repo", &repo_arg, "--head", head, "--base", base, "--title", title, "--body", body,
+        ];
+        if draft {
+            args.push("--draft");
+        }
+
+        let output = std::process::Command::new("gh")
+            .args(&args)
+            .output()
+            .context("Failed to execute gh command")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("Failed to create pull request: {}", stderr.trim()));
+        }
+
+        // gh prints the URL of the new pull request as the last line
+        let stdout = String::from_utf8(output.stdout)?;
+        stdout
+            .lines()
+            .last()
+            .map(|line| line.trim().to_string())
+            .ok_or_else(|| anyhow!("gh did not return a pull request URL"))
+    }
+
+    /// Title of issue `number`, via `gh issue view`
+    pub fn get_issue_title(&self, owner: &str, repo: &str, number: u32) -> Result<String> {
+        let output = std::process::Command::new("gh")
+            .args([
+                "issue",
+                "view",
+                &number.to_string(),
+                "--repo",
+                &format!("{}/{}", owner, repo),
+                "--json",
+                "title",
+                "--jq",
+                ".title",
+            ])
+            .output()
+            .context("Failed to execute gh command")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if stderr.contains("Could not resolve to an issue") {
+                return Err(anyhow!("Issue #{} does not exist in {}/{}", number, owner, repo));
+            }
+            if let Some(limited) = RateLimited::from_gh_stderr(&stderr) {
+                return Err(limited.into());
+            }
+            return Err(anyhow!("Failed to fetch issue #{}: {}", number, stderr.trim()));
+        }
+
+        Ok(String::from_utf8(output.stdout)?.trim().to_string())
+    }
+
+    /// Head branch of pull request `number` and whether it comes from a fork, via `gh pr view`
+    pub fn get_pull_request_head(&self, owner: &str, repo: &str, number: u32) -> Result<(String, bool)> {
+        let output = std::process::Command::new("gh")
+            .args([
+                "pr",
+                "view",
+                &number.to_string(),
+                "--repo",
+                &format!("{}/{}", owner, repo),
+                "--json",
+                "headRefName,isCrossRepository",
+                "--jq",
+                "[.headRefName, .isCrossRepository] | @tsv",
+            ])
+            .output()
+            .context("Failed to execute gh command")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if stderr.contains("Could not resolve to a PullRequest") {
+                return Err(anyhow!("Pull request #{} does not exist in {}/{}", number, owner, repo));
+            }
+            if let Some(limited) = RateLimited::from_gh_stderr(&stderr) {
+                return Err(limited.into());
+            }
+            return Err(anyhow!("Failed to fetch pull request #{}: {}", number, stderr.trim()));
+        }
+
+        let stdout = String::from_utf8(output.stdout)?;
+        let (branch, cross_repository) = stdout
+            .trim()
+            .split_once('\t')
+            .ok_or_else(|| anyhow!("Unexpected gh output for pull request #{}: {}", number, stdout.trim()))?;
+        Ok((branch.to_string(), cross_repository == "true"))
+    }
+
+    /// Login of the account `gh` is authenticated as
+    pub fn get_current_user(&self) -> Result<String> {
+        let output = std::process::Command::new("gh")
+            .args(["api", "user", "--jq", ".login"])
+            .output()
+            .context("Failed to execute gh command")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if let Some(limited) = RateLimited::from_gh_stderr(&stderr) {
+                return Err(limited.into());
+            }
+            return Err(anyhow!("Failed to look up the GitHub user: {}", stderr.trim()));
+        }
+
+        let login = String::from_utf8(output.stdout)?.trim().to_string();
+        if login.is_empty() {
+            return Err(anyhow!("gh did not return a GitHub login"));
+        }
+        Ok(login)
+    }
+
     pub fn parse_github_url(url: &str) -> Option<(String, String)> {
         // Parse both HTTPS and SSH URLs
         if let Some(captures) = url.strip_prefix("https://github.com/") {
@@ -155,6 +337,85 @@ impl GitHubClient {
     }
 }
 
+/// The JSON array `gh pr list --json` printed. gh sometimes puts notices (e.g. about a new
+/// release) on stdout as well, so anything before the array's first line or after it is skipped.
+fn parse_pr_list(stdout: &str) -> Result<Vec<serde_json::Value>> {
+    if stdout.trim().is_empty() {
+        return Ok(vec![]);
+    }
+
+    let start = stdout
+        .lines()
+        .scan(0, |offset, line| {
+            let line_start = *offset;
+            *offset += line.len() + 1;
+            Some((line_start, line))
+        })
+        .find(|(_, line)| line.trim_start().starts_with('['))
+        .map(|(offset, _)| offset);
+    let Some(start) = start else {
+        let first_line = stdout.trim().lines().next().unwrap_or_default();
+        return Err(anyhow!("Unexpected output from gh, expected JSON: {}", first_line));
+    };
+
+    // Only the first value is read, so a notice after the array doesn't matter either
+    serde_json::Deserializer::from_str(&stdout[start..])
+        .into_iter::<Vec<serde_json::Value>>()
+        .next()
+        .unwrap_or_else(|| Ok(vec![]))
+        .context("Failed to parse pull requests from gh output")
+}
+
+/// Turn what a failed `gh pr list` printed into an error that says what to do about it
+fn describe_gh_pr_list_failure(stderr: &str) -> anyhow::Error {
+    let stderr = stderr.trim();
+    if stderr.contains("not authenticated") || stderr.contains("authentication") {
+        anyhow!("GitHub authentication failed. Run 'gh auth login' to authenticate.")
+    } else if stderr.contains("Could not resolve to a Repository") {
+        anyhow!(
+            "GitHub repository not found, or your gh account can't see it. Check repositoryUrl in git-worktree-config.yaml and 'gh auth status'.\ngh said: {}",
+            stderr
+        )
+    } else if stderr.contains("Could not resolve host") || stderr.contains("connection refused") {
+        anyhow!(
+            "Could not reach GitHub; check your network connection.\ngh said: {}",
+            stderr
+        )
+    } else {
+        anyhow!("Failed to fetch pull requests: {}", stderr)
+    }
+}
+
+/// One result for a `statusCheckRollup`: any failure wins, then anything still running.
+/// Check runs report `status`/`conclusion`, commit statuses a single `state`.
+fn rollup_status(rollup: &serde_json::Value) -> Option<String> {
+    let checks = rollup.as_array().filter(|checks| !checks.is_empty())?;
+    let outcome = |check: &serde_json::Value| {
+        let field = |name: &str| check[name].as_str().unwrap_or("").to_uppercase();
+        match (field("conclusion"), field("state")) {
+            (conclusion, _)
+                if matches!(
+                    conclusion.as_str(),
+                    "FAILURE" | "TIMED_OUT" | "CANCELLED" | "ACTION_REQUIRED" | "STARTUP_FAILURE"
+                ) =>
+            {
+                "FAILURE"
+            }
+            (_, state) if matches!(state.as_str(), "FAILURE" | "ERROR") => "FAILURE",
+            (_, state) if matches!(state.as_str(), "PENDING" | "EXPECTED") => "PENDING",
+            (conclusion, state) if conclusion.is_empty() && state.is_empty() => "PENDING",
+            _ => "SUCCESS",
+        }
+    };
+
+    let outcomes: Vec<&str> = checks.iter().map(outcome).collect();
+    let status = ["FAILURE", "PENDING"]
+        .into_iter()
+        .find(|status| outcomes.contains(status))
+        .unwrap_or("SUCCESS");
+    Some(status.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -185,4 +446,74 @@ mod tests {
             assert_eq!(GitHubClient::parse_github_url(url), expected);
         }
     }
+
+    #[test]
+    fn test_parse_pr_list_skips_notices() {
+        let array = r#"[{"number": 42, "title": "Add login"}]"#;
+        assert_eq!(parse_pr_list(array).unwrap()[0]["number"], 42);
+        assert!(parse_pr_list("  \n").unwrap().is_empty());
+
+        let noisy = format!(
+            "A new release of gh is available: 2.40.0 → 2.50.0\nhttps://github.com/cli/cli/releases\n{}\n",
+            array
+        );
+        assert_eq!(parse_pr_list(&noisy).unwrap()[0]["title"], "Add login");
+
+        let pretty = "warning: something\n[\n  {\"number\": 7}\n]\n\nTo upgrade, run: brew upgrade gh\n";
+        assert_eq!(parse_pr_list(pretty).unwrap()[0]["number"], 7);
+
+        let error = parse_pr_list("no pull requests match your search\n").unwrap_err();
+        assert!(
+            error.to_string().contains("expected JSON: no pull requests"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_describe_gh_pr_list_failure() {
+        let message = |stderr| describe_gh_pr_list_failure(stderr).to_string();
+        assert!(message("HTTP 401: Requires authentication").contains("gh auth login"));
+        assert!(
+            message("GraphQL: Could not resolve to a Repository with the name 'owner/gone'. (repository)")
+                .starts_with("GitHub repository not found")
+        );
+        assert!(
+            message("error connecting to api.github.com: Could not resolve host").starts_with("Could not reach GitHub")
+        );
+        assert_eq!(
+            message("something else\n"),
+            "Failed to fetch pull requests: something else"
+        );
+    }
+
+    #[test]
+    fn test_rollup_status() {
+        let rollup = |checks: serde_json::Value| rollup_status(&checks);
+
+        assert_eq!(rollup(serde_json::json!([])), None);
+        assert_eq!(rollup(serde_json::Value::Null), None);
+        assert_eq!(
+            rollup(serde_json::json!([
+                {"status": "COMPLETED", "conclusion": "SUCCESS"},
+                {"state": "SUCCESS"},
+                {"status": "COMPLETED", "conclusion": "SKIPPED"}
+            ])),
+            Some("SUCCESS".to_string())
+        );
+        assert_eq!(
+            rollup(serde_json::json!([
+                {"status": "IN_PROGRESS", "conclusion": ""},
+                {"status": "COMPLETED", "conclusion": "SUCCESS"}
+            ])),
+            Some("PENDING".to_string())
+        );
+        assert_eq!(
+            rollup(serde_json::json!([
+                {"status": "IN_PROGRESS", "conclusion": ""},
+                {"state": "ERROR"}
+            ])),
+            Some("FAILURE".to_string())
+        );
+    }
 }
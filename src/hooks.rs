@@ -1,65 +1,130 @@
 use anyhow::{Context, Result};
 use colored::Colorize;
-use std::path::Path;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
-use crate::config::GitWorktreeConfig;
+use crate::config::{GitWorktreeConfig, LocalConfig};
+use crate::{git, platform, ui, utils};
 
 pub fn execute_hooks(hook_type: &str, working_directory: &Path, variables: &[(&str, &str)]) -> Result<()> {
+    run_hooks(hook_type, working_directory, variables, false)
+}
+
+/// `postAdd` hooks, detached from gwt when `background` or `backgroundHooks` is set so the
+/// shell integration can cd into the new worktree straight away
+pub fn execute_post_add_hooks(working_directory: &Path, variables: &[(&str, &str)], background: bool) -> Result<()> {
+    run_hooks("postAdd", working_directory, variables, background)
+}
+
+/// The commands `execute_hooks` would run, with variables substituted and commented lines
+/// left out, for `--dry-run` previews
+pub fn render_hooks(hook_type: &str, working_directory: &Path, variables: &[(&str, &str)]) -> Result<Vec<String>> {
+    Ok(match configured_hooks(hook_type, working_directory)? {
+        Some((config_path, _, hook_commands)) => {
+            let variables = with_relative_worktree_path(variables, config_path.parent());
+            render_commands(&hook_commands, &borrowed(&variables))
+        }
+        None => Vec::new(),
+    })
+}
+
+/// The config's path, the project config and the non-empty list of `hook_type` hooks, if there are any
+fn configured_hooks(
+    hook_type: &str,
+    working_directory: &Path,
+) -> Result<Option<(PathBuf, GitWorktreeConfig, Vec<String>)>> {
     // Find the config file
-    let config = match GitWorktreeConfig::find_config()? {
-        Some((_, config)) => config,
+    let (config_path, config) = match GitWorktreeConfig::find_config()? {
+        Some(found) => found,
         None => {
             // No config file found, skip hooks
-            return Ok(());
+            return Ok(None);
         }
     };
 
-    let hooks = match &config.hooks {
-        Some(hooks) => hooks,
-        None => return Ok(()),
+    // A worktree's .gwt.local.yaml can override individual hook types
+    let local_hooks = LocalConfig::find(working_directory)?.and_then(|local| local.hooks);
+
+    let hooks = match (config.effective_hooks(), local_hooks) {
+        (Some(project_hooks), Some(local_hooks)) => project_hooks.merged_with(&local_hooks),
+        (Some(hooks), None) | (None, Some(hooks)) => hooks,
+        (None, None) => return Ok(None),
     };
 
-    let hook_commands = match hook_type {
-        "postAdd" => &hooks.post_add,
-        "postRemove" => &hooks.post_remove,
-        _ => return Ok(()),
+    let hook_commands = match hooks.get(hook_type) {
+        Some(Some(commands)) if !commands.is_empty() => commands.clone(),
+        _ => return Ok(None),
     };
 
-    let hook_commands = match hook_commands {
-        Some(commands) => commands,
-        None => return Ok(()),
+    Ok(Some((config_path, config, hook_commands)))
+}
+
+/// `variables` plus `worktreePathRelative`: `worktreePath` relative to the project root, or
+/// unchanged for worktrees outside it
+fn with_relative_worktree_path<'a>(
+    variables: &[(&'a str, &'a str)],
+    project_root: Option<&Path>,
+) -> Vec<(&'a str, String)> {
+    let mut all: Vec<(&str, String)> = variables
+        .iter()
+        .map(|(name, value)| (*name, value.to_string()))
+        .collect();
+
+    let worktree_path = variables.iter().find(|(name, _)| *name == "worktreePath");
+    if let (Some((_, worktree_path)), Some(root)) = (worktree_path, project_root) {
+        // git reports canonical paths; a removed worktree can't be canonicalized, but then came from git
+        let canonical = |path: &Path| fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let relative = utils::relative_to(&canonical(Path::new(worktree_path)), &canonical(root));
+        all.push(("worktreePathRelative", relative));
+    }
+    all
+}
+
+fn borrowed<'a>(variables: &'a [(&'a str, String)]) -> Vec<(&'a str, &'a str)> {
+    variables.iter().map(|(name, value)| (*name, value.as_str())).collect()
+}
+
+fn run_hooks(hook_type: &str, working_directory: &Path, variables: &[(&str, &str)], background: bool) -> Result<()> {
+    let Some((config_path, config, hook_commands)) = configured_hooks(hook_type, working_directory)? else {
+        return Ok(());
     };
+    let variables = with_relative_worktree_path(variables, config_path.parent());
+    let variables = &borrowed(&variables);
 
-    if hook_commands.is_empty() {
+    if background || config.background_hooks == Some(true) {
+        let commands = render_commands(&hook_commands, variables);
+        if !commands.is_empty() {
+            let log_path = spawn_in_background(hook_type, &commands, working_directory)?;
+            ui::success(format!("{} hooks running in the background", hook_type));
+            println!(
+                "   {}",
+                format!("Follow them with: tail -f {}", log_path.display()).dimmed()
+            );
+        }
         return Ok(());
     }
 
-    println!("{}", format!("🪝 Running {} hooks...", hook_type).cyan());
+    ui::step(format!("🪝 Running {} hooks...", hook_type));
 
-    for hook in hook_commands {
+    for hook in &hook_commands {
         // Skip commented lines
-        if hook.trim().starts_with('#') {
+        if is_commented(hook) {
             println!("   {}", format!("Skipping commented hook: {}", hook).yellow());
             continue;
         }
 
-        // Replace variables in the hook command
-        let mut command = hook.clone();
-        for (var_name, var_value) in variables {
-            let placeholder = format!("${{{}}}", var_name);
-            command = command.replace(&placeholder, var_value);
-        }
+        let command = substitute_variables(hook, variables);
 
         println!("   {}", format!("Executing: {}", command).blue());
 
         // Execute with streaming output - this is the key improvement!
         match execute_command_streaming(&command, working_directory) {
             Ok(()) => {
-                println!("   {}", "✓ Hook completed successfully".green());
+                ui::success("Hook completed successfully");
             }
             Err(e) => {
-                println!("   {}", format!("⚠️  Hook failed: {}", e).yellow());
+                ui::warn(format!("Hook failed: {}", e));
                 // Continue with other hooks even if one fails
             }
         }
@@ -68,9 +133,80 @@ pub fn execute_hooks(hook_type: &str, working_directory: &Path, variables: &[(&s
     Ok(())
 }
 
+/// Hooks can be switched off by commenting them out with `#`
+fn is_commented(hook: &str) -> bool {
+    hook.trim().starts_with('#')
+}
+
+/// The hooks that would run, in order, with their variables substituted
+fn render_commands(hook_commands: &[String], variables: &[(&str, &str)]) -> Vec<String> {
+    hook_commands
+        .iter()
+        .filter(|hook| !is_commented(hook))
+        .map(|hook| substitute_variables(hook, variables))
+        .collect()
+}
+
+/// Replace `${name}` placeholders in a hook command
+fn substitute_variables(hook: &str, variables: &[(&str, &str)]) -> String {
+    let mut command = hook.to_string();
+    for (var_name, var_value) in variables {
+        let placeholder = format!("${{{}}}", var_name);
+        command = command.replace(&placeholder, var_value);
+    }
+    command
+}
+
+/// Start `commands` one after another in a detached shell, writing their output to a log in
+/// the worktree's git directory (so it never shows up in `git status`). Returns the log path.
+fn spawn_in_background(hook_type: &str, commands: &[String], working_directory: &Path) -> Result<PathBuf> {
+    let log_dir = git::worktree_git_dir(working_directory).unwrap_or_else(|| working_directory.to_path_buf());
+    let log_path = log_dir.join(format!("gwt-{}.log", hook_type));
+    let log = File::create(&log_path).with_context(|| format!("Failed to create {}", log_path.display()))?;
+
+    let (shell, command_flag) = platform::default_shell();
+    let mut cmd = Command::new(shell);
+    cmd.arg(command_flag)
+        .arg(background_script(commands, std::env::consts::OS))
+        .current_dir(working_directory)
+        .stdin(Stdio::null())
+        .stdout(log.try_clone()?)
+        .stderr(log);
+
+    // A process group of its own keeps Ctrl-C in the shell from reaching the hooks
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+        const DETACHED_PROCESS: u32 = 0x0000_0008;
+        cmd.creation_flags(CREATE_NEW_PROCESS_GROUP | DETACHED_PROCESS);
+    }
+
+    cmd.spawn().context("Failed to start hooks in the background")?;
+    Ok(log_path)
+}
+
+/// One shell script running `commands` in order; like foreground hooks, a failure doesn't stop the rest
+fn background_script(commands: &[String], os: &str) -> String {
+    match os {
+        "windows" => commands.join(" & "),
+        _ => commands
+            .iter()
+            .map(|command| format!("echo '$ {}'\n{}", command.replace('\'', "'\\''"), command))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
 fn execute_command_streaming(command: &str, working_directory: &Path) -> Result<()> {
-    let mut cmd = Command::new("sh");
-    cmd.arg("-c")
+    let (shell, command_flag) = platform::default_shell();
+    let mut cmd = Command::new(shell);
+    cmd.arg(command_flag)
         .arg(command)
         .current_dir(working_directory)
         .stdout(Stdio::inherit())
@@ -85,3 +221,73 @@ fn execute_command_streaming(command: &str, working_directory: &Path) -> Result<
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_substitute_variables() {
+        let variables = [("branchName", "feature/login"), ("worktreePath", "/work/feature/login")];
+        assert_eq!(
+            substitute_variables("cd ${worktreePath} && echo ${branchName} ${branchName}", &variables),
+            "cd /work/feature/login && echo feature/login feature/login"
+        );
+        // Unknown placeholders and shell variables are left for the shell
+        assert_eq!(
+            substitute_variables("echo ${baseBranch} $HOME", &variables),
+            "echo ${baseBranch} $HOME"
+        );
+    }
+
+    #[test]
+    fn test_relative_worktree_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("project");
+        fs::create_dir_all(root.join("feature/login")).unwrap();
+
+        let inside = root.join("feature/login");
+        let variables = with_relative_worktree_path(
+            &[
+                ("branchName", "feature/login"),
+                ("worktreePath", inside.to_str().unwrap()),
+            ],
+            Some(&root),
+        );
+        assert_eq!(variables[2], ("worktreePathRelative", "feature/login".to_string()));
+        assert_eq!(variables[1].1, inside.to_str().unwrap());
+
+        // Outside the project root there is nothing to be relative to
+        let outside = dir.path().join("elsewhere");
+        let variables = with_relative_worktree_path(&[("worktreePath", outside.to_str().unwrap())], Some(&root));
+        assert_eq!(variables[1].1, outside.to_str().unwrap());
+
+        assert_eq!(
+            with_relative_worktree_path(&[("branchName", "main")], Some(&root)).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_render_commands_skips_commented_hooks() {
+        let hooks = vec![
+            "npm install".to_string(),
+            "  # echo ${branchName}".to_string(),
+            "echo ${branchName}".to_string(),
+        ];
+        assert_eq!(
+            render_commands(&hooks, &[("branchName", "feature")]),
+            vec!["npm install", "echo feature"]
+        );
+    }
+
+    #[test]
+    fn test_background_script() {
+        let commands = vec!["npm install".to_string(), "echo 'done'".to_string()];
+        assert_eq!(
+            background_script(&commands, "linux"),
+            "echo '$ npm install'\nnpm install\necho '$ echo '\\''done'\\'''\necho 'done'"
+        );
+        assert_eq!(background_script(&commands, "windows"), "npm install & echo 'done'");
+    }
+}
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use clap::Parser;
 use colored::Colorize;
+use std::process::ExitCode;
 
 mod bitbucket_api;
 mod bitbucket_auth;
@@ -13,26 +14,239 @@ mod config;
 mod git;
 mod github;
 mod hooks;
+mod platform;
+mod project;
+mod rate_limit;
+mod ui;
 mod utils;
 
-use cli::{AuthAction, Cli, Commands, CompletionAction};
-use commands::{add, auth, init, list, remove};
+use cli::{AuthAction, Cli, Commands, CompletionAction, PathStyleArgs};
+use commands::{add, auth, init, list, list_format, lock, move_all, remove, status, switch, upgrade};
+use config::{DefaultCommand, PathStyle};
 
-fn main() -> Result<()> {
+fn main() -> ExitCode {
     let cli = Cli::parse();
+    ui::configure(cli.quiet, cli.no_color);
 
-    match cli.command {
-        Commands::Init { repo_url, provider } => {
-            init::run(&repo_url, provider)?;
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            ui::report_error(&error);
+            ExitCode::FAILURE
         }
-        Commands::Add { branch_name } => {
-            add::run(&branch_name)?;
+    }
+}
+
+/// Bare `gwt` runs the project's `defaultCommand`; outside a project, status explains how to start one
+fn run_default_command() -> Result<()> {
+    let default_command = config::GitWorktreeConfig::find_config()
+        .ok()
+        .flatten()
+        .and_then(|(_, config)| config.default_command)
+        .unwrap_or_default();
+
+    match default_command {
+        DefaultCommand::Status => status::run(),
+        DefaultCommand::List => list::run(&list::ListOptions::default()),
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    let Some(command) = cli.command else {
+        return run_default_command();
+    };
+
+    // The upgrade command does its own checking, and completion output must stay clean
+    let notify_updates = !matches!(command, Commands::Upgrade { .. } | Commands::Completions { .. })
+        && upgrade::update_check_enabled(|| {
+            config::GitWorktreeConfig::find_config()
+                .ok()
+                .flatten()
+                .and_then(|(_, config)| config.check_updates)
+                .unwrap_or(false)
+        });
+
+    match command {
+        Commands::Init {
+            repo_url,
+            provider,
+            host_aliases,
+            main_worktree_name,
+            remote_name,
+            no_clone_rename,
+            no_remote_check,
+            mirror,
+            submodules,
+            template,
+        } => {
+            let options = init::InitOptions {
+                provider,
+                host_aliases: host_aliases.into_iter().collect(),
+                main_worktree_name,
+                remote_name,
+                no_clone_rename,
+                no_remote_check,
+                mirror,
+                submodules,
+                template,
+            };
+            init::run(&repo_url, options)?;
+        }
+        Commands::Add {
+            branch_name,
+            from_issue,
+            checkout_pr,
+            name_from_pr,
+            path,
+            detach,
+            git_ref,
+            from,
+            base_sha,
+            track_remote,
+            track,
+            no_track,
+            bg_hooks,
+            create_pr,
+            draft,
+            title,
+            base,
+            open_pr_if_exists,
+            print,
+            orphan,
+            path_style,
+        } => {
+            let issue = from_issue.map(add::issue_branch).transpose()?;
+            let branch_name = match (&issue, checkout_pr) {
+                (Some(issue), _) => issue.branch_name.clone(),
+                (None, Some(number)) => add::pull_request_branch(number)?,
+                (None, None) => branch_name.unwrap_or_default(),
+            };
+            // The branch is still what git, `gwt list` and `gwt remove` know the worktree by
+            let dir_name = checkout_pr
+                .filter(|_| name_from_pr)
+                .map(|number| format!("pr-{}", number));
+            let options = add::AddOptions {
+                path: path.as_deref(),
+                detach,
+                git_ref: git_ref.as_deref(),
+                from: from.as_deref(),
+                base_sha: base_sha.as_deref(),
+                track_remote: track_remote.as_deref(),
+                track: (track || no_track).then_some(track),
+                background_hooks: bg_hooks,
+                create_pr,
+                draft,
+                title: title.as_deref(),
+                base: base.as_deref(),
+                path_style: path_style_override(&path_style),
+                issue: issue.as_ref(),
+                dir_name: dir_name.as_deref(),
+                open_existing_pr: open_pr_if_exists,
+                print_pr_url: print,
+                orphan,
+            };
+            add::run(&branch_name, &options)?;
+        }
+        Commands::List {
+            pr_limit,
+            since,
+            since_only,
+            full_titles,
+            contains,
+            no_pr_only,
+            compact,
+            json,
+            json_lines,
+            format,
+            fetch_prs_timeout,
+            include_bare,
+            all,
+            no_summary,
+            last_commit,
+            untracked,
+            no_pr_for,
+            mine,
+            conflicts,
+            path_style,
+        } => {
+            let options = list::ListOptions {
+                pr_limit,
+                since,
+                since_only,
+                full_titles,
+                contains,
+                no_pr_only,
+                compact,
+                json,
+                json_lines,
+                format: format.as_deref().map(list_format::Template::parse).transpose()?,
+                fetch_prs_timeout: fetch_prs_timeout.map(std::time::Duration::from_secs),
+                include_bare,
+                all,
+                no_summary,
+                last_commit,
+                untracked,
+                no_pr_for,
+                mine,
+                conflicts,
+                path_style: path_style_override(&path_style),
+            };
+            list::run(&options)?;
+        }
+        Commands::Lock {
+            branch_name,
+            reason,
+            path_style,
+        } => {
+            lock::lock(
+                branch_name.as_deref(),
+                reason.as_deref(),
+                path_style_override(&path_style),
+            )?;
         }
-        Commands::List => {
-            list::run()?;
+        Commands::Unlock {
+            branch_name,
+            path_style,
+        } => {
+            lock::unlock(branch_name.as_deref(), path_style_override(&path_style))?;
         }
-        Commands::Remove { branch_name } => {
-            remove::run(branch_name.as_deref())?;
+        Commands::MoveAll { new_root, path_style } => {
+            move_all::run(&new_root, path_style_override(&path_style))?;
+        }
+        Commands::Remove {
+            branch_name,
+            force_protected,
+            force_branch,
+            all_merged,
+            yes,
+            dry_run,
+            path_style,
+        } => {
+            if all_merged {
+                remove::run_all_merged(yes, dry_run, path_style_override(&path_style))?;
+            } else {
+                remove::run(
+                    branch_name.as_deref(),
+                    force_protected,
+                    force_branch,
+                    yes,
+                    dry_run,
+                    path_style_override(&path_style),
+                )?;
+            }
+        }
+        Commands::Switch {
+            branch_name,
+            create,
+            print_path,
+            safe,
+            path_style,
+        } => {
+            switch::run(&branch_name, create, print_path, safe, path_style_override(&path_style))?;
+        }
+        Commands::Config { action, yes } => {
+            // Not imported by name: it would shadow the crate's config module
+            commands::config::run(action, yes)?;
         }
         Commands::Auth { action } => match action {
             AuthAction::Github => {
@@ -48,11 +262,22 @@ fn main() -> Result<()> {
         Commands::Completions { action } => {
             handle_completions(action)?;
         }
+        Commands::Upgrade { refresh, background } => {
+            upgrade::run(refresh, background)?;
+        }
+    }
+
+    if notify_updates {
+        upgrade::notify_if_outdated();
     }
 
     Ok(())
 }
 
+fn path_style_override(args: &PathStyleArgs) -> Option<PathStyle> {
+    PathStyle::from_flags(args.relative, args.absolute)
+}
+
 fn handle_completions(action: Option<CompletionAction>) -> Result<()> {
     match action {
         None => {
@@ -63,10 +288,18 @@ fn handle_completions(action: Option<CompletionAction>) -> Result<()> {
             // Output the pre-generated completion to stdout
             println!("{}", completions::get_completion_content(shell));
         }
-        Some(CompletionAction::Install { shell }) => {
+        Some(CompletionAction::Install { all: true, .. }) => {
+            completions::install_completions_for_all_shells()?;
+        }
+        Some(CompletionAction::Install { shell, .. }) => {
             let shell = shell.unwrap_or_else(|| completions::detect_shell().unwrap_or(clap_complete::Shell::Bash));
             completions::install_completions_for_shell(shell)?;
         }
+        Some(CompletionAction::Shells) => {
+            for shell in completions::supported_shells(completions::detect_shell().ok()) {
+                println!("{}", shell);
+            }
+        }
     }
     Ok(())
 }
@@ -78,10 +311,10 @@ fn check_completions_status() -> Result<()> {
     let installed = completions::check_completions_installed(shell)?;
 
     if installed {
-        println!("✓ Completions appear to be installed");
+        ui::success("Completions appear to be installed");
         println!("\nTo reinstall or update, run: {}", "gwt completions install".cyan());
     } else {
-        println!("✗ Completions not installed");
+        ui::error("Completions not installed");
         println!("\nTo install completions, run: {}", "gwt completions install".cyan());
     }
 
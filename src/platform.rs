@@ -0,0 +1,84 @@
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+/// Open a URL in the default browser
+pub fn open_url(url: &str) -> Result<()> {
+    run_opener(&open_command(std::env::consts::OS, url))
+}
+
+/// Open a file or directory with the default application
+#[allow(dead_code)]
+pub fn open_path(path: &Path) -> Result<()> {
+    let target = path.to_str().context("Path is not valid UTF-8")?;
+    run_opener(&open_command(std::env::consts::OS, target))
+}
+
+/// Shell program and the flag that makes it run a command string, e.g. `sh -c`
+pub fn default_shell() -> (&'static str, &'static str) {
+    shell_for(std::env::consts::OS)
+}
+
+fn shell_for(os: &str) -> (&'static str, &'static str) {
+    match os {
+        "windows" => ("cmd", "/C"),
+        _ => ("sh", "-c"),
+    }
+}
+
+/// Command vector that hands `target` to the platform's default handler
+fn open_command(os: &str, target: &str) -> Vec<String> {
+    match os {
+        "macos" => vec!["open".to_string(), target.to_string()],
+        // Not `cmd /C start`: cmd would split URLs at `&` and take a quoted target for a window title
+        "windows" => vec![
+            "rundll32".to_string(),
+            "url.dll,FileProtocolHandler".to_string(),
+            target.to_string(),
+        ],
+        _ => vec!["xdg-open".to_string(), target.to_string()],
+    }
+}
+
+fn run_opener(command: &[String]) -> Result<()> {
+    let (program, args) = command.split_first().context("Empty command")?;
+
+    let status = Command::new(program)
+        .args(args)
+        .status()
+        .with_context(|| format!("Failed to run {}", program))?;
+
+    if !status.success() {
+        bail!("{} exited with code: {:?}", program, status.code());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_command_per_platform() {
+        let url = "https://example.com";
+        assert_eq!(open_command("linux", url), vec!["xdg-open", url]);
+        assert_eq!(open_command("freebsd", url), vec!["xdg-open", url]);
+        assert_eq!(open_command("macos", url), vec!["open", url]);
+        assert_eq!(
+            open_command("windows", url),
+            vec!["rundll32", "url.dll,FileProtocolHandler", url]
+        );
+
+        // Query strings reach the handler as a single argument
+        let query = "https://example.com/compare?a=1&b=2";
+        assert_eq!(open_command("windows", query).last().map(String::as_str), Some(query));
+    }
+
+    #[test]
+    fn test_shell_per_platform() {
+        assert_eq!(shell_for("linux"), ("sh", "-c"));
+        assert_eq!(shell_for("macos"), ("sh", "-c"));
+        assert_eq!(shell_for("windows"), ("cmd", "/C"));
+    }
+}
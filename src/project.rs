@@ -0,0 +1,156 @@
+use anyhow::{bail, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::{GitWorktreeConfig, PathStyle, CONFIG_FILENAME};
+use crate::{git, utils};
+
+/// Formats worktree paths for output according to `pathStyle` or a command-line override
+pub struct PathDisplay {
+    style: PathStyle,
+    project_root: Option<PathBuf>,
+}
+
+impl PathDisplay {
+    /// The override wins over the project's `pathStyle`; outside a project paths stay absolute
+    pub fn new(style_override: Option<PathStyle>) -> Result<Self> {
+        Ok(Self::for_project(
+            style_override,
+            GitWorktreeConfig::find_config()?.as_ref(),
+        ))
+    }
+
+    /// Like `new`, for a project configuration that has already been found
+    pub fn for_project(style_override: Option<PathStyle>, found_config: Option<&(PathBuf, GitWorktreeConfig)>) -> Self {
+        let style = style_override
+            .or_else(|| found_config.and_then(|(_, config)| config.path_style))
+            .unwrap_or_default();
+        let project_root = found_config
+            .and_then(|(config_path, _)| config_path.parent().map(Path::to_path_buf))
+            // git reports canonical worktree paths, so the root must be canonical to strip it
+            .map(|root| fs::canonicalize(&root).unwrap_or(root));
+
+        Self { style, project_root }
+    }
+
+    pub fn show(&self, path: &Path) -> String {
+        match (self.style, &self.project_root) {
+            (PathStyle::Relative, Some(root)) => {
+                let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+                utils::relative_to(&path, root)
+            }
+            _ => path.display().to_string(),
+        }
+    }
+}
+
+/// Find the project root (the directory holding git-worktree-config.yaml).
+///
+/// Works from any worktree of the project, including ones placed outside the project root,
+/// because config discovery falls back to the repository's shared git directory.
+pub fn find_project_root() -> Result<PathBuf> {
+    if let Some((config_path, _)) = GitWorktreeConfig::find_config()? {
+        if let Some(project_root) = config_path.parent() {
+            return Ok(project_root.to_path_buf());
+        }
+    }
+
+    // No config found, provide helpful error
+    if git::get_git_root()?.is_some() {
+        bail!("Found git repository but no git-worktree-config.yaml. This doesn't appear to be a worktree project.");
+    } else {
+        bail!("Not in a git repository or project root with git-worktree-config.yaml");
+    }
+}
+
+/// Find every project root at or below `dir`, sorted by path.
+///
+/// Doesn't look inside a project once found, nor into git repositories, worktrees or hidden
+/// directories, so large source trees are never walked. Directories below `start` that can't
+/// be read (e.g. other users' homes) are skipped.
+pub fn find_project_roots(start: &Path) -> Result<Vec<PathBuf>> {
+    let mut roots = Vec::new();
+    let mut pending = vec![start.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        if dir.join(CONFIG_FILENAME).exists() {
+            roots.push(dir);
+            continue;
+        }
+        if dir.join(".git").exists() {
+            continue;
+        }
+
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) if dir != start => continue,
+            Err(e) => return Err(e.into()),
+        };
+        for entry in entries.flatten() {
+            let hidden = entry.file_name().to_string_lossy().starts_with('.');
+            if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) && !hidden {
+                pending.push(entry.path());
+            }
+        }
+    }
+
+    roots.sort();
+    Ok(roots)
+}
+
+/// Find a directory to run git commands from.
+///
+/// Any worktree works since they all share one repository; `git worktree list` run from it
+/// is the authoritative list of worktrees regardless of where they live on disk.
+pub fn find_git_directory() -> Result<PathBuf> {
+    git_directory_for(GitWorktreeConfig::find_config()?.as_ref())
+}
+
+/// Like `find_git_directory`, for a project configuration that has already been found
+pub fn git_directory_for(found_config: Option<&(PathBuf, GitWorktreeConfig)>) -> Result<PathBuf> {
+    if let Some((config_path, config)) = found_config {
+        let project_root = config_path.parent().unwrap_or(Path::new("."));
+
+        // Prefer the worktree init created, which is the least likely to be removed
+        if let Some(name) = &config.main_worktree_name {
+            let main_worktree = project_root.join(name);
+            if main_worktree.join(".git").exists() {
+                return Ok(main_worktree);
+            }
+        }
+
+        return find_existing_worktree(project_root);
+    }
+
+    // No config found, check if we're directly in a git repository
+    if let Some(git_root) = git::get_git_root()? {
+        Ok(git_root)
+    } else {
+        bail!("Not in a git repository or project root with git-worktree-config.yaml");
+    }
+}
+
+/// Find a worktree (or repository) directory directly under the project root.
+///
+/// Falls back to a bare repository (e.g. `repo.git`) so the first worktree of a
+/// bare layout can be created from it.
+pub fn find_existing_worktree(project_root: &Path) -> Result<PathBuf> {
+    let mut dirs = Vec::new();
+    for entry in fs::read_dir(project_root)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            dirs.push(entry.path());
+        }
+    }
+
+    if let Some(worktree) = dirs.iter().find(|dir| dir.join(".git").exists()) {
+        return Ok(worktree.clone());
+    }
+
+    // Only ask git once no regular worktree was found; it is a process per directory
+    if let Some(bare_repo) = dirs.into_iter().find(|dir| git::is_bare_repository(dir)) {
+        return Ok(bare_repo);
+    }
+
+    bail!("No existing worktrees found in project root. Create one first using gwt init.")
+}
@@ -0,0 +1,161 @@
+use chrono::{DateTime, Local, TimeZone, Utc};
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use std::time::Duration;
+
+use crate::ui;
+
+/// Longest wait worth sitting through before retrying a rate-limited request once
+pub const MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+/// A provider refused a request because its API rate limit was used up
+#[derive(Debug)]
+pub struct RateLimited {
+    pub provider: &'static str,
+    /// When the limit resets, if the provider said so
+    pub reset_at: Option<DateTime<Utc>>,
+}
+
+impl RateLimited {
+    /// Rate limit signalled by an HTTP response: a 429, or a refusal with `X-RateLimit-Remaining: 0`
+    pub fn from_response(
+        provider: &'static str,
+        status: StatusCode,
+        headers: &HeaderMap,
+        now: DateTime<Utc>,
+    ) -> Option<Self> {
+        let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok()).map(str::trim);
+
+        let exhausted = header("X-RateLimit-Remaining") == Some("0");
+        if status != StatusCode::TOO_MANY_REQUESTS && !(exhausted && status.is_client_error()) {
+            return None;
+        }
+
+        // Retry-After is relative seconds; X-RateLimit-Reset is a Unix timestamp
+        let reset_at = header("Retry-After")
+            .and_then(|seconds| seconds.parse::<i64>().ok())
+            .map(|seconds| now + chrono::Duration::seconds(seconds))
+            .or_else(|| {
+                header("X-RateLimit-Reset")
+                    .and_then(|timestamp| timestamp.parse::<i64>().ok())
+                    .and_then(|timestamp| Utc.timestamp_opt(timestamp, 0).single())
+            });
+
+        Some(RateLimited { provider, reset_at })
+    }
+
+    /// Rate limit reported by `gh` on stderr; `gh` doesn't say when it resets
+    pub fn from_gh_stderr(stderr: &str) -> Option<Self> {
+        let stderr = stderr.to_lowercase();
+        if stderr.contains("rate limit exceeded") || stderr.contains("secondary rate limit") {
+            Some(RateLimited {
+                provider: "GitHub",
+                reset_at: None,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// How long to wait before retrying, or `None` when the reset is unknown or too far off
+    pub fn backoff(&self, now: DateTime<Utc>) -> Option<Duration> {
+        let wait = (self.reset_at? - now).to_std().unwrap_or(Duration::ZERO);
+        (wait <= MAX_BACKOFF).then_some(wait)
+    }
+
+    /// Announce a short wait before the one retry
+    pub fn warn_retrying(&self, wait: Duration) {
+        ui::warn(format!(
+            "{} API rate limit reached, retrying in {}s...",
+            self.provider,
+            wait.as_secs().max(1)
+        ));
+    }
+}
+
+impl std::fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} API rate limit reached.", self.provider)?;
+        match self.reset_at {
+            Some(reset_at) => write!(
+                f,
+                " It resets at {}.",
+                reset_at.with_timezone(&Local).format("%H:%M:%S")
+            ),
+            None => write!(f, " Try again in a few minutes."),
+        }
+    }
+}
+
+impl std::error::Error for RateLimited {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_from_response_detects_rate_limits() {
+        let now = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+
+        let limited = RateLimited::from_response(
+            "Bitbucket",
+            StatusCode::TOO_MANY_REQUESTS,
+            &headers(&[("Retry-After", "30")]),
+            now,
+        )
+        .unwrap();
+        assert_eq!(limited.reset_at, Some(now + chrono::Duration::seconds(30)));
+
+        let limited = RateLimited::from_response(
+            "Bitbucket",
+            StatusCode::FORBIDDEN,
+            &headers(&[("X-RateLimit-Remaining", "0"), ("X-RateLimit-Reset", "1700000600")]),
+            now,
+        )
+        .unwrap();
+        assert_eq!(limited.reset_at, Utc.timestamp_opt(1_700_000_600, 0).single());
+
+        // A successful response that used the last request is not itself a refusal
+        assert!(RateLimited::from_response(
+            "Bitbucket",
+            StatusCode::OK,
+            &headers(&[("X-RateLimit-Remaining", "0")]),
+            now
+        )
+        .is_none());
+        assert!(RateLimited::from_response("Bitbucket", StatusCode::FORBIDDEN, &HeaderMap::new(), now).is_none());
+    }
+
+    #[test]
+    fn test_from_gh_stderr() {
+        assert!(RateLimited::from_gh_stderr("GraphQL: API rate limit exceeded for user ID 1.").is_some());
+        assert!(RateLimited::from_gh_stderr("You have exceeded a secondary rate limit.").is_some());
+        assert!(RateLimited::from_gh_stderr("GraphQL: Could not resolve to a Repository").is_none());
+    }
+
+    #[test]
+    fn test_backoff_only_for_short_waits() {
+        let now = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        let at = |seconds| RateLimited {
+            provider: "Bitbucket",
+            reset_at: Some(now + chrono::Duration::seconds(seconds)),
+        };
+
+        assert_eq!(at(5).backoff(now), Some(Duration::from_secs(5)));
+        assert_eq!(at(-5).backoff(now), Some(Duration::ZERO));
+        assert_eq!(at(120).backoff(now), None);
+        assert_eq!(
+            RateLimited::from_gh_stderr("rate limit exceeded").unwrap().backoff(now),
+            None
+        );
+    }
+}
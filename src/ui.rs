@@ -0,0 +1,68 @@
+use colored::{ColoredString, Colorize};
+use std::fmt::Display;
+use std::io::{self, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Apply the global `--quiet` and `--no-color` flags
+pub fn configure(quiet: bool, no_color: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+    if no_color {
+        colored::control::set_override(false);
+    }
+}
+
+/// Whether `--quiet` was given, for output that doesn't go through the helpers below
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// `✓ message` in green, for completed steps (hidden by `--quiet`)
+pub fn success(message: impl Display) {
+    if !is_quiet() {
+        println!("{}", format!("✓ {}", message).green());
+    }
+}
+
+/// Progress line for work that is about to happen (hidden by `--quiet`)
+pub fn step(message: impl Display) {
+    if !is_quiet() {
+        println!("{}", message.to_string().cyan());
+    }
+}
+
+/// `⚠️  message` in yellow on stderr, for problems that don't stop the command
+pub fn warn(message: impl Display) {
+    eprintln!("{}", for_stderr(format!("⚠️  {}", message).yellow()));
+}
+
+/// Print `question (y/N): ` and read the answer from stdin
+pub fn ask_yes_no(question: &str) -> io::Result<bool> {
+    print!("{}", format!("{} (y/N): ", question).cyan());
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let answer = input.trim().to_lowercase();
+    Ok(answer == "y" || answer == "yes")
+}
+
+/// `❌ message` in red on stderr, for steps that failed
+pub fn error(message: impl Display) {
+    eprintln!("{}", for_stderr(format!("❌ {}", message).red()));
+}
+
+/// The error a command failed with, on stderr with its causes, as `main` returning it would print it
+pub fn report_error(error: &anyhow::Error) {
+    eprintln!("{} {:?}", for_stderr("Error:".red().bold()), error);
+}
+
+/// `colored` only checks whether stdout is a terminal; stderr may be going to a log file instead
+fn for_stderr(text: ColoredString) -> String {
+    if io::stderr().is_terminal() {
+        text.to_string()
+    } else {
+        text.clear().to_string()
+    }
+}
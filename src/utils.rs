@@ -1,4 +1,300 @@
+use chrono::{DateTime, Duration, Local, TimeZone, Utc};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Once;
+
+use crate::ui;
+
+/// `name` in gwt's cache directory, following the XDG cache directory convention
+pub fn cache_file(name: &str) -> Option<PathBuf> {
+    let cache_dir = std::env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+
+    Some(cache_dir.join("gwt").join(name))
+}
+
+/// How long a cached lookup (latest release, current user) is trusted
+pub const CACHE_TTL_HOURS: i64 = 24;
+
+/// Whether an entry cached at `checked_at` is still within the TTL at `now`
+pub fn cache_is_fresh(checked_at: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+    now - checked_at < Duration::hours(CACHE_TTL_HOURS)
+}
+
+/// The JSON cache at `path`; `None` when it is missing or unreadable
+pub fn read_json_cache<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Store `value` as the JSON cache at `path`
+pub fn write_json_cache<T: Serialize>(path: &Path, value: &T) {
+    // The cache is an optimization only, so failing to write it is not an error
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(content) = serde_json::to_string(value) {
+        let _ = fs::write(path, content);
+    }
+}
+
 #[allow(dead_code)]
 pub fn clean_branch_name(branch: &str) -> String {
     branch.strip_prefix("refs/heads/").unwrap_or(branch).to_string()
 }
+
+/// Warn (once per run) that a provider stopped returning pull requests at the configured cap
+pub fn warn_pr_limit_reached(count: usize) {
+    static WARNED: Once = Once::new();
+    WARNED.call_once(|| {
+        ui::warn(format!(
+            "Stopped after {} pull requests; some PRs may be omitted. Raise --pr-limit (or prLimit in git-worktree-config.yaml) to fetch more.",
+            count
+        ));
+    });
+}
+
+/// Match `text` against a glob pattern where `*` matches any run of characters and `?` one character
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` in the pattern and the text index it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, star_t)) = backtrack {
+            p = star + 1;
+            t = star_t + 1;
+            backtrack = Some((star, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Shorten `text` to at most `max_chars` characters, ending with an ellipsis when cut.
+/// Counts characters rather than bytes so multibyte titles are never split mid-character.
+pub fn truncate_with_ellipsis(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+
+    let mut truncated: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// Replace the host of a git remote URL (HTTPS, `ssh://` or scp-like `user@host:path`)
+/// with its real name when it is one of `aliases`, e.g. an SSH config `Host` alias
+pub fn resolve_host_alias(url: &str, aliases: &BTreeMap<String, String>) -> String {
+    let (prefix, rest) = match url.find("://") {
+        Some(index) => url.split_at(index + 3),
+        None => ("", url),
+    };
+
+    // The host ends at the first path or port separator; scp-like URLs use ':' before the path
+    let authority = &rest[..rest.find('/').unwrap_or(rest.len())];
+    let host_start = authority.find('@').map(|index| index + 1).unwrap_or(0);
+    let host_end = rest[host_start..]
+        .find([':', '/'])
+        .map(|index| host_start + index)
+        .unwrap_or(rest.len());
+
+    match aliases.get(&rest[host_start..host_end]) {
+        Some(real_host) => format!("{}{}{}{}", prefix, &rest[..host_start], real_host, &rest[host_end..]),
+        None => url.to_string(),
+    }
+}
+
+/// `path` relative to `base`, or `path` unchanged when it lies outside `base`
+pub fn relative_to(path: &Path, base: &Path) -> String {
+    match path.strip_prefix(base) {
+        Ok(relative) if relative.as_os_str().is_empty() => ".".to_string(),
+        Ok(relative) => relative.display().to_string(),
+        Err(_) => path.display().to_string(),
+    }
+}
+
+/// Lowercase `text` into a branch-name fragment: runs of anything but ASCII letters and
+/// digits become a single `-`, cut at a word boundary once it passes `max_chars`
+pub fn slugify(text: &str, max_chars: usize) -> String {
+    let mut slug = String::new();
+    for word in text
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+    {
+        if !slug.is_empty() && slug.len() + 1 + word.len() > max_chars {
+            break;
+        }
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.push_str(&word.to_ascii_lowercase());
+    }
+    slug.truncate(max_chars);
+    slug
+}
+
+/// Parse an RFC 3339 timestamp as GitHub, Bitbucket Cloud and `git log --format=%aI` give them
+pub fn parse_rfc3339(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp.trim())
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// Convert milliseconds since the Unix epoch, as Bitbucket Data Center gives them
+pub fn from_epoch_millis(millis: u64) -> Option<DateTime<Utc>> {
+    Utc.timestamp_millis_opt(i64::try_from(millis).ok()?).single()
+}
+
+/// How long ago `time` was, e.g. "5m ago", "2h ago", "yesterday" or "3 days ago", with days
+/// counted in the local time zone. Anything older than a week shows its local date.
+pub fn format_relative_time(time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    relative_time_in(time, now, &Local)
+}
+
+fn relative_time_in<Tz: TimeZone>(time: DateTime<Utc>, now: DateTime<Utc>, tz: &Tz) -> String {
+    let elapsed = now - time;
+    // A clock slightly ahead of ours shouldn't produce "in 2m"
+    if elapsed < Duration::minutes(1) {
+        return "just now".to_string();
+    }
+    if elapsed < Duration::hours(1) {
+        return format!("{}m ago", elapsed.num_minutes());
+    }
+
+    let date = time.with_timezone(tz).date_naive();
+    let days = (now.with_timezone(tz).date_naive() - date).num_days();
+    match days {
+        0 => format!("{}h ago", elapsed.num_hours()),
+        1 => "yesterday".to_string(),
+        2..=6 => format!("{} days ago", days),
+        _ => date.format("%Y-%m-%d").to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Fix login bug", 50), "fix-login-bug");
+        assert_eq!(slugify("  [UI] Can't save: 2FA form!  ", 50), "ui-can-t-save-2fa-form");
+        assert_eq!(slugify("Crash when opening settings page", 20), "crash-when-opening");
+        assert_eq!(slugify("Supercalifragilistic", 5), "super");
+        assert_eq!(slugify("日本語", 50), "");
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("production", "production"));
+        assert!(!glob_match("production", "production-2"));
+        assert!(glob_match("release/*", "release/1.2"));
+        assert!(glob_match("release/*", "release/1.2/hotfix"));
+        assert!(!glob_match("release/*", "feature/release"));
+        assert!(glob_match("*-stable", "v2-stable"));
+        assert!(glob_match("v?", "v1"));
+        assert!(!glob_match("v?", "v10"));
+        assert!(glob_match("*", "anything"));
+    }
+
+    #[test]
+    fn test_truncate_with_ellipsis() {
+        assert_eq!(truncate_with_ellipsis("short", 10), "short");
+        assert_eq!(truncate_with_ellipsis("exactly10!", 10), "exactly10!");
+        assert_eq!(truncate_with_ellipsis("a longer title", 8), "a longe…");
+        assert_eq!(truncate_with_ellipsis("päivitä äänet", 6), "päivi…");
+        assert_eq!(truncate_with_ellipsis("🚀🚀🚀", 2), "🚀…");
+    }
+
+    #[test]
+    fn test_resolve_host_alias() {
+        let aliases = BTreeMap::from([
+            ("github-work".to_string(), "github.com".to_string()),
+            ("bb".to_string(), "bitbucket.org".to_string()),
+        ]);
+
+        assert_eq!(
+            resolve_host_alias("git@github-work:org/repo.git", &aliases),
+            "git@github.com:org/repo.git"
+        );
+        assert_eq!(
+            resolve_host_alias("ssh://git@bb:7999/ws/repo.git", &aliases),
+            "ssh://git@bitbucket.org:7999/ws/repo.git"
+        );
+        assert_eq!(
+            resolve_host_alias("https://bb/ws/repo", &aliases),
+            "https://bitbucket.org/ws/repo"
+        );
+        assert_eq!(
+            resolve_host_alias("git@github.com:org/repo.git", &aliases),
+            "git@github.com:org/repo.git"
+        );
+        // Only whole host names match
+        assert_eq!(
+            resolve_host_alias("git@github-work2:org/repo.git", &aliases),
+            "git@github-work2:org/repo.git"
+        );
+    }
+
+    #[test]
+    fn test_relative_to() {
+        let root = Path::new("/work/project");
+        assert_eq!(
+            relative_to(Path::new("/work/project/feature/login"), root),
+            "feature/login"
+        );
+        assert_eq!(relative_to(Path::new("/work/project"), root), ".");
+        assert_eq!(relative_to(Path::new("/elsewhere/hotfix"), root), "/elsewhere/hotfix");
+    }
+
+    #[test]
+    fn test_relative_time_from_rfc3339_and_epoch_millis() {
+        let now = parse_rfc3339("2024-05-10T15:00:00Z").unwrap();
+        let at = |timestamp: &str| relative_time_in(parse_rfc3339(timestamp).unwrap(), now, &Utc);
+
+        assert_eq!(at("2024-05-10T14:59:30Z"), "just now");
+        assert_eq!(at("2024-05-10T14:15:00Z"), "45m ago");
+        assert_eq!(at("2024-05-10T12:30:00.123456+00:00"), "2h ago");
+        assert_eq!(at("2024-05-09T20:00:00Z"), "yesterday");
+        assert_eq!(at("2024-05-07T09:00:00+02:00"), "3 days ago");
+        assert_eq!(at("2024-04-01T12:00:00Z"), "2024-04-01");
+        // Slightly ahead of our clock
+        assert_eq!(at("2024-05-10T15:00:05Z"), "just now");
+
+        // Data Center's epoch milliseconds name the same instants
+        let millis = parse_rfc3339("2024-05-10T13:00:00Z").unwrap().timestamp_millis() as u64;
+        assert_eq!(
+            relative_time_in(from_epoch_millis(millis).unwrap(), now, &Utc),
+            "2h ago"
+        );
+        assert_eq!(from_epoch_millis(millis), parse_rfc3339("2024-05-10T15:00:00+02:00"));
+    }
+
+    #[test]
+    fn test_relative_time_counts_days_in_the_given_time_zone() {
+        let now = parse_rfc3339("2024-05-10T01:00:00Z").unwrap();
+        let evening = parse_rfc3339("2024-05-09T22:00:00Z").unwrap();
+
+        // 22:00 UTC is the same day as 01:00 UTC in Helsinki (UTC+3), but the day before in UTC
+        let helsinki = chrono::FixedOffset::east_opt(3 * 3600).unwrap();
+        assert_eq!(relative_time_in(evening, now, &Utc), "yesterday");
+        assert_eq!(relative_time_in(evening, now, &helsinki), "3h ago");
+    }
+}
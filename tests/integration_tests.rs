@@ -145,3 +145,2240 @@ fn test_gwt_init_directory_cleanup() {
 
     cleanup_test_env(temp_dir);
 }
+
+#[test]
+fn test_gwt_list_and_remove_worktree_outside_project_root() {
+    let (temp_dir, _, project) = setup_project();
+    let temp_path = temp_dir.path();
+
+    // Create a worktree outside the project root with raw git
+    let outside = temp_path.join("outside-wt");
+    run_git(
+        &project.join("main"),
+        &["worktree", "add", outside.to_str().unwrap(), "-b", "outside"],
+    );
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).arg("list");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("outside"))
+        .stdout(predicate::str::contains("(outside project root)"));
+
+    // Removal must use the path reported by git rather than <root>/<branch>
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["remove", "outside"]).write_stdin("y\n");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("✓ Worktree removed"));
+
+    assert!(!outside.exists(), "Outside worktree should be removed");
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_add_with_path_outside_project_root() {
+    let (temp_dir, _, project) = setup_project();
+    let temp_path = temp_dir.path();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["add", "external", "--path", "../external-wt"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("✓ Worktree created at"));
+
+    let external = temp_path.join("external-wt");
+    assert!(external.join(".git").exists(), "Worktree should be created at --path");
+
+    // Commands run from inside the external worktree still find the project
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&external).arg("list");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("main"))
+        .stdout(predicate::str::contains("(outside project root)"));
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&external).arg("remove").write_stdin("y\n");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("✓ Worktree removed"));
+
+    assert!(!external.exists(), "External worktree should be removed");
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_switch_create_then_switch() {
+    let (temp_dir, _, project) = setup_project();
+
+    // Without --create a missing worktree is an error
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["switch", "feature/x"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("gwt switch --create feature/x"));
+
+    let worktree = project.join("feature/x");
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["switch", "--create", "--print-path", "feature/x"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let stdout = String::from_utf8(output).unwrap();
+    assert!(worktree.join(".git").exists(), "Worktree should be created");
+    assert!(stdout.trim_end().ends_with(worktree.to_str().unwrap()));
+
+    // Switching again finds the existing worktree without creating anything
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(project.join("main"))
+        .args(["switch", "--create", "--print-path", "feature/x"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Preparing worktree").not())
+        .stdout(predicate::str::contains(worktree.to_str().unwrap()));
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_switch_away_from_uncommitted_changes() {
+    let (temp_dir, _, project) = setup_project();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/x"]);
+    cmd.assert().success();
+
+    let main = project.join("main");
+    fs::write(main.join("README.md"), "work in progress\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&main).args(["switch", "feature/x"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("has uncommitted changes"));
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&main).args(["switch", "--safe", "feature/x"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("has uncommitted changes"));
+
+    // With stashOnSwitch the changes are put away and come back on return
+    let config_path = project.join("git-worktree-config.yaml");
+    let mut config = fs::read_to_string(&config_path).unwrap();
+    config.push_str("stashOnSwitch: true\n");
+    fs::write(&config_path, config).unwrap();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&main).args(["switch", "--safe", "feature/x"]);
+    cmd.assert().success().stdout(predicate::str::contains("Stashed"));
+    assert!(run_git(&main, &["status", "--porcelain"]).trim().is_empty());
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(project.join("feature/x")).args(["switch", "main"]);
+    cmd.assert().success().stdout(predicate::str::contains("Restored"));
+    assert_eq!(
+        fs::read_to_string(main.join("README.md")).unwrap(),
+        "work in progress\n"
+    );
+    assert!(run_git(&main, &["stash", "list"]).trim().is_empty());
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_failed_switch_does_not_stash() {
+    let (temp_dir, _, project) = setup_project();
+
+    let config_path = project.join("git-worktree-config.yaml");
+    let mut config = fs::read_to_string(&config_path).unwrap();
+    config.push_str("stashOnSwitch: true\n");
+    fs::write(&config_path, config).unwrap();
+
+    let main = project.join("main");
+    fs::write(main.join("README.md"), "work in progress\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&main).args(["switch", "typo-branch"]);
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("Stashed").not())
+        .stderr(predicate::str::contains("No worktree found"));
+
+    assert_eq!(
+        fs::read_to_string(main.join("README.md")).unwrap(),
+        "work in progress\n"
+    );
+    assert!(run_git(&main, &["stash", "list"]).trim().is_empty());
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_remove_protected_branch_requires_flag_and_typed_confirmation() {
+    let (temp_dir, _, project) = setup_project();
+
+    let config_path = project.join("git-worktree-config.yaml");
+    let mut config = fs::read_to_string(&config_path).unwrap();
+    config.push_str("protectedBranches:\n  - production\n  - release/*\n");
+    fs::write(&config_path, config).unwrap();
+
+    let worktree = project.join("release/1.0");
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "release/1.0"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["remove", "release/1.0"])
+        .write_stdin("y\n");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--force-protected"));
+    assert!(worktree.exists());
+
+    // A plain "y" is not enough; the branch name has to be typed
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["remove", "release/1.0", "--force-protected"])
+        .write_stdin("y\n");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Removal cancelled"));
+    assert!(worktree.exists());
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["remove", "release/1.0", "--force-protected"])
+        .write_stdin("release/1.0\n");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("✓ Worktree removed"));
+    assert!(!worktree.exists());
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_add_detach_and_remove() {
+    let (temp_dir, _, project) = setup_project();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(project.join("main"))
+        .args(["add", "scratch", "--detach"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("✓ Worktree created at"));
+
+    let worktree = project.join("scratch");
+    assert!(worktree.join(".git").exists(), "Detached worktree should be created");
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).arg("list");
+    cmd.assert().success().stdout(predicate::str::contains("(detached at"));
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["remove", "scratch"]).write_stdin("y\n");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("no branch to delete"))
+        .stdout(predicate::str::contains("Failed to delete branch").not());
+    assert!(!worktree.exists());
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_list_since_counts_unmerged_commits() {
+    let (temp_dir, _, project) = setup_project();
+
+    for branch in ["feature/done", "feature/wip"] {
+        let mut cmd = Command::cargo_bin("gwt").unwrap();
+        cmd.current_dir(&project).args(["add", branch]);
+        cmd.assert().success();
+    }
+
+    let wip = project.join("feature/wip");
+    fs::write(wip.join("wip.txt"), "work in progress").unwrap();
+    run_git(&wip, &["add", "wip.txt"]);
+    run_git(&wip, &["commit", "-m", "WIP"]);
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["list", "--since"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("1 commit not in origin/main"))
+        .stdout(predicate::str::contains("up to date with origin/main"));
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["list", "--since-only"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("feature/wip"))
+        .stdout(predicate::str::contains("feature/done").not());
+
+    // An unknown rev is reported rather than failing the whole listing
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["list", "--since", "origin/nope"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("origin/nope not found"));
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_add_reports_missing_base_branch() {
+    let (temp_dir, _, project) = setup_project();
+    // Without origin/HEAD there is no default branch to fall back to
+    run_git(&project.join("main"), &["remote", "set-head", "origin", "--delete"]);
+
+    let config_path = project.join("git-worktree-config.yaml");
+    let config = fs::read_to_string(&config_path).unwrap();
+    fs::write(&config_path, config.replace("mainBranch: main", "mainBranch: trunk")).unwrap();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/x"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Base branch 'origin/trunk' does not exist"))
+        .stderr(predicate::str::contains("Available branches on origin:\n  main"))
+        .stderr(predicate::str::contains("mainBranch"));
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_config_set_and_clear_hook() {
+    let (temp_dir, _, project) = setup_project();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["config", "set-hook", "postSwitch", "echo switched to ${branchName}"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Changes to"))
+        .stdout(predicate::str::contains("+   - echo switched to ${branchName}"))
+        .stdout(predicate::str::contains("postSwitch hooks:"))
+        .stdout(predicate::str::contains("- echo switched to ${branchName}"));
+
+    let config = fs::read_to_string(project.join("git-worktree-config.yaml")).unwrap();
+    assert!(config.contains("postSwitch"));
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["switch", "main"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("switched to main"));
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["config", "clear-hook", "postSwitch"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("postSwitch has no hooks"));
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["config", "set-hook", "preAdd", "echo nope"]);
+    cmd.assert().failure();
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_config_validate() {
+    let (temp_dir, _, project) = setup_project();
+
+    // A local origin path is fine for git but not for GitHub pull request lookups
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["config", "validate"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("is valid"))
+        .stderr(predicate::str::contains("line 2: repositoryUrl"));
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["config", "set-hook", "postAdd", "/no/such/setup.sh", "--yes"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["config", "validate"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "postAdd hook script '/no/such/setup.sh' does not exist",
+        ))
+        .stderr(predicate::str::contains("1 error(s)"));
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_add_detects_worktree_path_collisions() {
+    let (temp_dir, _, project) = setup_project();
+
+    // Same leaf in different namespaces lives in separate directories
+    for branch in ["hotfix/login", "bugfix/login"] {
+        let mut cmd = Command::cargo_bin("gwt").unwrap();
+        cmd.current_dir(&project).args(["add", branch]);
+        cmd.assert().success();
+        assert!(project.join(branch).join(".git").exists());
+    }
+
+    // A branch named like an existing namespace would swallow its worktrees
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "hotfix"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "would contain the existing worktree for 'hotfix/login'",
+        ))
+        .stderr(predicate::str::contains("--path"));
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "main/nested"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "would be nested inside the worktree for 'main'",
+        ))
+        .stderr(predicate::str::contains("gwt add main/nested --path"));
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_list_contains_finds_owning_worktree() {
+    let (temp_dir, _, project) = setup_project();
+    let temp_path = temp_dir.path();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/search"]);
+    cmd.assert().success();
+
+    let file = project.join("feature/search/README.md");
+    fs::write(&file, "search").unwrap();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["list", "--contains"]).arg(&file);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("feature/search"))
+        .stdout(predicate::str::contains("main").not());
+
+    // Relative paths resolve against the current directory
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(project.join("main")).args(["list", "--contains", "."]);
+    cmd.assert().success().stdout(predicate::str::contains("main"));
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["list", "--contains"]).arg(temp_path);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("is not inside any worktree"));
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_list_no_pr_only_requires_pr_information() {
+    // A local origin has no provider to ask about pull requests
+    let (temp_dir, _, project) = setup_project();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["list", "--no-pr-only"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--no-pr-only needs pull request information"));
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["list", "--no-pr-only", "--contains", "."]);
+    cmd.assert().failure();
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_list_mine_requires_pr_information() {
+    let (temp_dir, _, project) = setup_project();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["list", "--mine"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--mine needs pull request information"));
+
+    // Worktrees without a pull request are never "mine", so the two filters can't combine
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["list", "--mine", "--no-pr-only"]);
+    cmd.assert().failure();
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_path_style_relative() {
+    let (temp_dir, _, project) = setup_project();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/docs", "--relative"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("✓ Worktree created at: feature/docs\n"));
+
+    let config_path = project.join("git-worktree-config.yaml");
+    let config = fs::read_to_string(&config_path).unwrap();
+    fs::write(&config_path, format!("{}pathStyle: relative\n", config)).unwrap();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["switch", "feature/docs"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("cd feature/docs"));
+
+    // The command line wins over the config
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["switch", "feature/docs", "--absolute"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("cd feature/docs").not());
+
+    // The shell wrapper needs a path it can cd to from anywhere
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["switch", "feature/docs", "--print-path"]);
+    cmd.assert().success().stdout(predicate::str::starts_with("/"));
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_add_first_worktree_in_bare_layout() {
+    let temp_dir = setup_test_env();
+    let temp_path = temp_dir.path();
+
+    let origin = create_origin_repo(temp_path);
+    let project = temp_path.join("project");
+    // A bare clone with remote-tracking branches and no worktrees yet
+    create_bare_layout_project(&project, &origin);
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/first"]);
+    cmd.assert().success();
+    assert!(project.join("feature/first/.git").exists());
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_flags_unpushed_commits() {
+    let (temp_dir, _, project) = setup_project();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/local"]);
+    cmd.assert().success();
+
+    let local = project.join("feature/local");
+    fs::write(local.join("local.txt"), "local only").unwrap();
+    run_git(&local, &["add", "local.txt"]);
+    run_git(&local, &["commit", "-m", "Local only"]);
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).arg("list");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("⬆ 1 unpushed commit"));
+
+    // Confirming once is not enough when commits would only exist locally
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["remove", "feature/local"])
+        .write_stdin("y\nn\n");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("not pushed to any remote"))
+        .stdout(predicate::str::contains("Removal cancelled."));
+    assert!(local.exists());
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["remove", "feature/local"])
+        .write_stdin("y\ny\nn\n");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("✓ Worktree removed"));
+    assert!(!local.exists());
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_quiet_hides_progress_output() {
+    let (temp_dir, _, project) = setup_project();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["add", "feature/quiet", "--quiet", "--no-color"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Preparing worktree").not())
+        .stdout(predicate::str::contains("✓").not());
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["-q", "list"]);
+    cmd.assert().success().stdout(predicate::str::contains("feature/quiet"));
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_add_passes_base_branch_to_hooks() {
+    let (temp_dir, _, project) = setup_project();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["config", "set-hook", "postAdd", "echo based on ${baseBranch}"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/base"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("based on origin/main"));
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["add", "feature/stacked", "--from", "feature/base"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("from 'feature/base'"))
+        .stdout(predicate::str::contains("based on feature/base"));
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_init_with_custom_main_worktree_name() {
+    let temp_dir = setup_test_env();
+    let temp_path = temp_dir.path();
+
+    let origin = create_origin_repo(temp_path);
+    let project = temp_path.join("project");
+    fs::create_dir_all(&project).unwrap();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args([
+        "init",
+        origin.to_str().unwrap(),
+        "--provider",
+        "github",
+        "--main-worktree-name",
+        "root",
+    ]);
+    cmd.assert().success();
+
+    assert!(project.join("root/.git").exists());
+    assert!(!project.join("main").exists());
+    let config = fs::read_to_string(project.join("git-worktree-config.yaml")).unwrap();
+    assert!(config.contains("mainWorktreeName: root"));
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/named"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["switch", "main", "--print-path"]);
+    cmd.assert().success().stdout(predicate::str::ends_with("root\n"));
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_list_compact_prints_one_line_per_worktree() {
+    let (temp_dir, _, project) = setup_project();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/compact"]);
+    cmd.assert().success();
+
+    let worktree = project.join("feature/compact");
+    fs::write(worktree.join("compact.txt"), "compact").unwrap();
+    run_git(&worktree, &["add", "compact.txt"]);
+    run_git(&worktree, &["commit", "-m", "Compact"]);
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["list", "--compact"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("main             -\n"))
+        .stdout(predicate::str::contains("feature/compact  -"))
+        .stdout(predicate::str::contains("⬆1\n"))
+        .stdout(predicate::str::contains("unpushed commit").not());
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_add_from_issue_requires_github() {
+    let (temp_dir, _, project) = setup_project();
+
+    let config_path = project.join("git-worktree-config.yaml");
+    let config = fs::read_to_string(&config_path).unwrap();
+    fs::write(
+        &config_path,
+        config.replace("sourceControl: github", "sourceControl: bitbucket-cloud"),
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "--from-issue", "42"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--from-issue only supports GitHub projects"));
+
+    // A branch name or an issue is required, but not both
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).arg("add");
+    cmd.assert().failure();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["add", "feature/x", "--from-issue", "42"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+
+    cleanup_test_env(temp_dir);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_gwt_add_checkout_pr_fetches_the_pull_request_branch() {
+    let (temp_dir, origin, project) = setup_project();
+    let temp_path = temp_dir.path();
+    use_github_repository_url(&project);
+
+    // Pushed by someone else after the project was cloned
+    let contributor = temp_path.join("contributor");
+    run_git(
+        temp_path,
+        &["clone", origin.to_str().unwrap(), contributor.to_str().unwrap()],
+    );
+    run_git(&contributor, &["checkout", "-b", "feature/review"]);
+    fs::write(contributor.join("review.txt"), "to review\n").unwrap();
+    run_git(&contributor, &["add", "."]);
+    run_git(&contributor, &["commit", "-m", "Add review"]);
+    run_git(&contributor, &["push", "origin", "feature/review"]);
+
+    let path = install_fake_gh(
+        temp_path,
+        "case \"$3\" in 7) printf 'feature/review\\tfalse\\n' ;; *) printf 'feature/gone\\tfalse\\n' ;; esac",
+    );
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .env("PATH", &path)
+        .args(["add", "--checkout-pr", "7"]);
+    cmd.assert().success();
+    assert_eq!(
+        fs::read_to_string(project.join("feature/review/review.txt")).unwrap(),
+        "to review\n"
+    );
+
+    // A head branch that can't be fetched fails instead of starting an empty branch
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .env("PATH", &path)
+        .args(["add", "--checkout-pr", "8"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Failed to fetch 'feature/gone'"));
+    assert!(!project.join("feature/gone").exists());
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_add_checkout_pr_requires_github() {
+    let (temp_dir, _, project) = setup_project();
+
+    let config_path = project.join("git-worktree-config.yaml");
+    let config = fs::read_to_string(&config_path).unwrap();
+    fs::write(
+        &config_path,
+        config.replace("sourceControl: github", "sourceControl: bitbucket-cloud"),
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["add", "--checkout-pr", "128", "--name-from-pr"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--checkout-pr only supports GitHub projects"));
+    assert!(!project.join("pr-128").exists());
+
+    // The directory name only makes sense for a pull request checkout
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/x", "--name-from-pr"]);
+    cmd.assert().failure();
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_remove_respects_confirmations_setting() {
+    let (temp_dir, _, project) = setup_project();
+
+    for branch in ["feature/one", "feature/two", "feature/three"] {
+        let mut cmd = Command::cargo_bin("gwt").unwrap();
+        cmd.current_dir(&project).args(["add", branch]);
+        cmd.assert().success();
+    }
+
+    let config_path = project.join("git-worktree-config.yaml");
+    let config = fs::read_to_string(&config_path).unwrap();
+
+    // A clean worktree is removed without a prompt at destructive-only
+    fs::write(&config_path, format!("{}confirmations: destructive-only\n", config)).unwrap();
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["remove", "feature/one"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Are you sure").not())
+        .stdout(predicate::str::contains("Worktree removed"));
+
+    // At none, a non-terminal stdin still needs --yes
+    fs::write(&config_path, format!("{}confirmations: none\n", config)).unwrap();
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["remove", "feature/two"]);
+    cmd.assert().failure().stderr(predicate::str::contains("Pass --yes"));
+    assert!(project.join("feature/two").exists());
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["remove", "feature/two", "--yes"]);
+    cmd.assert().success();
+    assert!(!project.join("feature/two").exists());
+
+    // --yes skips the prompt at the default level too
+    fs::write(&config_path, &config).unwrap();
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["remove", "-y", "feature/three"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Are you sure").not());
+    assert!(!project.join("feature/three").exists());
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_init_with_custom_remote_name() {
+    let temp_dir = setup_test_env();
+    let temp_path = temp_dir.path();
+
+    let origin = create_origin_repo(temp_path);
+    let project = temp_path.join("project");
+    fs::create_dir_all(&project).unwrap();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args([
+        "init",
+        origin.to_str().unwrap(),
+        "--provider",
+        "github",
+        "--origin",
+        "upstream",
+    ]);
+    cmd.assert().success();
+
+    let config = fs::read_to_string(project.join("git-worktree-config.yaml")).unwrap();
+    assert!(config.contains("remoteName: upstream"));
+    assert_eq!(run_git(&project.join("main"), &["remote"]).trim(), "upstream");
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/remote"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("from 'upstream/main'"));
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_init_no_clone_rename_keeps_repo_directory() {
+    let temp_dir = setup_test_env();
+    let temp_path = temp_dir.path();
+
+    let origin = create_origin_repo(temp_path);
+    let repo_name = origin.file_stem().unwrap().to_str().unwrap().to_string();
+    let project = temp_path.join("project");
+    fs::create_dir_all(&project).unwrap();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args([
+        "init",
+        origin.to_str().unwrap(),
+        "--provider",
+        "github",
+        "--no-clone-rename",
+    ]);
+    cmd.assert().success();
+
+    assert!(project.join(&repo_name).join(".git").exists());
+    assert!(!project.join("main").exists());
+    let config = fs::read_to_string(project.join("git-worktree-config.yaml")).unwrap();
+    assert!(config.contains(&format!("mainWorktreeName: {}", repo_name)));
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/kept"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["switch", "main", "--print-path"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::ends_with(format!("{}\n", repo_name)));
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_add_tracks_branches_on_custom_remote() {
+    let temp_dir = setup_test_env();
+    let temp_path = temp_dir.path();
+
+    let origin = create_origin_repo(temp_path);
+    let project = temp_path.join("project");
+    fs::create_dir_all(&project).unwrap();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args([
+        "init",
+        origin.to_str().unwrap(),
+        "--provider",
+        "github",
+        "-o",
+        "upstream",
+    ]);
+    cmd.assert().success();
+
+    // Someone else pushes a branch
+    let main = project.join("main");
+    run_git(&main, &["push", "upstream", "main:feature/shared"]);
+    run_git(&main, &["fetch", "upstream"]);
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/shared"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("exists remotely"));
+    assert_eq!(
+        run_git(&project.join("feature/shared"), &["rev-parse", "--abbrev-ref", "@{u}"]),
+        "upstream/feature/shared"
+    );
+
+    // A missing base branch is reported against the configured remote, once there is no
+    // remote default branch to fall back to
+    run_git(&main, &["remote", "set-head", "upstream", "--delete"]);
+    let config_path = project.join("git-worktree-config.yaml");
+    let config = fs::read_to_string(&config_path).unwrap();
+    fs::write(&config_path, config.replace("mainBranch: main", "mainBranch: trunk")).unwrap();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/new"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Base branch 'upstream/trunk' does not exist"))
+        .stderr(predicate::str::contains("Available branches on upstream:"));
+
+    cleanup_test_env(temp_dir);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_gwt_list_keeps_looking_up_prs_after_one_branch_fails() {
+    let (temp_dir, _, project) = setup_project();
+    let temp_path = temp_dir.path();
+    use_github_repository_url(&project);
+
+    for branch in ["feature/a", "feature/b"] {
+        let mut cmd = Command::cargo_bin("gwt").unwrap();
+        cmd.current_dir(&project).args(["add", branch]);
+        cmd.assert().success();
+    }
+
+    // The lookup for feature/a fails; the others, and the open PR list, still answer
+    let path = install_fake_gh(
+        temp_path,
+        r#"case "$*" in
+    *"--head feature/a "*) echo 'HTTP 502: Bad Gateway' >&2; exit 1 ;;
+    *"--head feature/b "*) echo '[{"number":2,"title":"B","state":"OPEN","url":"https://github.com/owner/repo/pull/2","isDraft":false}]' ;;
+    *"--head "*) echo '[]' ;;
+    *) echo '[{"number":3,"title":"Remote","state":"OPEN","url":"https://github.com/owner/repo/pull/3","isDraft":false,"headRefName":"feature/remote"}]' ;;
+  esac"#,
+    );
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).env("PATH", path).args(["list"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("https://github.com/owner/repo/pull/2"))
+        .stdout(predicate::str::contains("https://github.com/owner/repo/pull/3"))
+        .stderr(predicate::str::contains("Could not fetch pull request information"));
+
+    cleanup_test_env(temp_dir);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_gwt_list_fetch_prs_timeout_stops_a_hanging_gh() {
+    let (temp_dir, _, project) = setup_project();
+    let temp_path = temp_dir.path();
+    use_github_repository_url(&project);
+
+    // A gh that is logged in but never answers
+    let path = install_fake_gh(temp_path, "sleep 30");
+
+    let started = std::time::Instant::now();
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .env("PATH", path)
+        .args(["list", "--fetch-prs-timeout", "1"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("main"))
+        .stderr(predicate::str::contains("PR fetch timed out"));
+    assert!(
+        started.elapsed() < std::time::Duration::from_secs(15),
+        "gwt list waited for gh: {:?}",
+        started.elapsed()
+    );
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_list_json_wraps_output_in_versioned_object() {
+    let (temp_dir, _, project) = setup_project();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/json"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["list", "--json"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+
+    let value: serde_json::Value = serde_json::from_slice(&output).expect("list --json should print only JSON");
+    assert_eq!(value["version"], 1);
+    assert_eq!(value["remotePullRequests"], serde_json::json!([]));
+
+    let worktrees = value["worktrees"].as_array().unwrap();
+    let feature = worktrees
+        .iter()
+        .find(|wt| wt["branch"] == "feature/json")
+        .expect("feature worktree should be listed");
+    assert!(feature["path"].as_str().unwrap().ends_with("feature/json"));
+    assert_eq!(feature["detached"], false);
+    assert_eq!(feature["unpushed"], 0);
+    assert_eq!(feature["pullRequest"], serde_json::Value::Null);
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_list_format_prints_one_line_per_worktree() {
+    let (temp_dir, _, project) = setup_project();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/format"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["list", "--format", "{branch}|{path}|{pr_url}|{untracked}"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let output = String::from_utf8(output).unwrap();
+
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 2, "one line per worktree and nothing else: {}", output);
+    let feature = lines
+        .iter()
+        .find(|line| line.starts_with("feature/format|"))
+        .expect("feature worktree should be listed");
+    assert!(feature.ends_with("feature/format||0"), "{}", feature);
+    assert!(
+        std::path::Path::new(feature.split('|').nth(1).unwrap()).is_absolute(),
+        "{}",
+        feature
+    );
+
+    // Paths follow --relative like the rest of the output
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["list", "--format", "{branch}|{path}", "--relative"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("feature/format|feature/format\n"));
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["list", "--format", "{branch} {pr_link}"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown placeholder '{pr_link}'"));
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_list_json_lines_prints_one_object_per_worktree() {
+    let (temp_dir, _, project) = setup_project();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/lines"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["list", "--json-lines"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let output = String::from_utf8(output).unwrap();
+
+    let lines: Vec<serde_json::Value> = output
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("every line should be a JSON object"))
+        .collect();
+    assert_eq!(lines.len(), 2, "{}", output);
+    assert!(lines.iter().all(|line| line["kind"] == "worktree"), "{}", output);
+    assert!(lines.iter().any(|line| line["branch"] == "feature/lines"), "{}", output);
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["list", "--json-lines", "--json"]);
+    cmd.assert().failure();
+
+    cleanup_test_env(temp_dir);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_gwt_list_json_lines_prints_nothing_when_a_pr_filter_fails() {
+    let (temp_dir, _, project) = setup_project();
+    let temp_path = temp_dir.path();
+    use_github_repository_url(&project);
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/lines"]);
+    cmd.assert().success();
+
+    // Logged in, but every pull request lookup fails
+    let path = install_fake_gh(temp_path, "echo 'HTTP 502: Bad Gateway' >&2; exit 1");
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .env("PATH", path)
+        .args(["list", "--json-lines", "--no-pr-only"]);
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains(
+            "Could not determine which worktrees have no pull request",
+        ));
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_list_shows_differently_named_upstream() {
+    let (temp_dir, _, project) = setup_project();
+
+    for branch in ["feature/same", "feature/renamed"] {
+        let mut cmd = Command::cargo_bin("gwt").unwrap();
+        cmd.current_dir(&project).args(["add", branch]);
+        cmd.assert().success();
+    }
+    run_git(&project.join("feature/same"), &["push", "-u", "origin", "feature/same"]);
+    run_git(
+        &project.join("feature/renamed"),
+        &["push", "-u", "origin", "feature/renamed:alice/renamed"],
+    );
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).arg("list");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("tracks origin/alice/renamed"))
+        .stdout(predicate::str::contains("tracks origin/feature/same").not());
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["list", "--json"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let value: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let upstream_of = |branch: &str| {
+        value["worktrees"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|wt| wt["branch"] == branch)
+            .unwrap()["upstream"]
+            .clone()
+    };
+    assert_eq!(upstream_of("feature/renamed"), "origin/alice/renamed");
+    assert_eq!(upstream_of("feature/same"), "origin/feature/same");
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_add_falls_back_to_remote_default_for_stale_main_branch() {
+    let (temp_dir, _, project) = setup_project();
+
+    // The repository renamed master to main after the project was set up
+    let config_path = project.join("git-worktree-config.yaml");
+    let config = fs::read_to_string(&config_path).unwrap();
+    fs::write(&config_path, config.replace("mainBranch: main", "mainBranch: master")).unwrap();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/renamed-default"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Base branch 'origin/master' does not exist; using origin's default branch 'main' instead",
+        ))
+        .stdout(predicate::str::contains("from 'origin/main'"));
+    assert!(project.join("feature/renamed-default/.git").exists());
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_without_subcommand_prints_summary() {
+    let temp_dir = setup_test_env();
+    let temp_path = temp_dir.path();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(temp_path);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Not inside a gwt project"));
+
+    let origin = create_origin_repo(temp_path);
+    let project = temp_path.join("project");
+    init_project(&project, &origin);
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/summary"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).arg("--no-color");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Main branch: main"))
+        .stdout(predicate::str::contains("Worktrees: 2"))
+        .stdout(predicate::str::contains("gwt --help"));
+
+    // defaultCommand swaps the summary for the worktree list
+    let config_path = project.join("git-worktree-config.yaml");
+    let mut config = fs::read_to_string(&config_path).unwrap();
+    config.push_str("defaultCommand: list\n");
+    fs::write(&config_path, config).unwrap();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).arg("--no-color");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Local Worktrees:"))
+        .stdout(predicate::str::contains("feature/summary"));
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_add_and_remove_orphan_branch() {
+    let (temp_dir, _, project) = setup_project();
+
+    let docs = project.join("docs");
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "--orphan", "docs"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("orphan, no commits yet"));
+    assert!(!docs.join("README.md").exists(), "An orphan branch starts empty");
+    assert_eq!(run_git(&docs, &["branch", "--show-current"]).trim(), "docs");
+
+    // No commits yet: not mistaken for a worktree whose branch was deleted
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["list", "--no-color"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("docs"))
+        .stdout(predicate::str::contains("orphaned").not());
+
+    // It has no ref yet, but it is checked out all the same
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "docs", "--path", "docs-again"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("already checked out at"))
+        .stderr(predicate::str::contains("gwt switch docs"));
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["remove", "docs", "--dry-run"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("skip branch 'docs', it has no commits yet"));
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["remove", "docs"]).write_stdin("y\n");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("had no commits, nothing to delete"));
+    assert!(!docs.exists());
+
+    // With a commit of its own, the unrelated history is deleted like any other branch
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "--orphan", "gh-pages"]);
+    cmd.assert().success();
+    let pages = project.join("gh-pages");
+    fs::write(pages.join("index.html"), "<h1>Docs</h1>\n").unwrap();
+    run_git(&pages, &["add", "index.html"]);
+    run_git(&pages, &["commit", "-m", "Publish docs"]);
+
+    // --yes alone never force-deletes: the unrelated history is kept
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["remove", "gh-pages", "--yes"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Branch 'gh-pages' was not deleted"));
+    assert!(!run_git(&project.join("main"), &["branch", "--list", "gh-pages"]).is_empty());
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "gh-pages"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["remove", "gh-pages", "--yes", "--force-branch"]);
+    cmd.assert().success();
+    assert!(run_git(&project.join("main"), &["branch", "--list", "gh-pages"])
+        .trim()
+        .is_empty());
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_add_creates_nested_parent_directories() {
+    let (temp_dir, _, project) = setup_project();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/deep/nested"]);
+    cmd.assert().success();
+    assert!(project.join("feature/deep/nested/.git").exists());
+
+    // Parents created for a worktree git then refused are cleaned up again
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["add", "broken/deep/nested", "--from", "does-not-exist"]);
+    cmd.assert().failure();
+    assert!(!project.join("broken").exists());
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_list_hides_bare_repository() {
+    let temp_dir = setup_test_env();
+    let temp_path = temp_dir.path();
+
+    let origin = create_origin_repo(temp_path);
+    let project = temp_path.join("project");
+    create_bare_layout_project(&project, &origin);
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/listed"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["list", "--no-color"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("feature/listed"))
+        .stdout(predicate::str::contains("(bare)").not())
+        .stdout(predicate::str::contains("1 worktree\n"));
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["list", "--no-color", "--include-bare", "--no-summary"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("(bare)"))
+        .stdout(predicate::str::contains("1 worktree").not());
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["list", "--json"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"bare\": true"));
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_list_all_projects() {
+    let temp_dir = setup_test_env();
+    let temp_path = temp_dir.path();
+
+    let origin = create_origin_repo(temp_path);
+    let workspace = temp_path.join("workspace");
+    init_project(&workspace.join("api"), &origin);
+    init_project(&workspace.join("web"), &origin);
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(workspace.join("web")).args(["add", "feature/web-only"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&workspace).args(["list", "--all", "--no-color"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let stdout = String::from_utf8(output).unwrap();
+
+    let api = stdout.find("== api ==").expect("api header");
+    let web = stdout.find("== web ==").expect("web header");
+    assert!(api < web);
+    assert!(stdout[web..].contains("feature/web-only"));
+    assert!(!stdout[..web].contains("feature/web-only"));
+
+    let empty = temp_path.join("empty");
+    fs::create_dir_all(&empty).unwrap();
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&empty).args(["list", "--all"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("No git-worktree-config.yaml found"));
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_add_track_remote() {
+    let (temp_dir, origin, project) = setup_project();
+    let temp_path = temp_dir.path();
+
+    // A second remote, as in a fork workflow
+    let upstream = temp_path.join("upstream.git");
+    run_git(
+        temp_path,
+        &["clone", "--bare", origin.to_str().unwrap(), upstream.to_str().unwrap()],
+    );
+    let main = project.join("main");
+    run_git(&main, &["remote", "add", "upstream", upstream.to_str().unwrap()]);
+    run_git(&main, &["push", "upstream", "main:feature/shared"]);
+    run_git(&main, &["fetch", "upstream"]);
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["add", "feature/mine", "--track-remote", "upstream/feature/shared"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("tracking 'upstream/feature/shared'"));
+    assert_eq!(
+        run_git(&project.join("feature/mine"), &["rev-parse", "--abbrev-ref", "@{u}"]),
+        "upstream/feature/shared"
+    );
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["add", "feature/other", "--track-remote", "upstream/feature/missing"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Remote branch 'upstream/feature/missing' does not exist",
+        ))
+        .stderr(predicate::str::contains("git fetch upstream"));
+    assert!(!project.join("feature/other").exists());
+
+    // Only exact names count, not patterns that happen to match a branch
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["add", "feature/other", "--track-remote", "upstream/feature/sh*"]);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Remote branch 'upstream/feature/sh*' does not exist",
+    ));
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args([
+        "add",
+        "feature/other",
+        "--track-remote",
+        "upstream/feature/shared",
+        "--from",
+        "main",
+    ]);
+    cmd.assert().failure().stderr(predicate::str::contains("--from"));
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_add_default_track_setting() {
+    let (temp_dir, _, project) = setup_project();
+
+    let upstream_of = |worktree: &str| {
+        std::process::Command::new("git")
+            .args(["rev-parse", "--abbrev-ref", "@{u}"])
+            .current_dir(project.join(worktree))
+            .output()
+            .unwrap()
+    };
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/untracked"]);
+    cmd.assert().success();
+    assert!(!upstream_of("feature/untracked").status.success());
+
+    let config_path = project.join("git-worktree-config.yaml");
+    let config = fs::read_to_string(&config_path).unwrap();
+    fs::write(&config_path, format!("{}defaultTrack: true\n", config)).unwrap();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/tracked"]);
+    cmd.assert().success();
+    assert_eq!(
+        String::from_utf8(upstream_of("feature/tracked").stdout).unwrap().trim(),
+        "origin/main"
+    );
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["add", "feature/opted-out", "--no-track"]);
+    cmd.assert().success();
+    assert!(!upstream_of("feature/opted-out").status.success());
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_add_runs_hooks_in_background() {
+    let (temp_dir, _, project) = setup_project();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args([
+        "config",
+        "set-hook",
+        "postAdd",
+        "sleep 1 && echo ${branchName} > hooked.txt",
+    ]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/bg", "--bg-hooks"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("postAdd hooks running in the background"))
+        .stdout(predicate::str::contains("gwt-postAdd.log"));
+
+    // gwt returned before the hook finished, and the hook carries on without it
+    let marker = project.join("feature/bg/hooked.txt");
+    assert!(!marker.exists());
+    let mut written = String::new();
+    for _ in 0..50 {
+        written = fs::read_to_string(&marker).unwrap_or_default();
+        if !written.is_empty() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    assert_eq!(written.trim(), "feature/bg");
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_init_mirror() {
+    let temp_dir = setup_test_env();
+    let temp_path = temp_dir.path();
+
+    let origin = create_origin_repo(temp_path);
+    let project = temp_path.join("project");
+    fs::create_dir_all(&project).unwrap();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["init", origin.to_str().unwrap(), "--provider", "github", "--mirror"]);
+    cmd.assert().success();
+
+    let mirror = project.join(format!("{}.git", origin.file_stem().unwrap().to_str().unwrap()));
+    assert_eq!(run_git(&mirror, &["config", "remote.origin.mirror"]), "true");
+    assert!(project.join("main").join(".git").is_file());
+    let config = fs::read_to_string(project.join("git-worktree-config.yaml")).unwrap();
+    assert!(config.contains("layout: mirror"));
+
+    // New branches start from the mirror's own main branch
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/mirrored"]);
+    cmd.assert().success().stdout(predicate::str::contains("from 'main'"));
+    assert!(project.join("feature/mirrored").exists());
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["list", "--no-color"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("feature/mirrored"))
+        .stdout(predicate::str::contains("unpushed").not());
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_init_and_add_with_submodules() {
+    let temp_dir = setup_test_env();
+    let temp_path = temp_dir.path();
+
+    let origin = create_origin_repo(temp_path);
+    let library = temp_path.join("library");
+    fs::create_dir_all(&library).unwrap();
+    run_git(&library, &["init", "-b", "main"]);
+    fs::write(library.join("lib.txt"), "shared\n").unwrap();
+    run_git(&library, &["add", "."]);
+    run_git(&library, &["commit", "-m", "Add library"]);
+
+    // git refuses local-path submodules unless the file protocol is allowed
+    let source = temp_path.join("source");
+    run_git(
+        &source,
+        &[
+            "-c",
+            "protocol.file.allow=always",
+            "submodule",
+            "add",
+            library.to_str().unwrap(),
+            "lib",
+        ],
+    );
+    run_git(&source, &["commit", "-m", "Add submodule"]);
+    run_git(&source, &["push", origin.to_str().unwrap(), "main"]);
+
+    // Without --submodules, gwt points out what was left empty
+    let plain = temp_path.join("plain");
+    fs::create_dir_all(&plain).unwrap();
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&plain)
+        .args(["init", origin.to_str().unwrap(), "--provider", "github"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("This repository has submodules"));
+    assert!(!plain.join("main/lib/lib.txt").exists());
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&plain).args(["add", "feature/no-lib"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("This branch has submodules"));
+
+    let allow_file_protocol = [
+        ("GIT_CONFIG_COUNT", "1"),
+        ("GIT_CONFIG_KEY_0", "protocol.file.allow"),
+        ("GIT_CONFIG_VALUE_0", "always"),
+    ];
+    let project = temp_path.join("project");
+    fs::create_dir_all(&project).unwrap();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).envs(allow_file_protocol).args([
+        "init",
+        origin.to_str().unwrap(),
+        "--provider",
+        "github",
+        "--recurse-submodules",
+    ]);
+    cmd.assert().success();
+    assert!(project.join("main/lib/lib.txt").exists());
+    let config = fs::read_to_string(project.join("git-worktree-config.yaml")).unwrap();
+    assert!(config.contains("submodules: true"));
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .envs(allow_file_protocol)
+        .args(["add", "feature/with-lib"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Initializing submodules"));
+    assert!(project.join("feature/with-lib/lib/lib.txt").exists());
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_add_resumes_unfinished_setup() {
+    let temp_dir = setup_test_env();
+    let temp_path = temp_dir.path();
+
+    // A branch whose checked-in hook overrides don't parse makes the postAdd step fail
+    let origin = create_origin_repo(temp_path);
+    let source = temp_path.join("source");
+    run_git(&source, &["switch", "-c", "feature/broken-hooks"]);
+    fs::write(source.join(".gwt.local.yaml"), "hooks: [not, a, map\n").unwrap();
+    run_git(&source, &["add", "."]);
+    run_git(&source, &["commit", "-m", "Add broken hook overrides"]);
+    run_git(&source, &["push", origin.to_str().unwrap(), "feature/broken-hooks"]);
+
+    let project = temp_path.join("project");
+    init_project(&project, &origin);
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/broken-hooks"]);
+    cmd.assert().failure();
+    let worktree = project.join("feature/broken-hooks");
+    assert!(worktree.exists(), "the worktree itself was created");
+
+    // Once fixed, the same command finishes the setup instead of reporting a duplicate
+    fs::remove_file(worktree.join(".gwt.local.yaml")).unwrap();
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/broken-hooks"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("setting it up did not finish"))
+        .stdout(predicate::str::contains("Resuming setup of 'feature/broken-hooks'"));
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/broken-hooks"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("already exists"));
+
+    cleanup_test_env(temp_dir);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_gwt_add_create_pr_waits_for_the_first_commit() {
+    let (temp_dir, origin, project) = setup_project();
+    let temp_path = temp_dir.path();
+    use_github_repository_url(&project);
+
+    let gh_log = temp_path.join("gh.log");
+    let path = install_fake_gh(
+        temp_path,
+        &format!(
+            "echo \"$*\" >> {}; echo https://github.com/owner/repo/pull/1",
+            gh_log.display()
+        ),
+    );
+
+    // A new branch has nothing to review yet, so nothing is pushed or opened
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .env("PATH", &path)
+        .args(["add", "feature/x", "--create-pr"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("No pull request opened"));
+    assert!(
+        !gh_log.exists(),
+        "gh was called: {}",
+        fs::read_to_string(&gh_log).unwrap()
+    );
+    assert!(run_git(&origin, &["branch", "--list", "feature/x"]).is_empty());
+
+    // After the first commit, the same command pushes the branch and opens the pull request
+    let worktree = project.join("feature/x");
+    fs::write(worktree.join("feature.txt"), "feature\n").unwrap();
+    run_git(&worktree, &["add", "."]);
+    run_git(&worktree, &["commit", "-m", "Add feature"]);
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .env("PATH", &path)
+        .args(["add", "feature/x", "--create-pr"]);
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Pull request created: https://github.com/owner/repo/pull/1",
+    ));
+    assert!(fs::read_to_string(&gh_log)
+        .unwrap()
+        .contains("pr create --repo owner/repo --head feature/x --base main"));
+    assert!(!run_git(&origin, &["branch", "--list", "feature/x"]).is_empty());
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_init_hook_templates() {
+    let temp_dir = setup_test_env();
+    let temp_path = temp_dir.path();
+    let origin = create_origin_repo(temp_path);
+
+    for (template, expected) in [("rust", Some("- cargo fetch")), ("none", None)] {
+        let project = temp_path.join(template);
+        fs::create_dir_all(&project).unwrap();
+
+        let mut cmd = Command::cargo_bin("gwt").unwrap();
+        cmd.current_dir(&project).args([
+            "init",
+            origin.to_str().unwrap(),
+            "--provider",
+            "github",
+            "--template",
+            template,
+        ]);
+        cmd.assert().success();
+
+        let config = fs::read_to_string(project.join("git-worktree-config.yaml")).unwrap();
+        match expected {
+            Some(hook) => assert!(config.contains(hook), "{}", config),
+            None => assert!(!config.contains("hooks:"), "{}", config),
+        }
+    }
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_init_reports_unreachable_repository() {
+    let temp_dir = setup_test_env();
+    let temp_path = temp_dir.path();
+    let missing = temp_path.join("missing.git");
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(temp_path)
+        .args(["init", missing.to_str().unwrap(), "--provider", "github"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Repository not found"))
+        .stderr(predicate::str::contains("--no-remote-check"));
+    assert!(!temp_path.join("git-worktree-config.yaml").exists());
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_list_untracked() {
+    let (temp_dir, _, project) = setup_project();
+
+    let main = project.join("main");
+    fs::write(main.join("README.md"), "modified\n").unwrap();
+
+    // Tracked modifications alone don't count
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["list", "--no-color", "--untracked"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("untracked").not());
+
+    fs::write(main.join("notes.txt"), "new\n").unwrap();
+    fs::create_dir_all(main.join("scratch")).unwrap();
+    fs::write(main.join("scratch/a.txt"), "a\n").unwrap();
+    fs::write(main.join("scratch/b.txt"), "b\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["list", "--no-color", "--untracked"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("✚ 2 untracked files"));
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["list", "--json"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"untrackedFiles\": 2"));
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_list_last_commit() {
+    let (temp_dir, _, project) = setup_project();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["list", "--no-color", "--last-commit"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("gwt-test, just now"));
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["list", "--no-color"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("gwt-test").not());
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["list", "--json"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"lastCommitAuthor\": \"gwt-test\""))
+        .stdout(predicate::str::contains("\"lastCommitDate\""));
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_add_open_pr_if_exists_never_fails_the_add() {
+    let (temp_dir, _, project) = setup_project();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/print", "--print"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--open-pr-if-exists"));
+
+    // A local origin has no provider to ask, so the lookup can only warn
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["add", "feature/review", "--open-pr-if-exists", "--print"]);
+    cmd.assert().success().stderr(predicate::str::contains(
+        "Could not look up a pull request for 'feature/review'",
+    ));
+    assert!(project.join("feature/review").exists());
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_lock_and_unlock() {
+    let (temp_dir, _, project) = setup_project();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/keep"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["lock", "feature/keep", "--reason", "on a USB drive"]);
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Locked worktree for 'feature/keep' (on a USB drive)",
+    ));
+    assert!(run_git(&project.join("main"), &["worktree", "list", "--porcelain"]).contains("locked on a USB drive"));
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["list", "--no-color"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("🔒 locked (on a USB drive)"));
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["list", "--json"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"lockReason\": \"on a USB drive\""));
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["remove", "feature/keep", "--yes"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Run 'gwt unlock feature/keep' first"));
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["lock", "feature/keep"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("already locked"));
+
+    // Without a branch, the current worktree is the target
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(project.join("feature/keep")).args(["unlock"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Unlocked worktree for 'feature/keep'"));
+    assert!(!run_git(&project.join("main"), &["worktree", "list", "--porcelain"]).contains("locked"));
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["list", "--no-color"]);
+    cmd.assert().success().stdout(predicate::str::contains("locked").not());
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_add_base_sha() {
+    let (temp_dir, _, project) = setup_project();
+
+    let main = project.join("main");
+    let initial = run_git(&main, &["rev-parse", "HEAD"]);
+    fs::write(main.join("later.txt"), "later\n").unwrap();
+    run_git(&main, &["add", "."]);
+    run_git(&main, &["commit", "-m", "Later commit"]);
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["add", "repro/initial", "--base-sha", &initial]);
+    cmd.assert().success();
+    assert_eq!(run_git(&project.join("repro/initial"), &["rev-parse", "HEAD"]), initial);
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["add", "repro-detached", "--detach", "--base-sha", &initial]);
+    cmd.assert().success();
+    assert_eq!(
+        run_git(&project.join("repro-detached"), &["rev-parse", "HEAD"]),
+        initial
+    );
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args([
+        "add",
+        "repro/missing",
+        "--base-sha",
+        "0123456789abcdef0123456789abcdef01234567",
+    ]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("does not exist in this repository"));
+    assert!(!project.join("repro/missing").exists());
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["add", "repro/other", "--base-sha", &initial, "--from", "main"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_remove_dry_run() {
+    let (temp_dir, _, project) = setup_project();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["config", "set-hook", "postRemove", "echo removed ${branchName}"]);
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/preview"]);
+    cmd.assert().success();
+    let worktree = project.join("feature/preview");
+    fs::write(worktree.join("new.txt"), "new\n").unwrap();
+    run_git(&worktree, &["add", "."]);
+    run_git(&worktree, &["commit", "-m", "Unmerged work"]);
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["remove", "feature/preview", "--dry-run", "--no-color"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Dry run, nothing was removed"))
+        .stdout(predicate::str::contains(
+            "ask before force-deleting branch 'feature/preview' (unmerged changes)",
+        ))
+        .stdout(predicate::str::contains("echo removed feature/preview"))
+        .stdout(predicate::str::contains("Are you sure").not());
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["remove", "feature/preview", "--dry-run", "--yes", "--no-color"]);
+    cmd.assert().success().stdout(predicate::str::contains(
+        "keep branch 'feature/preview' (unmerged changes",
+    ));
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args([
+        "remove",
+        "feature/preview",
+        "--dry-run",
+        "--yes",
+        "--force-branch",
+        "--no-color",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("force-delete branch 'feature/preview'"));
+
+    assert!(worktree.exists());
+    assert!(!run_git(&project.join("main"), &["branch", "--list", "feature/preview"]).is_empty());
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_list_marks_orphaned_worktrees() {
+    let (temp_dir, _, project) = setup_project();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/gone"]);
+    cmd.assert().success();
+
+    // git branch -D refuses to delete a checked-out branch, but the ref can still disappear
+    run_git(&project.join("main"), &["update-ref", "-d", "refs/heads/feature/gone"]);
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["list", "--no-color"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("feature/gone (orphaned)"))
+        .stdout(predicate::str::contains("gwt remove feature/gone"))
+        .stdout(predicate::str::contains("main (orphaned)").not());
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["remove", "feature/gone", "--yes"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Branch: feature/gone (already deleted)"));
+    assert!(!project.join("feature/gone").exists());
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_add_branch_checked_out_elsewhere() {
+    let (temp_dir, _, project) = setup_project();
+
+    // The common case: the branch was created in the main checkout and never pushed
+    let main = project.join("main");
+    run_git(&main, &["checkout", "-b", "local-only"]);
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "local-only"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("already checked out at"))
+        .stderr(predicate::str::contains("gwt add <new-branch> --from local-only"));
+    assert!(!project.join("local-only").exists());
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["add", "local-only-2", "--from", "local-only"]);
+    cmd.assert().success();
+    assert_eq!(
+        run_git(&project.join("local-only-2"), &["rev-parse", "HEAD"]),
+        run_git(&main, &["rev-parse", "HEAD"])
+    );
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_move_all() {
+    let (temp_dir, _, project) = setup_project();
+    let temp_path = temp_dir.path();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/move"]);
+    cmd.assert().success();
+
+    let occupied = temp_path.join("occupied");
+    fs::create_dir_all(&occupied).unwrap();
+    fs::write(occupied.join("file.txt"), "taken\n").unwrap();
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["move-all", occupied.to_str().unwrap()]);
+    cmd.assert().failure().stderr(predicate::str::contains("is not empty"));
+
+    let moved = temp_path.join("moved");
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["move-all", moved.to_str().unwrap()]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Moved project to"));
+    assert!(!project.exists());
+
+    // Both directions of the worktree links point at the new location
+    assert_eq!(
+        run_git(&moved.join("feature/move"), &["rev-parse", "--abbrev-ref", "HEAD"]),
+        "feature/move"
+    );
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&moved).args(["list", "--json"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(moved.join("feature/move").to_str().unwrap()));
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_remove_all_merged_respects_keep_branches() {
+    let (temp_dir, _, project) = setup_project();
+
+    let config_path = project.join("git-worktree-config.yaml");
+    let mut config = fs::read_to_string(&config_path).unwrap();
+    config.push_str("keepBranches:\n  - \"env/*\"\n");
+    fs::write(&config_path, config).unwrap();
+
+    for branch in ["done/one", "env/staging", "wip/dirty", "feature/fresh"] {
+        let mut cmd = Command::cargo_bin("gwt").unwrap();
+        cmd.current_dir(&project).args(["add", branch]);
+        cmd.assert().success();
+    }
+    fs::write(project.join("wip/dirty/notes.txt"), "draft\n").unwrap();
+
+    // done/one's work lands on main; feature/fresh never had any
+    let done = project.join("done/one");
+    fs::write(done.join("done.txt"), "done\n").unwrap();
+    run_git(&done, &["add", "."]);
+    run_git(&done, &["commit", "-m", "Finish one"]);
+    run_git(&done, &["push", "origin", "HEAD:main"]);
+    run_git(&project.join("main"), &["pull", "--ff-only"]);
+
+    // An unmerged branch is never a candidate
+    let unmerged = project.join("feature/unmerged");
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["add", "feature/unmerged"]);
+    cmd.assert().success();
+    fs::write(unmerged.join("work.txt"), "work\n").unwrap();
+    run_git(&unmerged, &["add", "."]);
+    run_git(&unmerged, &["commit", "-m", "Work in progress"]);
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project)
+        .args(["remove", "--all-merged", "--dry-run", "--no-color"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("done/one"))
+        .stdout(predicate::str::contains(
+            "env/staging (matches 'env/*' in keepBranches)",
+        ))
+        .stdout(predicate::str::contains("wip/dirty (has uncommitted changes)"))
+        .stdout(predicate::str::contains("feature/fresh (no commits yet)"))
+        .stdout(predicate::str::contains("feature/unmerged").not())
+        .stdout(predicate::str::contains("Dry run"))
+        .stdout(predicate::str::contains("- delete branch 'done/one'"))
+        .stdout(predicate::str::contains("postRemove hooks"));
+    assert!(project.join("done/one").exists());
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["remove", "--all-merged", "--yes"]);
+    cmd.assert().success();
+
+    assert!(!project.join("done/one").exists());
+    assert!(project.join("env/staging").exists());
+    assert!(project.join("wip/dirty").exists());
+    assert!(project.join("feature/fresh").exists());
+    assert!(unmerged.exists());
+    assert!(run_git(&project.join("main"), &["branch", "--list", "done/one"]).is_empty());
+
+    cleanup_test_env(temp_dir);
+}
+
+#[test]
+fn test_gwt_errors_go_to_stderr_without_color() {
+    let (temp_dir, _, project) = setup_project();
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["remove", "no-such-branch"]);
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains(
+            "Error: Worktree for 'no-such-branch' not found",
+        ))
+        .stderr(predicate::str::contains("Available worktrees:"))
+        .stderr(predicate::str::contains("\x1b[").not());
+
+    let mut cmd = Command::cargo_bin("gwt").unwrap();
+    cmd.current_dir(&project).args(["switch", "no-such-branch"]);
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains("gwt switch --create no-such-branch"));
+
+    cleanup_test_env(temp_dir);
+}
@@ -1,5 +1,5 @@
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tempfile::TempDir;
 
 /// Set up a temporary test environment
@@ -26,6 +26,131 @@ pub fn cleanup_test_env(temp_dir: TempDir) {
     drop(temp_dir);
 }
 
+/// Run a git command in `dir` and return its trimmed stdout, panicking on failure
+#[allow(dead_code)]
+pub fn run_git(dir: &Path, args: &[&str]) -> String {
+    let output = std::process::Command::new("git")
+        .args(["-c", "user.name=gwt-test", "-c", "user.email=gwt-test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .expect("Failed to execute git");
+
+    assert!(
+        output.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+/// Create a bare `origin.git` repository in `dir` with a single commit on `main`
+#[allow(dead_code)]
+pub fn create_origin_repo(dir: &Path) -> PathBuf {
+    let source = dir.join("source");
+    fs::create_dir_all(&source).expect("Failed to create source repository");
+    run_git(&source, &["init", "-b", "main"]);
+    fs::write(source.join("README.md"), "# test\n").expect("Failed to write README");
+    run_git(&source, &["add", "."]);
+    run_git(&source, &["commit", "-m", "Initial commit"]);
+
+    let origin = dir.join("origin.git");
+    run_git(
+        dir,
+        &["clone", "--bare", source.to_str().unwrap(), origin.to_str().unwrap()],
+    );
+    origin
+}
+
+/// Run `gwt init` for a local origin repository inside `project_dir`
+#[allow(dead_code)]
+pub fn init_project(project_dir: &Path, origin: &Path) {
+    fs::create_dir_all(project_dir).expect("Failed to create project directory");
+    assert_cmd::Command::cargo_bin("gwt")
+        .unwrap()
+        .current_dir(project_dir)
+        .args(["init", origin.to_str().unwrap(), "--provider", "github"])
+        .assert()
+        .success();
+}
+
+/// Set up a temporary test environment holding `origin.git` and a project initialised from it
+/// in `project`; returns the environment, the origin and the project directory
+#[allow(dead_code)]
+pub fn setup_project() -> (TempDir, PathBuf, PathBuf) {
+    let temp_dir = setup_test_env();
+    let origin = create_origin_repo(temp_dir.path());
+    let project = temp_dir.path().join("project");
+    init_project(&project, &origin);
+    (temp_dir, origin, project)
+}
+
+/// Set up `project_dir` as a bare-clone project: `repo.git` with remote-tracking branches, a
+/// config for `origin` and no worktrees yet
+#[allow(dead_code)]
+pub fn create_bare_layout_project(project_dir: &Path, origin: &Path) {
+    fs::create_dir_all(project_dir).expect("Failed to create project directory");
+
+    let bare = project_dir.join("repo.git");
+    run_git(
+        project_dir,
+        &["clone", "--bare", origin.to_str().unwrap(), bare.to_str().unwrap()],
+    );
+    run_git(
+        &bare,
+        &["config", "remote.origin.fetch", "+refs/heads/*:refs/remotes/origin/*"],
+    );
+    run_git(&bare, &["fetch", "origin"]);
+    fs::write(
+        project_dir.join("git-worktree-config.yaml"),
+        format!(
+            "repositoryUrl: {}\nmainBranch: main\ncreatedAt: 2025-06-25T17:25:28.766876Z\nsourceControl: github\n",
+            origin.display()
+        ),
+    )
+    .expect("Failed to write test config");
+}
+
+/// Point the project's config at a GitHub URL, so `gwt list` looks up pull requests with `gh`
+#[allow(dead_code)]
+pub fn use_github_repository_url(project_dir: &Path) {
+    let config_path = project_dir.join("git-worktree-config.yaml");
+    let config: String = fs::read_to_string(&config_path)
+        .expect("Failed to read test config")
+        .lines()
+        .map(|line| match line.starts_with("repositoryUrl:") {
+            true => "repositoryUrl: git@github.com:owner/repo.git\n".to_string(),
+            false => format!("{}\n", line),
+        })
+        .collect();
+    fs::write(&config_path, config).expect("Failed to write test config");
+}
+
+/// Put a `gh` in `dir/bin` that is logged in and runs `script` for every other command;
+/// returns a PATH with it first
+#[cfg(unix)]
+#[allow(dead_code)]
+pub fn install_fake_gh(dir: &Path, script: &str) -> String {
+    use std::os::unix::fs::PermissionsExt;
+
+    let bin = dir.join("bin");
+    fs::create_dir_all(&bin).expect("Failed to create bin directory");
+    let gh = bin.join("gh");
+    fs::write(
+        &gh,
+        format!(
+            "#!/bin/sh\ncase \"$1\" in\n  auth) echo fake-token ;;\n  *) {} ;;\nesac\n",
+            script
+        ),
+    )
+    .expect("Failed to write fake gh");
+    fs::set_permissions(&gh, fs::Permissions::from_mode(0o755)).expect("Failed to make fake gh executable");
+
+    format!("{}:{}", bin.display(), std::env::var("PATH").unwrap())
+}
+
 /// Create a test configuration file
 #[allow(dead_code)]
 pub fn create_test_config(dir: &std::path::Path, repo_url: &str, main_branch: &str) -> PathBuf {
@@ -59,7 +184,7 @@ pub fn get_current_branch(repo_dir: &std::path::Path) -> Result<String, Box<dyn
     use std::process::Command;
 
     let output = Command::new("git")
-        .args(&["symbolic-ref", "--short", "HEAD"])
+        .args(["symbolic-ref", "--short", "HEAD"])
         .current_dir(repo_dir)
         .output()?;
 
@@ -84,7 +209,7 @@ pub fn is_git_available() -> bool {
 #[macro_export]
 macro_rules! require_git {
     () => {
-        if !crate::test_utils::is_git_available() {
+        if !$crate::test_utils::is_git_available() {
             eprintln!("Skipping test: git not available");
             return;
         }